@@ -82,8 +82,7 @@ async fn pexec(db: &Db, file: &Path) -> Result<(), sqlx::Error> {
 	// -- Read the file.
 	let content = fs::read_to_string(file)?;
 
-	// FIXME: Make the split more sql proof.
-	let sqls: Vec<&str> = content.split(';').collect();
+	let sqls = split_sql(&content);
 
 	for sql in sqls {
 		sqlx::query(sql).execute(db).await?;
@@ -92,6 +91,43 @@ async fn pexec(db: &Db, file: &Path) -> Result<(), sqlx::Error> {
 	Ok(())
 }
 
+/// Splits the sql content on the `;` which are not in a string, a comment,
+/// or a `$$` quoted body (e.g., of a plpgsql function).
+fn split_sql(content: &str) -> Vec<&str> {
+	let bytes = content.as_bytes();
+	let mut sqls = Vec::new();
+	let mut start = 0;
+	let mut in_quote = false;
+	let mut in_dollar = false;
+	let mut in_comment = false;
+
+	let mut i = 0;
+	while i < bytes.len() {
+		let next = bytes.get(i + 1).copied();
+		match bytes[i] {
+			b'\n' if in_comment => in_comment = false,
+			_ if in_comment => (),
+			b'\'' if !in_dollar => in_quote = !in_quote,
+			b'$' if !in_quote && next == Some(b'$') => {
+				in_dollar = !in_dollar;
+				i += 1;
+			}
+			b'-' if !in_quote && !in_dollar && next == Some(b'-') => {
+				in_comment = true
+			}
+			b';' if !in_quote && !in_dollar => {
+				sqls.push(&content[start..i]);
+				start = i + 1;
+			}
+			_ => (),
+		}
+		i += 1;
+	}
+	sqls.push(&content[start..]);
+
+	sqls
+}
+
 async fn new_db_pool(db_con_url: &str) -> Result<Db, sqlx::Error> {
 	PgPoolOptions::new()
 		.max_connections(1)
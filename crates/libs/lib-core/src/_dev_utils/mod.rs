@@ -3,6 +3,7 @@
 mod dev_db;
 
 use crate::ctx::Ctx;
+use crate::model::tag::{Tag, TagBmc, TagForCreate};
 use crate::model::task::{Task, TaskBmc, TaskForCreate};
 use crate::model::{self, ModelManager};
 use tokio::sync::OnceCell;
@@ -50,6 +51,7 @@ pub async fn seed_tasks(
 			mm,
 			TaskForCreate {
 				title: title.to_string(),
+				..Default::default()
 			},
 		)
 		.await?;
@@ -60,3 +62,27 @@ pub async fn seed_tasks(
 
 	Ok(tasks)
 }
+
+pub async fn seed_tags(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	names: &[&str],
+) -> model::Result<Vec<Tag>> {
+	let mut tags = Vec::new();
+
+	for name in names {
+		let id = TagBmc::create(
+			ctx,
+			mm,
+			TagForCreate {
+				name: name.to_string(),
+			},
+		)
+		.await?;
+		let tag = TagBmc::get(ctx, mm, id).await?;
+
+		tags.push(tag);
+	}
+
+	Ok(tags)
+}
@@ -1,4 +1,4 @@
-use lib_utils::envs::get_env;
+use lib_utils::envs::{get_env, get_env_parse};
 use std::sync::OnceLock;
 
 pub fn core_config() -> &'static CoreConfig {
@@ -16,6 +16,12 @@ pub struct CoreConfig {
 	// -- Db
 	pub DB_URL: String,
 
+	// -- Blob
+	pub BLOB_STORE: String,
+
+	// -- Idempotency
+	pub IDEMPOTENCY_TTL_SEC: i64,
+
 	// -- Web
 	pub WEB_FOLDER: String,
 }
@@ -26,6 +32,12 @@ impl CoreConfig {
 			// -- Db
 			DB_URL: get_env("SERVICE_DB_URL")?,
 
+			// -- Blob
+			BLOB_STORE: get_env("SERVICE_BLOB_STORE")?,
+
+			// -- Idempotency
+			IDEMPOTENCY_TTL_SEC: get_env_parse("SERVICE_IDEMPOTENCY_TTL_SEC")?,
+
 			// -- Web
 			WEB_FOLDER: get_env("SERVICE_WEB_FOLDER")?,
 		})
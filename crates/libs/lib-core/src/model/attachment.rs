@@ -0,0 +1,380 @@
+use crate::ctx::Ctx;
+use crate::model::base::{self, DbBmc};
+use crate::model::schema::Rfc3339Schema;
+use crate::model::task::TaskBmc;
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{FilterNodes, OpValsInt64};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use tracing::debug;
+use uuid::Uuid;
+
+// region:    --- Attachment Types
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct Attachment {
+	pub id: i64,
+	pub task_id: i64,
+	pub uploader_id: i64,
+
+	pub file_name: String,
+	pub content_type: String,
+	pub size: i64,
+
+	#[serde(skip)]
+	pub blob_key: String,
+
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "Rfc3339Schema")]
+	pub ctime: OffsetDateTime,
+}
+
+/// Note: Not `Deserialize`, as the content comes from the web
+///       multipart upload (and not from the json-rpc params).
+pub struct AttachmentForCreate {
+	pub task_id: i64,
+	pub file_name: String,
+	pub content_type: String,
+	pub content: Vec<u8>,
+}
+
+#[derive(Fields)]
+struct AttachmentForInsert {
+	task_id: i64,
+	uploader_id: i64,
+	file_name: String,
+	content_type: String,
+	size: i64,
+	blob_key: String,
+}
+
+#[derive(FilterNodes, Default)]
+struct AttachmentFilter {
+	task_id: Option<OpValsInt64>,
+}
+// endregion: --- Attachment Types
+
+// region:    --- AttachmentBmc
+pub struct AttachmentBmc;
+
+impl DbBmc for AttachmentBmc {
+	const TABLE: &'static str = "attachment";
+}
+
+/// Note: The row is always created before its blob, and deleted before it,
+///       so that a blob without a row is always an orphan
+///       (see `cleanup_orphan_blobs`).
+impl AttachmentBmc {
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		attachment_c: AttachmentForCreate,
+	) -> Result<i64> {
+		// -- Check task
+		TaskBmc::get(ctx, mm, attachment_c.task_id).await?;
+
+		let blob_key = Uuid::new_v4().to_string();
+		let attachment_i = AttachmentForInsert {
+			task_id: attachment_c.task_id,
+			uploader_id: ctx.user_id(),
+			file_name: attachment_c.file_name,
+			content_type: attachment_c.content_type,
+			size: attachment_c.content.len() as i64,
+			blob_key: blob_key.clone(),
+		};
+		let id = base::create::<Self, _>(ctx, mm, attachment_i).await?;
+
+		// -- Put the content (and do not leave a row without its blob)
+		if let Err(ex) = mm.blob_store().put(&blob_key, attachment_c.content).await {
+			base::delete::<Self>(ctx, mm, id).await?;
+			return Err(ex.into());
+		}
+
+		Ok(id)
+	}
+
+	/// Returns the attachment, when its task is visible to the ctx user.
+	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Attachment> {
+		let attachment: Attachment = base::get::<Self, _>(ctx, mm, id).await?;
+
+		// -- Check task
+		TaskBmc::check_visible(ctx, mm, attachment.task_id).await?;
+
+		Ok(attachment)
+	}
+
+	/// Returns the attachment with its content.
+	pub async fn get_content(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> Result<(Attachment, Vec<u8>)> {
+		let attachment = Self::get(ctx, mm, id).await?;
+		let content = mm.blob_store().get(&attachment.blob_key).await?;
+
+		Ok((attachment, content))
+	}
+
+	pub async fn list_for_task(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		task_id: i64,
+	) -> Result<Vec<Attachment>> {
+		// -- Check task
+		TaskBmc::get(ctx, mm, task_id).await?;
+
+		let filter = AttachmentFilter {
+			task_id: Some(task_id.into()),
+		};
+
+		base::list::<Self, _, _>(ctx, mm, Some(vec![filter]), None).await
+	}
+
+	/// Note: Only the uploader or the task owner can delete an attachment.
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		let attachment = Self::get(ctx, mm, id).await?;
+
+		// -- Check uploader or task owner
+		if attachment.uploader_id != ctx.user_id() {
+			let task = TaskBmc::get(ctx, mm, attachment.task_id).await?;
+			if task.owner_id != ctx.user_id() {
+				return Err(Error::AccessDenied {
+					entity: Self::TABLE,
+					id,
+				});
+			}
+		}
+
+		base::delete::<Self>(ctx, mm, id).await?;
+
+		// Note: If this fails, the blob is an orphan, removed by the next cleanup.
+		mm.blob_store().delete(&attachment.blob_key).await?;
+
+		Ok(())
+	}
+
+	/// Deletes the blobs without an attachment row (e.g., the ones of the
+	/// attachments deleted with their task), and returns their count.
+	pub async fn cleanup_orphan_blobs(
+		_ctx: &Ctx,
+		mm: &ModelManager,
+	) -> Result<usize> {
+		let blob_keys = mm.blob_store().list_keys().await?;
+
+		let used_keys: Vec<String> = sqlx::query_scalar(
+			"SELECT blob_key FROM attachment WHERE blob_key = ANY($1)",
+		)
+		.bind(&blob_keys)
+		.fetch_all(mm.db())
+		.await?;
+
+		let mut count = 0;
+		for key in blob_keys.iter().filter(|key| !used_keys.contains(key)) {
+			mm.blob_store().delete(key).await?;
+			count += 1;
+		}
+		debug!("{:<12} - cleanup_orphan_blobs - {count} deleted", "MODEL");
+
+		Ok(count)
+	}
+}
+// endregion: --- AttachmentBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::user::{User, UserBmc};
+	use crate::model::Error;
+	use anyhow::{Context, Result};
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_get_content_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_task =
+			_dev_utils::seed_tasks(&ctx, &mm, &["test_create_get_content_ok"])
+				.await?
+				.remove(0);
+		let fx_content = b"hello attachment".to_vec();
+
+		// -- Exec
+		let attachment_c = AttachmentForCreate {
+			task_id: fx_task.id,
+			file_name: "hello.txt".to_string(),
+			content_type: "text/plain".to_string(),
+			content: fx_content.clone(),
+		};
+		let id = AttachmentBmc::create(&ctx, &mm, attachment_c).await?;
+
+		// -- Check
+		let (attachment, content) =
+			AttachmentBmc::get_content(&ctx, &mm, id).await?;
+		assert_eq!(attachment.file_name, "hello.txt");
+		assert_eq!(attachment.size, fx_content.len() as i64);
+		assert_eq!(content, fx_content);
+		let attachments =
+			AttachmentBmc::list_for_task(&ctx, &mm, fx_task.id).await?;
+		assert_eq!(attachments.len(), 1);
+
+		// -- Clean
+		AttachmentBmc::delete(&ctx, &mm, id).await?;
+		let res = mm.blob_store().get(&attachment.blob_key).await;
+		assert!(res.is_err(), "blob should be deleted");
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_cleanup_orphan_blobs_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_task =
+			_dev_utils::seed_tasks(&ctx, &mm, &["test_cleanup_orphan_blobs_ok"])
+				.await?
+				.remove(0);
+		let attachment_c = AttachmentForCreate {
+			task_id: fx_task.id,
+			file_name: "orphan.txt".to_string(),
+			content_type: "text/plain".to_string(),
+			content: b"orphan".to_vec(),
+		};
+		let id = AttachmentBmc::create(&ctx, &mm, attachment_c).await?;
+		let fx_blob_key = AttachmentBmc::get(&ctx, &mm, id).await?.blob_key;
+		// Deleting the task cascades the attachment row, leaving the blob.
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		// -- Exec
+		let count = AttachmentBmc::cleanup_orphan_blobs(&ctx, &mm).await?;
+
+		// -- Check
+		assert!(count >= 1);
+		let res = mm.blob_store().get(&fx_blob_key).await;
+		assert!(
+			matches!(res, Err(crate::model::blob::Error::BlobNotFound(_))),
+			"BlobNotFound not matching"
+		);
+		let res = AttachmentBmc::get(&ctx, &mm, id).await;
+		assert!(
+			matches!(res, Err(Error::EntityNotFound { .. })),
+			"EntityNotFound not matching"
+		);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_get_content_err_not_visible() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::new(9039)?;
+		let other_ctx = Ctx::new(9040)?;
+		let fx_task =
+			_dev_utils::seed_tasks(&ctx, &mm, &["test_get_content_err_not_visible"])
+				.await?
+				.remove(0);
+		let attachment_c = AttachmentForCreate {
+			task_id: fx_task.id,
+			file_name: "private.txt".to_string(),
+			content_type: "text/plain".to_string(),
+			content: b"private".to_vec(),
+		};
+		let id = AttachmentBmc::create(&ctx, &mm, attachment_c).await?;
+
+		// -- Exec
+		let res = AttachmentBmc::get_content(&other_ctx, &mm, id).await;
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::TaskNotVisible { id, user_id: 9040 }) if id == fx_task.id),
+			"TaskNotVisible not matching"
+		);
+
+		// -- Clean
+		AttachmentBmc::delete(&ctx, &mm, id).await?;
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_delete_err_not_uploader_nor_owner() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::new(9039)?;
+		let fx_user: User = UserBmc::first_by_username(&ctx, &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+		let fx_user_ctx = Ctx::new(fx_user.id)?;
+		let fx_task = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&["test_delete_err_not_uploader_nor_owner"],
+		)
+		.await?
+		.remove(0);
+		// Note: The assignee sees the task, but is not its owner.
+		TaskBmc::assign(&ctx, &mm, fx_task.id, fx_user.id).await?;
+		let attachment_c = AttachmentForCreate {
+			task_id: fx_task.id,
+			file_name: "owner.txt".to_string(),
+			content_type: "text/plain".to_string(),
+			content: b"owner".to_vec(),
+		};
+		let id = AttachmentBmc::create(&ctx, &mm, attachment_c).await?;
+
+		// -- Exec
+		let res_assignee = AttachmentBmc::delete(&fx_user_ctx, &mm, id).await;
+		let res_other = AttachmentBmc::delete(&Ctx::new(9040)?, &mm, id).await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res_assignee,
+				Err(Error::AccessDenied {
+					entity: "attachment",
+					..
+				})
+			),
+			"AccessDenied not matching"
+		);
+		assert!(
+			matches!(res_other, Err(Error::TaskNotVisible { .. })),
+			"TaskNotVisible not matching"
+		);
+		// The attachment is still there, and its uploader can delete it.
+		AttachmentBmc::get_content(&fx_user_ctx, &mm, id).await?;
+		AttachmentBmc::delete(&ctx, &mm, id).await?;
+
+		// -- Exec & Check (the task owner deletes an attachment of the assignee)
+		let attachment_c = AttachmentForCreate {
+			task_id: fx_task.id,
+			file_name: "assignee.txt".to_string(),
+			content_type: "text/plain".to_string(),
+			content: b"assignee".to_vec(),
+		};
+		let id = AttachmentBmc::create(&fx_user_ctx, &mm, attachment_c).await?;
+		AttachmentBmc::delete(&ctx, &mm, id).await?;
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,219 @@
+use crate::ctx::Ctx;
+use crate::model::base::{self, time_to_sea_value, DbBmc};
+use crate::model::schema::{
+	OpValsInt64Schema, OpValsStringSchema, OpValsTimeSchema, Rfc3339Schema,
+};
+use crate::model::user::UserBmc;
+use crate::model::ModelManager;
+use crate::model::Result;
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{
+	FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::serde_as;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+// region:    --- AuditEvent Types
+
+/// An immutable record of a model write, with the `{field: {old, new}}` diff
+/// of the changed fields.
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct AuditEvent {
+	pub id: i64,
+	pub actor_id: i64,
+
+	pub entity: String,
+	pub entity_id: i64,
+	pub op: AuditOp,
+	pub diff: Value,
+
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "Rfc3339Schema")]
+	pub ctime: OffsetDateTime,
+}
+
+#[derive(
+	Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize, JsonSchema,
+)]
+#[sqlx(type_name = "audit_op", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOp {
+	Create,
+	Update,
+	Delete,
+}
+
+impl AuditOp {
+	pub(in crate::model) fn as_str(&self) -> &'static str {
+		match self {
+			AuditOp::Create => "create",
+			AuditOp::Update => "update",
+			AuditOp::Delete => "delete",
+		}
+	}
+}
+
+impl From<AuditOp> for sea_query::Value {
+	fn from(val: AuditOp) -> Self {
+		val.as_str().into()
+	}
+}
+
+/// e.g., `{"entity": "task", "entity_id": 1000, "ctime": {"$gte": "2024-01-01T00:00:00Z"}}`
+#[derive(FilterNodes, Deserialize, Default, Debug, JsonSchema)]
+pub struct AuditEventFilter {
+	#[schemars(with = "Option<OpValsInt64Schema>")]
+	actor_id: Option<OpValsInt64>,
+	#[schemars(with = "Option<OpValsStringSchema>")]
+	entity: Option<OpValsString>,
+	#[schemars(with = "Option<OpValsInt64Schema>")]
+	entity_id: Option<OpValsInt64>,
+	#[modql(cast_as = "audit_op")]
+	#[schemars(with = "Option<OpValsStringSchema>")]
+	op: Option<OpValsString>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[schemars(with = "Option<OpValsTimeSchema>")]
+	ctime: Option<OpValsValue>,
+}
+
+// endregion: --- AuditEvent Types
+
+// region:    --- AuditBmc
+pub struct AuditBmc;
+
+impl DbBmc for AuditBmc {
+	const TABLE: &'static str = "audit_event";
+}
+
+/// Note: Read only, as the audit events are written by the `base` writes
+///       (see `base::WriteTx`).
+impl AuditBmc {
+	/// Lists the audit events, for the admins only.
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filters: Option<Vec<AuditEventFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<AuditEvent>> {
+		UserBmc::check_admin(ctx, mm).await?;
+
+		base::list::<Self, _, _>(ctx, mm, filters, list_options).await
+	}
+}
+// endregion: --- AuditBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::task::{TaskBmc, TaskForUpdate};
+	use crate::model::user::UserForAdmin;
+	use crate::model::Error;
+	use anyhow::{Context, Result};
+	use serde_json::json;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_create_update_delete_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, &["test_audit 01"])
+			.await?
+			.remove(0);
+
+		// -- Exec
+		let task_u = TaskForUpdate {
+			title: Some("test_audit 01 - new".to_string()),
+			..Default::default()
+		};
+		TaskBmc::update(&ctx, &mm, fx_task.id, task_u).await?;
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		// -- Check
+		let filter: AuditEventFilter = serde_json::from_value(json!({
+			"entity": "task",
+			"entity_id": fx_task.id,
+		}))?;
+		let events = AuditBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		let ops: Vec<AuditOp> = events.iter().map(|e| e.op).collect();
+		assert_eq!(ops, &[AuditOp::Create, AuditOp::Update, AuditOp::Delete]);
+		assert!(events.iter().all(|e| e.actor_id == ctx.user_id()));
+		assert_eq!(events[0].diff["title"], json!({"new": "test_audit 01"}));
+		assert_eq!(
+			events[1].diff,
+			json!({"title": {"old": "test_audit 01", "new": "test_audit 01 - new"}})
+		);
+		assert_eq!(events[2].diff["id"], json!({"old": fx_task.id}));
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_audit_err_bypass_and_mutation() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, &["test_audit_err_bypass"])
+			.await?
+			.remove(0);
+
+		// -- Exec
+		let res_write =
+			sqlx::query("UPDATE task SET title = 'unaudited' WHERE id = $1")
+				.bind(fx_task.id)
+				.execute(mm.db())
+				.await;
+		let res_delete = sqlx::query("DELETE FROM audit_event WHERE entity_id = $1")
+			.bind(fx_task.id)
+			.execute(mm.db())
+			.await;
+
+		// -- Check
+		assert!(res_write.is_err(), "unaudited write should fail");
+		assert!(res_delete.is_err(), "audit_event delete should fail");
+		let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+		assert_eq!(task.title, "test_audit_err_bypass");
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_err_not_admin() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let root_ctx = Ctx::root_ctx();
+		let demo1: UserForAdmin =
+			UserBmc::first_by_username(&root_ctx, &mm, "demo1")
+				.await?
+				.context("Should have user 'demo1'")?;
+
+		// -- Exec
+		let res_admin = AuditBmc::list(&Ctx::new(demo1.id)?, &mm, None, None).await;
+		let res_other = AuditBmc::list(&Ctx::new(9036)?, &mm, None, None).await;
+
+		// -- Check
+		assert!(demo1.admin, "demo1 should be admin");
+		assert!(res_admin.is_ok(), "admin should list");
+		assert!(
+			matches!(res_other, Err(Error::AdminRequired { user_id: 9036 })),
+			"AdminRequired not matching"
+		);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
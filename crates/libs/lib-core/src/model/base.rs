@@ -1,18 +1,30 @@
 use crate::ctx::Ctx;
+use crate::model::audit::AuditOp;
+use crate::model::notify::TASK_CHANGES_CHANNEL;
+use crate::model::outbox::DomainEvent;
 use crate::model::ModelManager;
 use crate::model::{Error, Result};
-use modql::field::HasFields;
-use modql::filter::{FilterGroups, ListOptions};
+use lib_utils::time::parse_utc;
+use modql::field::{Field, Fields, HasFields};
+use modql::filter::{FilterGroups, IntoSeaError, ListOptions, SeaResult};
 use modql::SIden;
 use sea_query::{
-	Condition, Expr, Iden, IntoIden, PostgresQueryBuilder, Query, TableRef,
+	Condition, DynIden, Expr, Func, Iden, IntoIden, LockType, OnConflict, Order,
+	PostgresQueryBuilder, Query, ReturningClause, SelectStatement, SimpleExpr,
+	TableRef,
 };
 use sea_query_binder::SqlxBinder;
+use serde_json::{json, Map, Value};
 use sqlx::postgres::PgRow;
-use sqlx::FromRow;
+use sqlx::{FromRow, Postgres, Transaction};
+use std::collections::HashMap;
 
 const LIST_LIMIT_DEFAULT: i64 = 300;
 const LIST_LIMIT_MAX: i64 = 1000;
+const BULK_SIZE_MAX: usize = 1000;
+
+/// The value of the `DbBmc::AUDIT_REDACTED` fields in the audit diffs.
+const AUDIT_REDACTED_VALUE: &str = "[redacted]";
 
 #[derive(Iden)]
 pub enum CommonIden {
@@ -21,12 +33,90 @@ pub enum CommonIden {
 
 pub trait DbBmc {
 	const TABLE: &'static str;
+	/// The columns with secret values (e.g., `pwd`), redacted in the audit diffs.
+	const AUDIT_REDACTED: &'static [&'static str] = &[];
+	/// When true, the full row state after each create and update is written
+	/// as a new revision in the `{TABLE}_revision` table (e.g., `task_revision`).
+	const REVISIONED: bool = false;
+
+	/// The domain event of a write (from its audit diff), appended to the
+	/// `outbox` in the same transaction (see `model::outbox`).
+	fn domain_event(
+		_op: AuditOp,
+		_entity_id: i64,
+		_diff: &Value,
+	) -> Option<DomainEvent> {
+		None
+	}
 
 	fn table_ref() -> TableRef {
 		TableRef::Table(SIden(Self::TABLE).into_iden())
 	}
 }
 
+/// The rows targeted by the `update_many` and `delete_many` bulk functions.
+pub enum BulkTarget<F> {
+	/// All the ids must exist, otherwise, the whole operation fails.
+	Ids(Vec<i64>),
+	/// All the rows matching the filters.
+	/// (Empty filters are rejected, as they would match the whole table)
+	Filters(F),
+}
+
+impl<F> BulkTarget<F>
+where
+	F: Into<FilterGroups>,
+{
+	/// Returns the where condition, and for `Ids`, the ids that must all be affected.
+	fn into_cond(self) -> Result<(Condition, Option<Vec<i64>>)> {
+		match self {
+			BulkTarget::Ids(ids) => {
+				check_bulk_size(ids.len())?;
+				let cond = Condition::all()
+					.add(Expr::col(CommonIden::Id).is_in(ids.clone()));
+				Ok((cond, Some(ids)))
+			}
+			BulkTarget::Filters(filters) => {
+				let filters: FilterGroups = filters.into();
+				let groups = filters.groups();
+				if groups.is_empty() || groups.iter().any(|g| g.nodes().is_empty()) {
+					return Err(Error::BulkFiltersEmpty);
+				}
+				Ok((filters.try_into()?, None))
+			}
+		}
+	}
+}
+
+fn check_bulk_size(size: usize) -> Result<()> {
+	if size > BULK_SIZE_MAX {
+		Err(Error::BulkSizeOverMax {
+			max: BULK_SIZE_MAX,
+			actual: size,
+		})
+	} else {
+		Ok(())
+	}
+}
+
+/// Returns the first expected id not in the affected ids.
+fn first_id_missing(expected: &[i64], affected: &[i64]) -> Option<i64> {
+	expected.iter().find(|id| !affected.contains(id)).copied()
+}
+
+/// The modql `to_sea_value_fn` of the RFC3339 timestamp filters.
+pub fn time_to_sea_value(json_value: Value) -> SeaResult<sea_query::Value> {
+	let Value::String(moment) = json_value else {
+		return Err(IntoSeaError::custom("value must be a RFC3339 string"));
+	};
+	let time =
+		parse_utc(&moment).map_err(|ex| IntoSeaError::custom(ex.to_string()))?;
+
+	Ok(time.into())
+}
+
+/// Returns the list options with their defaults, the default limit,
+/// and the `id` order when no order is given (so that the pages are stable).
 pub fn finalize_list_options(
 	list_options: Option<ListOptions>,
 ) -> Result<ListOptions> {
@@ -45,6 +135,10 @@ pub fn finalize_list_options(
 		else {
 			list_options.limit = Some(LIST_LIMIT_DEFAULT);
 		}
+		// Set the default order if no order
+		if list_options.order_bys.is_none() {
+			list_options.order_bys = Some("id".into());
+		}
 		Ok(list_options)
 	}
 	// When None, return default
@@ -57,32 +151,36 @@ pub fn finalize_list_options(
 	}
 }
 
-pub async fn create<MC, E>(_ctx: &Ctx, mm: &ModelManager, data: E) -> Result<i64>
+pub async fn create<MC, E>(ctx: &Ctx, mm: &ModelManager, data: E) -> Result<i64>
 where
 	MC: DbBmc,
 	E: HasFields,
 {
-	let db = mm.db();
+	let mut wtx = begin(ctx, mm).await?;
+	let id = wtx.create::<MC>(data.not_none_fields()).await?;
+	wtx.commit().await?;
 
-	// -- Prep data
-	let fields = data.not_none_fields();
-	let (columns, sea_values) = fields.for_sea_insert();
+	Ok(id)
+}
 
-	// -- Build query
-	let mut query = Query::insert();
-	query
-		.into_table(MC::table_ref())
-		.columns(columns)
-		.values(sea_values)?
-		.returning(Query::returning().columns([CommonIden::Id]));
+/// Creates all of the entities with a single multi-row insert
+/// (all or nothing), and returns their ids in the same order.
+pub async fn create_many<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	data: Vec<E>,
+) -> Result<Vec<i64>>
+where
+	MC: DbBmc,
+	E: HasFields,
+{
+	let rows = data.into_iter().map(|e| e.not_none_fields()).collect();
 
-	// -- Exec query
-	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-	let (id,) = sqlx::query_as_with::<_, (i64,), _>(&sql, values)
-		.fetch_one(db)
-		.await?;
+	let mut wtx = begin(ctx, mm).await?;
+	let ids = wtx.create_many::<MC>(rows).await?;
+	wtx.commit().await?;
 
-	Ok(id)
+	Ok(ids)
 }
 
 pub async fn get<MC, E>(_ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<E>
@@ -151,73 +249,1047 @@ where
 	Ok(entities)
 }
 
-pub async fn update<MC, E>(
+pub async fn list_by_ids<MC, E>(
 	_ctx: &Ctx,
 	mm: &ModelManager,
-	id: i64,
-	data: E,
-) -> Result<()>
+	ids: &[i64],
+) -> Result<Vec<E>>
 where
 	MC: DbBmc,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
 	E: HasFields,
 {
 	let db = mm.db();
 
-	// -- Prep data
-	let fields = data.not_none_fields();
-	let fields = fields.for_sea_update();
+	// -- Build query
+	let mut query = Query::select();
+	query
+		.from(MC::table_ref())
+		.columns(E::field_column_refs())
+		.and_where(Expr::col(CommonIden::Id).is_in(ids.iter().copied()))
+		.order_by(CommonIden::Id, sea_query::Order::Asc);
+
+	// -- Exec query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let entities = sqlx::query_as_with::<_, E, _>(&sql, values)
+		.fetch_all(db)
+		.await?;
+
+	Ok(entities)
+}
+
+/// Returns the entity with only the `fields` (e.g., `["id", "title"]`), as a json
+/// object built by postgres (i.e., with its json representation of the values).
+pub async fn get_projected<MC, E>(
+	_ctx: &Ctx,
+	mm: &ModelManager,
+	id: i64,
+	fields: &[String],
+) -> Result<Value>
+where
+	MC: DbBmc,
+	E: HasFields,
+{
+	let db = mm.db();
 
 	// -- Build query
-	let mut query = Query::update();
+	let mut query = Query::select();
 	query
-		.table(MC::table_ref())
-		.values(fields)
+		.from(MC::table_ref())
+		.expr(projection_expr::<E>(fields)?)
 		.and_where(Expr::col(CommonIden::Id).eq(id));
 
 	// -- Exec query
 	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-	let count = sqlx::query_with(&sql, values)
-		.execute(db)
+	let (entity,) = sqlx::query_as_with::<_, (Value,), _>(&sql, values)
+		.fetch_optional(db)
 		.await?
-		.rows_affected();
-
-	// -- Check result
-	if count == 0 {
-		Err(Error::EntityNotFound {
+		.ok_or(Error::EntityNotFound {
 			entity: MC::TABLE,
 			id,
-		})
-	} else {
-		Ok(())
+		})?;
+
+	Ok(entity)
+}
+
+/// Same as `list`, but with only the `fields` of each entity (see `get_projected`).
+pub async fn list_projected<MC, E, F>(
+	_ctx: &Ctx,
+	mm: &ModelManager,
+	filters: Option<F>,
+	list_options: Option<ListOptions>,
+	fields: &[String],
+) -> Result<Vec<Value>>
+where
+	MC: DbBmc,
+	F: Into<FilterGroups>,
+	E: HasFields,
+{
+	let db = mm.db();
+
+	// -- Build query
+	let mut query = Query::select();
+	query
+		.from(MC::table_ref())
+		.expr(projection_expr::<E>(fields)?);
+
+	// condition from filter
+	if let Some(filters) = filters {
+		let filters: FilterGroups = filters.into();
+		let cond: Condition = filters.try_into()?;
+		query.cond_where(cond);
 	}
+
+	// list options
+	let list_options = finalize_list_options(list_options)?;
+	list_options.apply_to_sea_query(&mut query);
+
+	// -- Exec query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let entities = sqlx::query_as_with::<_, (Value,), _>(&sql, values)
+		.fetch_all(db)
+		.await?;
+
+	Ok(entities.into_iter().map(|(entity,)| entity).collect())
+}
+
+/// Returns the `jsonb_build_object(name, column, ...)` expression of the `fields`,
+/// which must all be fields of `E`.
+fn projection_expr<E>(fields: &[String]) -> Result<SimpleExpr>
+where
+	E: HasFields,
+{
+	if fields.is_empty() {
+		return Err(Error::ProjectionFieldsEmpty);
+	}
+	let names = E::field_names();
+	if let Some(field) = fields.iter().find(|f| !names.contains(&f.as_str())) {
+		return Err(Error::ProjectionFieldUnknown {
+			field: field.to_string(),
+		});
+	}
+
+	let mut args: Vec<SimpleExpr> = Vec::with_capacity(fields.len() * 2);
+	for (name, column_ref) in names.iter().zip(E::field_column_refs()) {
+		if fields.iter().any(|f| f == name) {
+			args.push(Expr::val(*name).into());
+			args.push(Expr::col(column_ref).into());
+		}
+	}
+
+	Ok(Func::cust(SIden("jsonb_build_object")).args(args).into())
+}
+
+pub async fn update<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	id: i64,
+	data: E,
+) -> Result<()>
+where
+	MC: DbBmc,
+	E: HasFields,
+{
+	update_fields::<MC>(ctx, mm, id, data.not_none_fields()).await
+}
+
+/// Updates the row with the fields as given
+/// (e.g., with a null value, which the `update` skips).
+pub async fn update_fields<MC>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	id: i64,
+	fields: Fields,
+) -> Result<()>
+where
+	MC: DbBmc,
+{
+	let mut wtx = begin(ctx, mm).await?;
+	wtx.update::<MC>(id, fields).await?;
+	wtx.commit().await
 }
 
-pub async fn delete<MC>(_ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()>
+pub async fn delete<MC>(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()>
 where
 	MC: DbBmc,
+{
+	let mut wtx = begin(ctx, mm).await?;
+	wtx.delete::<MC>(id).await?;
+	wtx.commit().await
+}
+
+/// Returns the ids of the targeted rows (e.g., to check them before a bulk update).
+pub async fn list_ids_by_target<MC, F>(
+	_ctx: &Ctx,
+	mm: &ModelManager,
+	target: BulkTarget<F>,
+) -> Result<Vec<i64>>
+where
+	MC: DbBmc,
+	F: Into<FilterGroups>,
 {
 	let db = mm.db();
 
 	// -- Build query
-	let mut query = Query::delete();
+	let (cond, expected_ids) = target.into_cond()?;
+	let mut query = Query::select();
 	query
-		.from_table(MC::table_ref())
-		.and_where(Expr::col(CommonIden::Id).eq(id));
+		.from(MC::table_ref())
+		.column(CommonIden::Id)
+		.cond_where(cond)
+		.order_by(CommonIden::Id, sea_query::Order::Asc);
 
 	// -- Exec query
 	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-	let count = sqlx::query_with(&sql, values)
-		.execute(db)
+	let ids: Vec<i64> = sqlx::query_as_with::<_, (i64,), _>(&sql, values)
+		.fetch_all(db)
 		.await?
-		.rows_affected();
+		.into_iter()
+		.map(|(id,)| id)
+		.collect();
 
 	// -- Check result
-	if count == 0 {
-		Err(Error::EntityNotFound {
+	if let Some(id) = expected_ids.and_then(|e| first_id_missing(&e, &ids)) {
+		return Err(Error::EntityNotFound {
 			entity: MC::TABLE,
 			id,
-		})
-	} else {
+		});
+	}
+
+	Ok(ids)
+}
+
+/// Updates all the targeted rows with the same data (all or nothing),
+/// and returns the updated ids.
+pub async fn update_many<MC, E, F>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	target: BulkTarget<F>,
+	data: E,
+) -> Result<Vec<i64>>
+where
+	MC: DbBmc,
+	E: HasFields,
+	F: Into<FilterGroups>,
+{
+	let mut wtx = begin(ctx, mm).await?;
+	let ids = wtx
+		.update_many::<MC, _>(target, data.not_none_fields())
+		.await?;
+	wtx.commit().await?;
+
+	Ok(ids)
+}
+
+/// Deletes all the targeted rows (all or nothing), and returns the deleted ids.
+pub async fn delete_many<MC, F>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	target: BulkTarget<F>,
+) -> Result<Vec<i64>>
+where
+	MC: DbBmc,
+	F: Into<FilterGroups>,
+{
+	let mut wtx = begin(ctx, mm).await?;
+	let ids = wtx.delete_many::<MC, _>(target).await?;
+	wtx.commit().await?;
+
+	Ok(ids)
+}
+
+/// Inserts the relation row (no-op if already there), and returns true when inserted.
+/// (see `WriteTx::link`)
+pub async fn link<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	entity_id: i64,
+	data: E,
+) -> Result<bool>
+where
+	MC: DbBmc,
+	E: HasFields,
+{
+	let mut wtx = begin(ctx, mm).await?;
+	let linked = wtx.link::<MC>(entity_id, data.all_fields()).await?;
+	wtx.commit().await?;
+
+	Ok(linked)
+}
+
+/// Deletes the relation row (no-op if not there), and returns true when deleted.
+/// (see `WriteTx::unlink`)
+pub async fn unlink<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	entity_id: i64,
+	data: E,
+) -> Result<bool>
+where
+	MC: DbBmc,
+	E: HasFields,
+{
+	let mut wtx = begin(ctx, mm).await?;
+	let unlinked = wtx.unlink::<MC>(entity_id, data.all_fields()).await?;
+	wtx.commit().await?;
+
+	Ok(unlinked)
+}
+
+// region:    --- WriteTx
+
+/// The audited write transaction, which is the only way for the Bmcs to write
+/// the db (the write functions above all go through it).
+///
+/// Each write inserts its `audit_event` rows (and revisions, see `DbBmc::REVISIONED`)
+/// in the same transaction, with the ctx user as actor. The db rejects the writes made outside of a `WriteTx`
+/// (see `sql/dev_initial/03-audit-guard.sql`).
+pub struct WriteTx {
+	actor_id: i64,
+	tx: Transaction<'static, Postgres>,
+}
+
+/// Begins an audited write transaction for the ctx user.
+pub async fn begin(ctx: &Ctx, mm: &ModelManager) -> Result<WriteTx> {
+	let mut tx = mm.db().begin().await?;
+	sqlx::query("SELECT set_config('app.audited', 'on', true)")
+		.execute(&mut *tx)
+		.await?;
+
+	Ok(WriteTx {
+		actor_id: ctx.user_id(),
+		tx,
+	})
+}
+
+impl WriteTx {
+	/// Commits the writes with their audit events.
+	/// (dropping the `WriteTx` rolls them back)
+	pub async fn commit(self) -> Result<()> {
+		self.tx.commit().await?;
+
+		Ok(())
+	}
+
+	/// Returns the rows of the select, read in the transaction (e.g., after its
+	/// writes, or under its locks).
+	///
+	/// Note: Only selects, so that the writes go through the audited `WriteTx`
+	///       functions (there is no access to the transaction connection).
+	pub async fn fetch_all<T>(&mut self, query: &SelectStatement) -> Result<Vec<T>>
+	where
+		T: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	{
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let rows = sqlx::query_as_with::<_, T, _>(&sql, values)
+			.fetch_all(&mut *self.tx)
+			.await?;
+
+		Ok(rows)
+	}
+
+	/// Same as `fetch_all`, for a single row (none is an `Sqlx` error).
+	pub async fn fetch_one<T>(&mut self, query: &SelectStatement) -> Result<T>
+	where
+		T: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	{
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let row = sqlx::query_as_with::<_, T, _>(&sql, values)
+			.fetch_one(&mut *self.tx)
+			.await?;
+
+		Ok(row)
+	}
+
+	/// Same as `fetch_all`, for an optional row.
+	pub async fn fetch_optional<T>(
+		&mut self,
+		query: &SelectStatement,
+	) -> Result<Option<T>>
+	where
+		T: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	{
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let row = sqlx::query_as_with::<_, T, _>(&sql, values)
+			.fetch_optional(&mut *self.tx)
+			.await?;
+
+		Ok(row)
+	}
+
+	/// Returns the entity, locked (`FOR UPDATE`) until the end of the transaction
+	/// (e.g., to check it before its update).
+	pub async fn get_for_update<MC, E>(&mut self, id: i64) -> Result<E>
+	where
+		MC: DbBmc,
+		E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+		E: HasFields,
+	{
+		let target = BulkTarget::<FilterGroups>::Ids(vec![id]);
+
+		Ok(self.list_for_update::<MC, E, _>(target).await?.remove(0))
+	}
+
+	/// Returns the targeted entities (ordered by id), locked (`FOR UPDATE`)
+	/// until the end of the transaction.
+	pub async fn list_for_update<MC, E, F>(
+		&mut self,
+		target: BulkTarget<F>,
+	) -> Result<Vec<E>>
+	where
+		MC: DbBmc,
+		E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+		E: HasFields,
+		F: Into<FilterGroups>,
+	{
+		// -- Lock the rows
+		let (cond, expected_ids) = target.into_cond()?;
+		let mut query = Query::select();
+		query
+			.from(MC::table_ref())
+			.column(CommonIden::Id)
+			.cond_where(cond)
+			.order_by(CommonIden::Id, Order::Asc)
+			.lock(LockType::Update);
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let ids: Vec<i64> = sqlx::query_as_with::<_, (i64,), _>(&sql, values)
+			.fetch_all(&mut *self.tx)
+			.await?
+			.into_iter()
+			.map(|(id,)| id)
+			.collect();
+
+		// -- Check result
+		if let Some(id) = expected_ids.and_then(|e| first_id_missing(&e, &ids)) {
+			return Err(Error::EntityNotFound {
+				entity: MC::TABLE,
+				id,
+			});
+		}
+
+		// -- Select the locked rows
+		let mut query = Query::select();
+		query
+			.from(MC::table_ref())
+			.columns(E::field_column_refs())
+			.and_where(Expr::col(CommonIden::Id).is_in(ids))
+			.order_by(CommonIden::Id, Order::Asc);
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let entities = sqlx::query_as_with::<_, E, _>(&sql, values)
+			.fetch_all(&mut *self.tx)
+			.await?;
+
+		Ok(entities)
+	}
+
+	pub async fn create<MC>(&mut self, fields: Fields) -> Result<i64>
+	where
+		MC: DbBmc,
+	{
+		// -- Prep data
+		let fields = fields.into_vec();
+		let names = field_names(&fields);
+		let (columns, sea_values) = Fields::new(fields).for_sea_insert();
+
+		// -- Build query
+		let mut query = Query::insert();
+		query
+			.into_table(MC::table_ref())
+			.columns(columns)
+			.values(sea_values)?
+			.returning(returning_id_row::<MC>());
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let (id, new) = sqlx::query_as_with::<_, (i64, Value), _>(&sql, values)
+			.fetch_one(&mut *self.tx)
+			.await?;
+
+		// -- Audit
+		self.revise::<MC>(&[(id, new.clone())]).await?;
+		let diff = audit_diff::<MC>(Some(&names), None, Some(&new));
+		self.audit::<MC>(AuditOp::Create, vec![(id, diff)]).await?;
+
+		Ok(id)
+	}
+
+	/// Creates all the rows with a single multi-row insert,
+	/// and returns their ids in the same order.
+	pub async fn create_many<MC>(&mut self, rows: Vec<Fields>) -> Result<Vec<i64>>
+	where
+		MC: DbBmc,
+	{
+		if rows.is_empty() {
+			return Ok(Vec::new());
+		}
+		check_bulk_size(rows.len())?;
+
+		// -- Prep data
+		// Note: The rows might not have the same fields, so the columns are the
+		//       union of all of them, and the missing values are set to the column DEFAULT.
+		let rows: Vec<Vec<Field>> = rows.into_iter().map(Fields::into_vec).collect();
+		let mut columns: Vec<DynIden> = Vec::new();
+		for field in rows.iter().flatten() {
+			let name = field.iden.to_string();
+			if !columns.iter().any(|c| c.to_string() == name) {
+				columns.push(field.iden.clone());
+			}
+		}
+		let names: Vec<String> = columns.iter().map(|c| c.to_string()).collect();
+
+		// -- Build query
+		let mut query = Query::insert();
+		query.into_table(MC::table_ref()).columns(columns.clone());
+		for mut row in rows {
+			let sea_values = columns.iter().map(|column| {
+				let name = column.to_string();
+				match row.iter().position(|f| f.iden.to_string() == name) {
+					Some(idx) => row.swap_remove(idx).value,
+					None => SimpleExpr::Custom("DEFAULT".to_string()),
+				}
+			});
+			query.values(sea_values.collect::<Vec<_>>())?;
+		}
+		query.returning(returning_id_row::<MC>());
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let rows = sqlx::query_as_with::<_, (i64, Value), _>(&sql, values)
+			.fetch_all(&mut *self.tx)
+			.await?;
+
+		// -- Audit
+		self.revise::<MC>(&rows).await?;
+		let events = rows
+			.iter()
+			.map(|(id, new)| (*id, audit_diff::<MC>(Some(&names), None, Some(new))))
+			.collect();
+		self.audit::<MC>(AuditOp::Create, events).await?;
+
+		Ok(rows.into_iter().map(|(id, _)| id).collect())
+	}
+
+	pub async fn update<MC>(&mut self, id: i64, fields: Fields) -> Result<()>
+	where
+		MC: DbBmc,
+	{
+		let cond = Condition::all().add(Expr::col(CommonIden::Id).eq(id));
+		let ids = self.update_cond::<MC>(cond, fields).await?;
+
+		// -- Check result
+		if ids.is_empty() {
+			Err(Error::EntityNotFound {
+				entity: MC::TABLE,
+				id,
+			})
+		} else {
+			Ok(())
+		}
+	}
+
+	/// Updates all the targeted rows with the same fields, and returns the updated ids.
+	pub async fn update_many<MC, F>(
+		&mut self,
+		target: BulkTarget<F>,
+		fields: Fields,
+	) -> Result<Vec<i64>>
+	where
+		MC: DbBmc,
+		F: Into<FilterGroups>,
+	{
+		let (cond, expected_ids) = target.into_cond()?;
+		let ids = self.update_cond::<MC>(cond, fields).await?;
+
+		// -- Check result (dropping the tx rolls it back)
+		if let Some(id) = expected_ids.and_then(|e| first_id_missing(&e, &ids)) {
+			return Err(Error::EntityNotFound {
+				entity: MC::TABLE,
+				id,
+			});
+		}
+
+		Ok(ids)
+	}
+
+	pub async fn delete<MC>(&mut self, id: i64) -> Result<()>
+	where
+		MC: DbBmc,
+	{
+		let cond = Condition::all().add(Expr::col(CommonIden::Id).eq(id));
+		let ids = self.delete_cond::<MC>(cond).await?;
+
+		// -- Check result
+		if ids.is_empty() {
+			Err(Error::EntityNotFound {
+				entity: MC::TABLE,
+				id,
+			})
+		} else {
+			Ok(())
+		}
+	}
+
+	/// Deletes all the targeted rows, and returns the deleted ids.
+	pub async fn delete_many<MC, F>(
+		&mut self,
+		target: BulkTarget<F>,
+	) -> Result<Vec<i64>>
+	where
+		MC: DbBmc,
+		F: Into<FilterGroups>,
+	{
+		let (cond, expected_ids) = target.into_cond()?;
+		let ids = self.delete_cond::<MC>(cond).await?;
+
+		// -- Check result (dropping the tx rolls it back)
+		if let Some(id) = expected_ids.and_then(|e| first_id_missing(&e, &ids)) {
+			return Err(Error::EntityNotFound {
+				entity: MC::TABLE,
+				id,
+			});
+		}
+
+		Ok(ids)
+	}
+
+	/// Inserts the relation row (e.g., `task_tag`), whose fields are its whole key,
+	/// and returns true when inserted (false when already there).
+	/// The audit events of the relation are on the `entity_id` (e.g., the task id).
+	pub async fn link<MC>(&mut self, entity_id: i64, fields: Fields) -> Result<bool>
+	where
+		MC: DbBmc,
+	{
+		// -- Prep data
+		let fields = fields.into_vec();
+		let names = field_names(&fields);
+		let (columns, sea_values) = Fields::new(fields).for_sea_insert();
+
+		// -- Build query
+		let mut query = Query::insert();
+		query
+			.into_table(MC::table_ref())
+			.columns(columns.clone())
+			.values(sea_values)?
+			.on_conflict(OnConflict::columns(columns).do_nothing().to_owned())
+			.returning(Query::returning().expr(row_json_expr::<MC>()));
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let new = sqlx::query_as_with::<_, (Value,), _>(&sql, values)
+			.fetch_optional(&mut *self.tx)
+			.await?;
+		let Some((new,)) = new else {
+			return Ok(false);
+		};
+
+		// -- Audit
+		let diff = audit_diff::<MC>(Some(&names), None, Some(&new));
+		self.audit::<MC>(AuditOp::Create, vec![(entity_id, diff)])
+			.await?;
+
+		Ok(true)
+	}
+
+	/// Deletes the relation row matching all the fields,
+	/// and returns true when deleted (false when not there).
+	pub async fn unlink<MC>(
+		&mut self,
+		entity_id: i64,
+		fields: Fields,
+	) -> Result<bool>
+	where
+		MC: DbBmc,
+	{
+		let mut cond = Condition::all();
+		for field in fields.into_vec() {
+			cond = cond.add(Expr::col(field.iden).eq(field.value));
+		}
+
+		// -- Build query
+		let mut query = Query::delete();
+		query
+			.from_table(MC::table_ref())
+			.cond_where(cond)
+			.returning(Query::returning().expr(row_json_expr::<MC>()));
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let olds = sqlx::query_as_with::<_, (Value,), _>(&sql, values)
+			.fetch_all(&mut *self.tx)
+			.await?;
+
+		// -- Audit
+		let events: Vec<(i64, Value)> = olds
+			.iter()
+			.map(|(old,)| (entity_id, audit_diff::<MC>(None, Some(old), None)))
+			.collect();
+		let unlinked = !events.is_empty();
+		self.audit::<MC>(AuditOp::Delete, events).await?;
+
+		Ok(unlinked)
+	}
+
+	/// Locks the `scope` (e.g., `"task_rank"`) of `scope_id` (e.g., an owner id)
+	/// until the end of the transaction, for the writes which read and write
+	/// many rows of the scope (e.g., the ranks of an owner tasks).
+	///
+	/// Note: This is a transaction advisory lock, so only the writes taking
+	///       the same scope lock are serialized.
+	pub async fn lock_scope(&mut self, scope: &str, scope_id: i64) -> Result<()> {
+		sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, $2))")
+			.bind(scope)
+			.bind(scope_id)
+			.execute(&mut *self.tx)
+			.await?;
+
+		Ok(())
+	}
+
+	/// Sets the text `column` of each `(id, value)` row, with a single set-based
+	/// update, which is recorded as one write of the scope (e.g., the ranks
+	/// rebalance of an owner): one audit event of the `scope` entity (with the
+	/// `{column: {"rows": count}}` diff), and the given domain `event`.
+	///
+	/// Note: The rows are not revised (see `DbBmc::REVISIONED`), so this is only
+	///       for the columns which are not reverted (e.g., `rank`).
+	pub async fn update_column_rows<MC>(
+		&mut self,
+		scope: &str,
+		column: &str,
+		rows: Vec<(i64, String)>,
+		scope_id: i64,
+		event: DomainEvent,
+	) -> Result<()>
+	where
+		MC: DbBmc,
+	{
+		if rows.is_empty() {
+			return Ok(());
+		}
+
+		// -- Exec query
+		let table = MC::TABLE;
+		let sql = format!(
+			"UPDATE \"{table}\" AS t SET \"{column}\" = v.value
+			 FROM unnest($1::bigint[], $2::text[]) AS v(id, value)
+			 WHERE t.id = v.id
+			 RETURNING t.id"
+		);
+		let (ids, values): (Vec<i64>, Vec<String>) = rows.into_iter().unzip();
+		let updated_ids: Vec<i64> = sqlx::query_scalar(&sql)
+			.bind(&ids)
+			.bind(values)
+			.fetch_all(&mut *self.tx)
+			.await?;
+
+		// -- Check result (dropping the tx rolls it back)
+		if let Some(id) = first_id_missing(&ids, &updated_ids) {
+			return Err(Error::EntityNotFound { entity: table, id });
+		}
+
+		// -- Audit
+		let diff = json!({ column: { "rows": updated_ids.len() } });
+		self.insert_audit_events(scope, AuditOp::Update, vec![(scope_id, diff)])
+			.await?;
+		self.append_domain_events(&[event]).await
+	}
+
+	/// Updates the rows matching the condition, and returns their ids.
+	async fn update_cond<MC>(
+		&mut self,
+		cond: Condition,
+		fields: Fields,
+	) -> Result<Vec<i64>>
+	where
+		MC: DbBmc,
+	{
+		// -- Prep data
+		let fields = fields.into_vec();
+		let names = field_names(&fields);
+		let fields = Fields::new(fields).for_sea_update();
+
+		// -- Lock the rows, for their old values
+		let mut query = Query::select();
+		query
+			.from(MC::table_ref())
+			.column(CommonIden::Id)
+			.expr(row_json_expr::<MC>())
+			.cond_where(cond.clone())
+			.lock(LockType::Update);
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let olds: HashMap<i64, Value> =
+			sqlx::query_as_with::<_, (i64, Value), _>(&sql, values)
+				.fetch_all(&mut *self.tx)
+				.await?
+				.into_iter()
+				.collect();
+
+		// -- Build query
+		let mut query = Query::update();
+		query
+			.table(MC::table_ref())
+			.values(fields)
+			.cond_where(cond)
+			.returning(returning_id_row::<MC>());
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let news = sqlx::query_as_with::<_, (i64, Value), _>(&sql, values)
+			.fetch_all(&mut *self.tx)
+			.await?;
+
+		// -- Audit
+		self.revise::<MC>(&news).await?;
+		let events = news
+			.iter()
+			.map(|(id, new)| {
+				let diff = audit_diff::<MC>(Some(&names), olds.get(id), Some(new));
+				(*id, diff)
+			})
+			.collect();
+		self.audit::<MC>(AuditOp::Update, events).await?;
+
+		Ok(news.into_iter().map(|(id, _)| id).collect())
+	}
+
+	/// Deletes the rows matching the condition, and returns their ids.
+	async fn delete_cond<MC>(&mut self, cond: Condition) -> Result<Vec<i64>>
+	where
+		MC: DbBmc,
+	{
+		// -- Build query
+		let mut query = Query::delete();
+		query
+			.from_table(MC::table_ref())
+			.cond_where(cond)
+			.returning(returning_id_row::<MC>());
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let olds = sqlx::query_as_with::<_, (i64, Value), _>(&sql, values)
+			.fetch_all(&mut *self.tx)
+			.await?;
+
+		// -- Audit
+		let events = olds
+			.iter()
+			.map(|(id, old)| (*id, audit_diff::<MC>(None, Some(old), None)))
+			.collect();
+		self.audit::<MC>(AuditOp::Delete, events).await?;
+
+		Ok(olds.into_iter().map(|(id, _)| id).collect())
+	}
+
+	/// Inserts the next revisions of the `(id, state)` rows, when `MC::REVISIONED`.
+	async fn revise<MC>(&mut self, rows: &[(i64, Value)]) -> Result<()>
+	where
+		MC: DbBmc,
+	{
+		if !MC::REVISIONED || rows.is_empty() {
+			return Ok(());
+		}
+
+		// Note: The revised rows are locked by their write, so the next
+		//       revision numbers cannot be taken concurrently.
+		let table = MC::TABLE;
+		let sql = format!(
+			"INSERT INTO {table}_revision ({table}_id, revision, actor_id, state)
+			 SELECT v.id, coalesce((
+			   SELECT max(r.revision) FROM {table}_revision r WHERE r.{table}_id = v.id
+			 ), 0) + 1, $1, v.state::jsonb
+			 FROM unnest($2::bigint[], $3::text[]) AS v(id, state)"
+		);
+		let (ids, states): (Vec<i64>, Vec<String>) = rows
+			.iter()
+			.map(|(id, state)| (*id, state.to_string()))
+			.unzip();
+		sqlx::query(&sql)
+			.bind(self.actor_id)
+			.bind(ids)
+			.bind(states)
+			.execute(&mut *self.tx)
+			.await?;
+
+		Ok(())
+	}
+
+	/// Inserts the audit events of the `(entity_id, diff)` list.
+	async fn audit<MC>(
+		&mut self,
+		op: AuditOp,
+		events: Vec<(i64, Value)>,
+	) -> Result<()>
+	where
+		MC: DbBmc,
+	{
+		if events.is_empty() {
+			return Ok(());
+		}
+
+		let domain_events: Vec<DomainEvent> = events
+			.iter()
+			.filter_map(|(entity_id, diff)| MC::domain_event(op, *entity_id, diff))
+			.collect();
+		self.append_domain_events(&domain_events).await?;
+		self.insert_audit_events(MC::TABLE, op, events).await
+	}
+
+	/// Appends the domain events to the outbox, and notifies them
+	/// (see `TASK_CHANGES_CHANNEL`).
+	async fn append_domain_events(&mut self, events: &[DomainEvent]) -> Result<()> {
+		let domain_events = events
+			.iter()
+			.map(serde_json::to_string)
+			.collect::<serde_json::Result<Vec<String>>>()?;
+		if !domain_events.is_empty() {
+			sqlx::query(
+				"INSERT INTO outbox (event)
+				 SELECT v.event::jsonb FROM unnest($1::text[]) AS v(event)",
+			)
+			.bind(&domain_events)
+			.execute(&mut *self.tx)
+			.await?;
+
+			// Note: Only sent on commit (as the outbox rows).
+			sqlx::query(
+				"SELECT pg_notify($1, v.event) FROM unnest($2::text[]) AS v(event)",
+			)
+			.bind(TASK_CHANGES_CHANNEL)
+			.bind(&domain_events)
+			.execute(&mut *self.tx)
+			.await?;
+		}
+
+		Ok(())
+	}
+
+	/// Inserts the `audit_event` rows of the `(entity_id, diff)` list.
+	async fn insert_audit_events(
+		&mut self,
+		entity: &str,
+		op: AuditOp,
+		events: Vec<(i64, Value)>,
+	) -> Result<()> {
+		// Note: The diffs are bound as text, hence the `::jsonb` cast.
+		let (entity_ids, diffs): (Vec<i64>, Vec<String>) = events
+			.into_iter()
+			.map(|(entity_id, diff)| (entity_id, diff.to_string()))
+			.unzip();
+		sqlx::query(
+			"INSERT INTO audit_event (actor_id, entity, entity_id, op, diff)
+			 SELECT $1, $2, v.entity_id, $3::audit_op, v.diff::jsonb
+			 FROM unnest($4::bigint[], $5::text[]) AS v(entity_id, diff)",
+		)
+		.bind(self.actor_id)
+		.bind(entity)
+		.bind(op.as_str())
+		.bind(entity_ids)
+		.bind(diffs)
+		.execute(&mut *self.tx)
+		.await?;
+
+		Ok(())
+	}
+}
+
+/// The whole row as a json object (for the audit diffs).
+fn row_json_expr<MC: DbBmc>() -> SimpleExpr {
+	Expr::cust(format!("to_jsonb(\"{}\".*)", MC::TABLE))
+}
+
+fn returning_id_row<MC: DbBmc>() -> ReturningClause {
+	Query::returning()
+		.exprs([Expr::col(CommonIden::Id).into(), row_json_expr::<MC>()])
+}
+
+fn field_names(fields: &[Field]) -> Vec<String> {
+	fields.iter().map(|f| f.iden.to_string()).collect()
+}
+
+/// Returns the `{field: {"old": .., "new": ..}}` diff of the changed fields
+/// (all the row fields when `names` is `None`), with the `MC::AUDIT_REDACTED`
+/// values redacted.
+fn audit_diff<MC: DbBmc>(
+	names: Option<&[String]>,
+	old: Option<&Value>,
+	new: Option<&Value>,
+) -> Value {
+	let names: Vec<&str> = match names {
+		Some(names) => names.iter().map(String::as_str).collect(),
+		None => old
+			.or(new)
+			.and_then(Value::as_object)
+			.map(|row| row.keys().map(String::as_str).collect())
+			.unwrap_or_default(),
+	};
+	let redact = |name: &str, value: &Value| {
+		if MC::AUDIT_REDACTED.contains(&name) {
+			Value::from(AUDIT_REDACTED_VALUE)
+		} else {
+			value.clone()
+		}
+	};
+
+	let mut diff = Map::new();
+	for name in names {
+		let old_value = old.and_then(|row| row.get(name));
+		let new_value = new.and_then(|row| row.get(name));
+		if old.is_some() && new.is_some() && old_value == new_value {
+			continue;
+		}
+
+		let mut change = Map::new();
+		if let Some(value) = old_value {
+			change.insert("old".to_string(), redact(name, value));
+		}
+		if let Some(value) = new_value {
+			change.insert("new".to_string(), redact(name, value));
+		}
+		diff.insert(name.to_string(), Value::Object(change));
+	}
+
+	Value::Object(diff)
+}
+
+// endregion: --- WriteTx
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use serde_json::json;
+
+	#[test]
+	fn test_finalize_list_options_default_order_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_cases = [
+			(None, "id ASC"),
+			(Some(json!({"limit": 2, "offset": 1})), "id ASC"),
+			(
+				Some(json!({"offset": 1, "order_bys": "!ctime"})),
+				"ctime DESC",
+			),
+		];
+
+		for (fx_list_options, fx_order_by) in fx_cases {
+			// -- Exec
+			let list_options: Option<ListOptions> =
+				fx_list_options.map(serde_json::from_value).transpose()?;
+			let list_options = finalize_list_options(list_options)?;
+
+			// -- Check
+			let order_bys: Vec<String> = list_options
+				.order_bys
+				.into_iter()
+				.flatten()
+				.map(|order_by| order_by.to_string())
+				.collect();
+			assert_eq!(order_bys, &[fx_order_by]);
+			assert!(list_options.limit.is_some());
+		}
+
 		Ok(())
 	}
 }
+// endregion: --- Tests
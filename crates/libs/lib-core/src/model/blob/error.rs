@@ -0,0 +1,44 @@
+use serde::Serialize;
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[derive(Debug, Serialize)]
+pub enum Error {
+	ConfigInvalid(String),
+	KeyInvalid(String),
+	BlobNotFound(String),
+
+	// -- Backends
+	Fs(String),
+	ObjectStore(String),
+}
+
+// region:    --- Froms
+impl From<std::io::Error> for Error {
+	fn from(val: std::io::Error) -> Self {
+		Self::Fs(val.to_string())
+	}
+}
+
+impl From<object_store::Error> for Error {
+	fn from(val: object_store::Error) -> Self {
+		match val {
+			object_store::Error::NotFound { path, .. } => Self::BlobNotFound(path),
+			other => Self::ObjectStore(other.to_string()),
+		}
+	}
+}
+// endregion: --- Froms
+
+// region:    --- Error Boilerplate
+impl core::fmt::Display for Error {
+	fn fmt(
+		&self,
+		fmt: &mut core::fmt::Formatter,
+	) -> core::result::Result<(), core::fmt::Error> {
+		write!(fmt, "{self:?}")
+	}
+}
+
+impl std::error::Error for Error {}
+// endregion: --- Error Boilerplate
@@ -0,0 +1,244 @@
+//! Blob store for the binary content of the model entities (e.g., attachments).
+//!
+//! The db only holds the blob keys, and the content is in a `BlobStore`,
+//! which is selected with the `SERVICE_BLOB_STORE` config:
+//!
+//! - `fs:<dir>` - `FsBlobStore`, one file per blob in the local `<dir>`.
+//! - `s3:<bucket>` - `S3BlobStore`, on any S3 compatible service
+//!   (the endpoint and credentials are taken from the standard
+//!   `AWS_ENDPOINT`, `AWS_ACCESS_KEY_ID`, ... environment variables).
+
+// region:    --- Modules
+
+mod error;
+
+pub use self::error::{Error, Result};
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+// endregion: --- Modules
+
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+	async fn put(&self, key: &str, content: Vec<u8>) -> Result<()>;
+
+	async fn get(&self, key: &str) -> Result<Vec<u8>>;
+
+	/// Note: Deleting a missing blob is not an error.
+	async fn delete(&self, key: &str) -> Result<()>;
+
+	async fn list_keys(&self) -> Result<Vec<String>>;
+}
+
+/// Returns the `BlobStore` for the `SERVICE_BLOB_STORE` config value.
+pub fn new_blob_store(config: &str) -> Result<Arc<dyn BlobStore>> {
+	match config.split_once(':') {
+		Some(("fs", dir)) if !dir.is_empty() => Ok(Arc::new(FsBlobStore::new(dir))),
+		Some(("s3", bucket)) if !bucket.is_empty() => {
+			Ok(Arc::new(S3BlobStore::from_env(bucket)?))
+		}
+		_ => Err(Error::ConfigInvalid(config.to_string())),
+	}
+}
+
+/// Keys are generated by the model layer, so only allow simple names
+/// (which keeps the fs store within its directory).
+fn validate_key(key: &str) -> Result<()> {
+	let valid = !key.is_empty()
+		&& key
+			.chars()
+			.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+	if valid {
+		Ok(())
+	} else {
+		Err(Error::KeyInvalid(key.to_string()))
+	}
+}
+
+// region:    --- FsBlobStore
+pub struct FsBlobStore {
+	dir: PathBuf,
+}
+
+impl FsBlobStore {
+	pub fn new(dir: impl Into<PathBuf>) -> Self {
+		FsBlobStore { dir: dir.into() }
+	}
+}
+
+#[async_trait]
+impl BlobStore for FsBlobStore {
+	async fn put(&self, key: &str, content: Vec<u8>) -> Result<()> {
+		validate_key(key)?;
+		tokio::fs::create_dir_all(&self.dir).await?;
+
+		// Write to a temporary file first, so that a blob is never partially visible.
+		let tmp_path = self.dir.join(format!(".{key}.tmp"));
+		tokio::fs::write(&tmp_path, content).await?;
+		tokio::fs::rename(&tmp_path, self.dir.join(key)).await?;
+
+		Ok(())
+	}
+
+	async fn get(&self, key: &str) -> Result<Vec<u8>> {
+		validate_key(key)?;
+
+		tokio::fs::read(self.dir.join(key))
+			.await
+			.map_err(|ex| match ex.kind() {
+				ErrorKind::NotFound => Error::BlobNotFound(key.to_string()),
+				_ => ex.into(),
+			})
+	}
+
+	async fn delete(&self, key: &str) -> Result<()> {
+		validate_key(key)?;
+
+		match tokio::fs::remove_file(self.dir.join(key)).await {
+			Err(ex) if ex.kind() != ErrorKind::NotFound => Err(ex.into()),
+			_ => Ok(()),
+		}
+	}
+
+	async fn list_keys(&self) -> Result<Vec<String>> {
+		let mut entries = match tokio::fs::read_dir(&self.dir).await {
+			Ok(entries) => entries,
+			Err(ex) if ex.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+			Err(ex) => return Err(ex.into()),
+		};
+
+		let mut keys = Vec::new();
+		while let Some(entry) = entries.next_entry().await? {
+			if let Some(name) = entry.file_name().to_str() {
+				if validate_key(name).is_ok() {
+					keys.push(name.to_string());
+				}
+			}
+		}
+
+		Ok(keys)
+	}
+}
+// endregion: --- FsBlobStore
+
+// region:    --- S3BlobStore
+pub struct S3BlobStore {
+	store: Arc<dyn ObjectStore>,
+}
+
+impl S3BlobStore {
+	/// Note: Any `ObjectStore` can back it (e.g., the `object_store::memory::InMemory`
+	///       as a local stand-in for tests).
+	pub fn new(store: Arc<dyn ObjectStore>) -> Self {
+		S3BlobStore { store }
+	}
+
+	/// Builds the S3 client from the standard `AWS_*` environment variables
+	/// (e.g., `AWS_ENDPOINT` and `AWS_ALLOW_HTTP` for a local MinIO).
+	pub fn from_env(bucket: &str) -> Result<Self> {
+		let store = AmazonS3Builder::from_env()
+			.with_bucket_name(bucket)
+			.build()?;
+
+		Ok(Self::new(Arc::new(store)))
+	}
+}
+
+#[async_trait]
+impl BlobStore for S3BlobStore {
+	async fn put(&self, key: &str, content: Vec<u8>) -> Result<()> {
+		validate_key(key)?;
+		self.store
+			.put(&ObjectPath::from(key), content.into())
+			.await?;
+
+		Ok(())
+	}
+
+	async fn get(&self, key: &str) -> Result<Vec<u8>> {
+		validate_key(key)?;
+		let content = self
+			.store
+			.get(&ObjectPath::from(key))
+			.await?
+			.bytes()
+			.await?;
+
+		Ok(content.to_vec())
+	}
+
+	async fn delete(&self, key: &str) -> Result<()> {
+		validate_key(key)?;
+
+		match self.store.delete(&ObjectPath::from(key)).await {
+			Err(object_store::Error::NotFound { .. }) => Ok(()),
+			res => Ok(res?),
+		}
+	}
+
+	async fn list_keys(&self) -> Result<Vec<String>> {
+		let metas: Vec<_> = self.store.list(None).try_collect().await?;
+
+		Ok(metas
+			.into_iter()
+			.map(|meta| meta.location.to_string())
+			.collect())
+	}
+}
+// endregion: --- S3BlobStore
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use object_store::memory::InMemory;
+
+	#[tokio::test]
+	async fn test_blob_stores_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_dir = std::env::temp_dir().join("test_blob_stores_ok");
+		let stores: Vec<Arc<dyn BlobStore>> = vec![
+			Arc::new(FsBlobStore::new(&fx_dir)),
+			Arc::new(S3BlobStore::new(Arc::new(InMemory::new()))),
+		];
+
+		for store in stores {
+			// -- Exec
+			store.put("blob-01", b"content 01".to_vec()).await?;
+			store.put("blob-02", b"content 02".to_vec()).await?;
+			store.delete("blob-02").await?;
+			store.delete("blob-02").await?;
+
+			// -- Check
+			assert_eq!(store.get("blob-01").await?, b"content 01");
+			assert_eq!(store.list_keys().await?, &["blob-01"]);
+			assert!(
+				matches!(store.get("blob-02").await, Err(Error::BlobNotFound(_))),
+				"BlobNotFound not matching"
+			);
+			assert!(
+				matches!(
+					store.put("../blob", vec![]).await,
+					Err(Error::KeyInvalid(_))
+				),
+				"KeyInvalid not matching"
+			);
+
+			// -- Clean
+			store.delete("blob-01").await?;
+		}
+		std::fs::remove_dir_all(fx_dir)?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,311 @@
+use crate::ctx::Ctx;
+use crate::model::base::{self, DbBmc};
+use crate::model::schema::Rfc3339Schema;
+use crate::model::task::TaskBmc;
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use lib_utils::time::{now_utc, Rfc3339};
+use lib_utils::validate::Validate;
+use modql::field::Fields;
+use modql::filter::{FilterNodes, ListOptions, OpValsInt64};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+// region:    --- Comment Types
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct Comment {
+	pub id: i64,
+	pub task_id: i64,
+	pub author_id: i64,
+
+	pub body: String,
+
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "Rfc3339Schema")]
+	pub ctime: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "Rfc3339Schema")]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Validate)]
+pub struct CommentForCreate {
+	pub task_id: i64,
+	#[validate(non_empty, length(max = 4096))]
+	pub body: String,
+}
+
+#[derive(Fields)]
+struct CommentForInsert {
+	task_id: i64,
+	author_id: i64,
+	body: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Validate)]
+pub struct CommentForUpdate {
+	#[validate(non_empty, length(max = 4096))]
+	pub body: String,
+}
+
+/// The `CommentForUpdate` with its modification time.
+#[derive(Fields)]
+struct CommentForEdit {
+	body: String,
+	mtime: OffsetDateTime,
+}
+
+#[derive(FilterNodes, Default)]
+struct CommentFilter {
+	task_id: Option<OpValsInt64>,
+}
+// endregion: --- Comment Types
+
+// region:    --- CommentBmc
+pub struct CommentBmc;
+
+impl DbBmc for CommentBmc {
+	const TABLE: &'static str = "comment";
+}
+
+/// Note: The author is the ctx user, and only the author can edit
+///       or delete a comment.
+impl CommentBmc {
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		comment_c: CommentForCreate,
+	) -> Result<i64> {
+		// -- Check task
+		TaskBmc::get(ctx, mm, comment_c.task_id).await?;
+
+		let comment_i = CommentForInsert {
+			task_id: comment_c.task_id,
+			author_id: ctx.user_id(),
+			body: comment_c.body,
+		};
+
+		base::create::<Self, _>(ctx, mm, comment_i).await
+	}
+
+	/// Returns the comment, when its task is visible to the ctx user.
+	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Comment> {
+		let comment: Comment = base::get::<Self, _>(ctx, mm, id).await?;
+
+		// -- Check task
+		TaskBmc::check_visible(ctx, mm, comment.task_id).await?;
+
+		Ok(comment)
+	}
+
+	/// Lists the comments of the task, oldest first by default (paginated
+	/// with the `list_options` limit and offset).
+	pub async fn list_for_task(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		task_id: i64,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<Comment>> {
+		// -- Check task
+		TaskBmc::get(ctx, mm, task_id).await?;
+
+		let filter = CommentFilter {
+			task_id: Some(task_id.into()),
+		};
+
+		base::list::<Self, _, _>(ctx, mm, Some(vec![filter]), list_options).await
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		comment_u: CommentForUpdate,
+	) -> Result<()> {
+		Self::check_author(ctx, mm, id).await?;
+
+		let comment_e = CommentForEdit {
+			body: comment_u.body,
+			mtime: now_utc(),
+		};
+
+		base::update::<Self, _>(ctx, mm, id, comment_e).await
+	}
+
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		Self::check_author(ctx, mm, id).await?;
+
+		base::delete::<Self>(ctx, mm, id).await
+	}
+
+	async fn check_author(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		let comment = Self::get(ctx, mm, id).await?;
+
+		if comment.author_id != ctx.user_id() {
+			return Err(Error::AccessDenied {
+				entity: Self::TABLE,
+				id,
+			});
+		}
+
+		Ok(())
+	}
+}
+// endregion: --- CommentBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use anyhow::Result;
+	use serde_json::json;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_for_task_paginated_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, &["test_list_for_task"])
+			.await?
+			.remove(0);
+		for body in ["comment 01", "comment 02", "comment 03"] {
+			let comment_c = CommentForCreate {
+				task_id: fx_task.id,
+				body: body.to_string(),
+			};
+			CommentBmc::create(&ctx, &mm, comment_c).await?;
+		}
+
+		// -- Exec
+		let list_options: ListOptions =
+			serde_json::from_value(json!({"limit": 2, "offset": 1}))?;
+		let comments =
+			CommentBmc::list_for_task(&ctx, &mm, fx_task.id, Some(list_options))
+				.await?;
+
+		// -- Check
+		let bodies: Vec<&str> = comments.iter().map(|c| c.body.as_str()).collect();
+		assert_eq!(bodies, &["comment 02", "comment 03"]);
+		assert!(comments.iter().all(|c| c.author_id == ctx.user_id()));
+
+		// -- Clean (comments are deleted with their task)
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_err_not_author() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let other_ctx = Ctx::new(9032)?;
+		// Note: Owned by the other user, who sees the comment, but is not its author.
+		let fx_task =
+			_dev_utils::seed_tasks(&other_ctx, &mm, &["test_update_err_not_author"])
+				.await?
+				.remove(0);
+		let comment_c = CommentForCreate {
+			task_id: fx_task.id,
+			body: "comment 01".to_string(),
+		};
+		let fx_comment_id = CommentBmc::create(&ctx, &mm, comment_c).await?;
+
+		// -- Exec
+		let comment_u = CommentForUpdate {
+			body: "edited".to_string(),
+		};
+		let res =
+			CommentBmc::update(&other_ctx, &mm, fx_comment_id, comment_u).await;
+		let res_delete = CommentBmc::delete(&other_ctx, &mm, fx_comment_id).await;
+
+		// -- Check
+		for res in [res, res_delete] {
+			assert!(
+				matches!(
+					res,
+					Err(Error::AccessDenied {
+						entity: "comment",
+						..
+					})
+				),
+				"AccessDenied not matching"
+			);
+		}
+		let comment = CommentBmc::get(&ctx, &mm, fx_comment_id).await?;
+		assert_eq!(comment.body, "comment 01");
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_get_err_not_visible() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::new(9039)?;
+		let fx_task =
+			_dev_utils::seed_tasks(&ctx, &mm, &["test_get_err_not_visible"])
+				.await?
+				.remove(0);
+		let comment_c = CommentForCreate {
+			task_id: fx_task.id,
+			body: "private comment".to_string(),
+		};
+		let fx_comment_id = CommentBmc::create(&ctx, &mm, comment_c).await?;
+
+		// -- Exec
+		let res = CommentBmc::get(&Ctx::new(9040)?, &mm, fx_comment_id).await;
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::TaskNotVisible { id, user_id: 9040 }) if id == fx_task.id),
+			"TaskNotVisible not matching"
+		);
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_validate_err_body() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_cases = [
+			("a".repeat(10_000), "length"),
+			("".to_string(), "non_empty"),
+		];
+
+		for (fx_body, fx_rule) in fx_cases {
+			// -- Exec
+			let comment_c = CommentForCreate {
+				task_id: 100,
+				body: fx_body.clone(),
+			};
+			let err_create = comment_c.validate().expect_err("Should fail");
+			let comment_u = CommentForUpdate { body: fx_body };
+			let err_update = comment_u.validate().expect_err("Should fail");
+
+			// -- Check
+			for err in [err_create, err_update] {
+				assert_eq!(err.field, "body");
+				assert_eq!(err.rule, fx_rule);
+			}
+		}
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -1,6 +1,8 @@
-use crate::model::store;
+use crate::model::task::TaskStatus;
+use crate::model::{blob, store};
 use derive_more::From;
 use lib_auth::pwd;
+use lib_utils::{rank, time};
 use serde::Serialize;
 use serde_with::{serde_as, DisplayFromStr};
 
@@ -13,26 +15,166 @@ pub enum Error {
 		entity: &'static str,
 		id: i64,
 	},
+	AccessDenied {
+		entity: &'static str,
+		id: i64,
+	},
+	AdminRequired {
+		user_id: i64,
+	},
 	ListLimitOverMax {
 		max: i64,
 		actual: i64,
 	},
+	BulkSizeOverMax {
+		max: usize,
+		actual: usize,
+	},
+	BulkFiltersEmpty,
+	ProjectionFieldUnknown {
+		field: String,
+	},
+	ProjectionFieldsEmpty,
+	UserPwdNotMatching {
+		user_id: i64,
+	},
+	IdempotencyKeyReused {
+		key: String,
+	},
+	IdempotencyKeyInProgress {
+		key: String,
+	},
+	IdempotencyKeyLeaseLost {
+		key: String,
+	},
+
+	// -- Constraint Violations (see `From<sqlx::Error>`)
+	UniqueViolation {
+		entity: String,
+		field: String,
+	},
+	ForeignKeyViolation {
+		entity: String,
+		field: String,
+	},
+	CheckViolation {
+		entity: String,
+		constraint: String,
+	},
+
+	TaskStatusTransitionInvalid {
+		id: i64,
+		from: TaskStatus,
+		to: TaskStatus,
+	},
+	TaskParentCycle {
+		id: i64,
+		parent_id: i64,
+	},
+	TaskTreeDepthOverMax {
+		max: i32,
+		actual: i32,
+	},
+	TaskRankScopeMismatch {
+		id: i64,
+		anchor_id: i64,
+	},
+	TaskRevisionNotFound {
+		id: i64,
+		revision: i32,
+	},
+	TaskNotVisible {
+		id: i64,
+		user_id: i64,
+	},
 
 	// -- Modules
 	#[from]
 	Pwd(pwd::Error),
 	#[from]
 	Store(store::Error),
+	#[from]
+	Blob(blob::Error),
+	#[from]
+	Rank(#[serde_as(as = "DisplayFromStr")] rank::Error),
+	#[from]
+	Time(#[serde_as(as = "DisplayFromStr")] time::Error),
 
 	// -- Externals
-	#[from]
 	Sqlx(#[serde_as(as = "DisplayFromStr")] sqlx::Error),
 	#[from]
 	SeaQuery(#[serde_as(as = "DisplayFromStr")] sea_query::error::Error),
 	#[from]
 	ModqlIntoSea(#[serde_as(as = "DisplayFromStr")] modql::filter::IntoSeaError),
+	#[from]
+	SerdeJson(#[serde_as(as = "DisplayFromStr")] serde_json::Error),
+}
+
+// region:    --- Constraint Violations
+
+// The Postgres SQLSTATE codes of the constraint violations.
+const PG_UNIQUE_VIOLATION: &str = "23505";
+const PG_FOREIGN_KEY_VIOLATION: &str = "23503";
+const PG_CHECK_VIOLATION: &str = "23514";
+
+/// Maps the database constraint violations to their typed variant,
+/// and the other sqlx errors to `Sqlx`.
+impl From<sqlx::Error> for Error {
+	fn from(err: sqlx::Error) -> Self {
+		constraint_violation(&err).unwrap_or(Self::Sqlx(err))
+	}
+}
+
+/// Returns the constraint violation of the error, with its `entity` from the table,
+/// and its `field` from the constraint name, as named by Postgres by default
+/// (e.g., `user_username_key` or `task_assignee_id_fkey`).
+///
+/// Note: The multi-column constraints are named for their field
+///       (e.g., `tag_name_key` for the `UNIQUE (owner_id, name)`).
+fn constraint_violation(err: &sqlx::Error) -> Option<Error> {
+	let db_err = err.as_database_error()?;
+	let code = db_err.code()?;
+	let table = db_err.table()?;
+	let constraint = db_err.constraint()?;
+
+	let entity = table.to_string();
+	let violation = match code.as_ref() {
+		PG_UNIQUE_VIOLATION => Error::UniqueViolation {
+			entity,
+			field: constraint_field(table, constraint, "_key"),
+		},
+		PG_FOREIGN_KEY_VIOLATION => Error::ForeignKeyViolation {
+			entity,
+			field: constraint_field(table, constraint, "_fkey"),
+		},
+		PG_CHECK_VIOLATION => Error::CheckViolation {
+			entity,
+			constraint: constraint.to_string(),
+		},
+		_ => return None,
+	};
+
+	Some(violation)
 }
 
+/// Returns the field of a `{table}_{field}{suffix}` constraint name,
+/// `id` for the `{table}_pkey`, and the constraint name otherwise.
+fn constraint_field(table: &str, constraint: &str, suffix: &str) -> String {
+	if constraint == format!("{table}_pkey") {
+		return "id".to_string();
+	}
+
+	constraint
+		.strip_prefix(table)
+		.and_then(|rest| rest.strip_prefix('_'))
+		.and_then(|rest| rest.strip_suffix(suffix))
+		.filter(|field| !field.is_empty())
+		.unwrap_or(constraint)
+		.to_string()
+}
+
+// endregion: --- Constraint Violations
+
 // region:    --- Error Boilerplate
 impl core::fmt::Display for Error {
 	fn fmt(
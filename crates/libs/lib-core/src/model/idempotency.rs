@@ -0,0 +1,345 @@
+//! The idempotency keys of the mutating calls, which store the result of the
+//! first call with a key, to replay it on the retries with the same key
+//! (e.g., the lib-rpc calls with an `idempotency_key`).
+//!
+//! The keys are per user, and expire after the `SERVICE_IDEMPOTENCY_TTL_SEC`
+//! window (then, deleted by the `IdempotencyKeyBmc::delete_expired` job).
+//!
+//! Note: The keys are not audited (as not application data).
+
+use crate::core_config;
+use crate::ctx::Ctx;
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use lib_utils::time::now_utc;
+use serde_json::Value;
+use time::{Duration, OffsetDateTime};
+use tracing::debug;
+
+// region:    --- Idempotency Types
+
+/// The reservation of a key, by `IdempotencyKeyBmc::reserve`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdempotencyReservation {
+	/// The key is reserved for the call, which must `complete` or `release` it.
+	Reserved(IdempotencyLease),
+	/// The result of the first call with the key, to replay.
+	Replay(Value),
+}
+
+/// The lease of a reserved key, until its `reserved_at` plus the lease duration
+/// (then, a retry with the key can take it over).
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdempotencyLease {
+	pub key: String,
+	reserved_at: OffsetDateTime,
+}
+
+// endregion: --- Idempotency Types
+
+// region:    --- IdempotencyKeyBmc
+pub struct IdempotencyKeyBmc;
+
+impl IdempotencyKeyBmc {
+	/// Reserves the key for the call, or returns the result of the first call
+	/// with the key.
+	///
+	/// The key of a call still in progress after the `lease` (e.g., cancelled
+	/// before it could `complete` or `release` it) is taken over, so the lease
+	/// must be longer than the calls.
+	///
+	/// Fails with `IdempotencyKeyReused` when the first call had another
+	/// `request_hash`, and with `IdempotencyKeyInProgress` when it is not done.
+	pub async fn reserve(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		key: &str,
+		request_hash: &str,
+		lease: std::time::Duration,
+	) -> Result<IdempotencyReservation> {
+		let user_id = ctx.user_id();
+
+		// -- Reserve the key (new, expired, or with an expired lease)
+		let reserved_at: Option<(OffsetDateTime,)> = sqlx::query_as(
+			"INSERT INTO idempotency_key (user_id, key, request_hash) VALUES ($1, $2, $3)
+			 ON CONFLICT (user_id, key) DO UPDATE
+			   SET request_hash = EXCLUDED.request_hash, result = NULL,
+			       ctime = now(), reserved_at = now()
+			   WHERE idempotency_key.ctime < $4
+			      OR (idempotency_key.result IS NULL AND idempotency_key.reserved_at < $5)
+			 RETURNING reserved_at",
+		)
+		.bind(user_id)
+		.bind(key)
+		.bind(request_hash)
+		.bind(expired_before())
+		.bind(now_utc() - lease)
+		.fetch_optional(mm.db())
+		.await?;
+		if let Some((reserved_at,)) = reserved_at {
+			return Ok(IdempotencyReservation::Reserved(IdempotencyLease {
+				key: key.to_string(),
+				reserved_at,
+			}));
+		}
+
+		// -- Or replay the first call
+		// Note: The first call key is gone when it failed in the meantime.
+		let first: Option<(String, Option<Value>)> = sqlx::query_as(
+			"SELECT request_hash, result FROM idempotency_key
+			 WHERE user_id = $1 AND key = $2",
+		)
+		.bind(user_id)
+		.bind(key)
+		.fetch_optional(mm.db())
+		.await?;
+
+		match first {
+			Some((first_hash, _)) if first_hash != request_hash => {
+				Err(Error::IdempotencyKeyReused {
+					key: key.to_string(),
+				})
+			}
+			Some((_, Some(result))) => Ok(IdempotencyReservation::Replay(result)),
+			_ => Err(Error::IdempotencyKeyInProgress {
+				key: key.to_string(),
+			}),
+		}
+	}
+
+	/// Stores the result of the call which holds the lease.
+	///
+	/// Fails with `IdempotencyKeyLeaseLost` when the lease was taken over
+	/// (i.e., the call outlived its lease), or the key released.
+	pub async fn complete(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		lease: &IdempotencyLease,
+		result: &Value,
+	) -> Result<()> {
+		let count = sqlx::query(
+			"UPDATE idempotency_key SET result = $4
+			 WHERE user_id = $1 AND key = $2 AND reserved_at = $3 AND result IS NULL",
+		)
+		.bind(ctx.user_id())
+		.bind(&lease.key)
+		.bind(lease.reserved_at)
+		.bind(result)
+		.execute(mm.db())
+		.await?
+		.rows_affected();
+
+		if count == 0 {
+			return Err(Error::IdempotencyKeyLeaseLost {
+				key: lease.key.clone(),
+			});
+		}
+
+		Ok(())
+	}
+
+	/// Deletes the key of a failed call, so that it can be retried
+	/// (unless the lease was taken over).
+	pub async fn release(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		lease: &IdempotencyLease,
+	) -> Result<()> {
+		sqlx::query(
+			"DELETE FROM idempotency_key
+			 WHERE user_id = $1 AND key = $2 AND reserved_at = $3 AND result IS NULL",
+		)
+		.bind(ctx.user_id())
+		.bind(&lease.key)
+		.bind(lease.reserved_at)
+		.execute(mm.db())
+		.await?;
+
+		Ok(())
+	}
+
+	/// Deletes the expired keys (of all users), and returns their count.
+	pub async fn delete_expired(_ctx: &Ctx, mm: &ModelManager) -> Result<u64> {
+		let count = sqlx::query("DELETE FROM idempotency_key WHERE ctime < $1")
+			.bind(expired_before())
+			.execute(mm.db())
+			.await?
+			.rows_affected();
+		debug!("{:<12} - delete_expired - {count} deleted", "MODEL");
+
+		Ok(count)
+	}
+}
+
+/// The creation time before which the keys are expired.
+fn expired_before() -> OffsetDateTime {
+	now_utc() - Duration::seconds(core_config().IDEMPOTENCY_TTL_SEC)
+}
+// endregion: --- IdempotencyKeyBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use anyhow::Result;
+	use serde_json::json;
+	use serial_test::serial;
+	use std::time::Duration;
+
+	const FX_LEASE: Duration = Duration::from_secs(60);
+
+	#[serial]
+	#[tokio::test]
+	async fn test_reserve_replay_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_key = "test_reserve_replay_ok";
+		let fx_hash = "hash 01";
+		let fx_result = json!({"id": 1000});
+
+		// -- Exec
+		let first =
+			IdempotencyKeyBmc::reserve(&ctx, &mm, fx_key, fx_hash, FX_LEASE).await?;
+		let res_in_progress =
+			IdempotencyKeyBmc::reserve(&ctx, &mm, fx_key, fx_hash, FX_LEASE).await;
+		let IdempotencyReservation::Reserved(lease) = first else {
+			panic!("First call should reserve the key");
+		};
+		IdempotencyKeyBmc::complete(&ctx, &mm, &lease, &fx_result).await?;
+		let replay =
+			IdempotencyKeyBmc::reserve(&ctx, &mm, fx_key, fx_hash, FX_LEASE).await?;
+
+		// -- Check
+		assert!(
+			matches!(res_in_progress, Err(Error::IdempotencyKeyInProgress { .. })),
+			"IdempotencyKeyInProgress not matching"
+		);
+		assert_eq!(replay, IdempotencyReservation::Replay(fx_result));
+
+		// -- Clean
+		clean_key(&mm, fx_key).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_reserve_err_reused() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_key = "test_reserve_err_reused";
+		let lease = reserve_lease(&ctx, &mm, fx_key, "hash 01", FX_LEASE).await?;
+		IdempotencyKeyBmc::complete(&ctx, &mm, &lease, &json!(null)).await?;
+
+		// -- Exec
+		let res =
+			IdempotencyKeyBmc::reserve(&ctx, &mm, fx_key, "hash 02", FX_LEASE).await;
+
+		// -- Check
+		assert!(
+			matches!(&res, Err(Error::IdempotencyKeyReused { key }) if key == fx_key),
+			"IdempotencyKeyReused not matching"
+		);
+
+		// -- Clean
+		clean_key(&mm, fx_key).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_release_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_key = "test_release_ok";
+		let lease = reserve_lease(&ctx, &mm, fx_key, "hash 01", FX_LEASE).await?;
+
+		// -- Exec
+		IdempotencyKeyBmc::release(&ctx, &mm, &lease).await?;
+
+		// -- Check
+		// The retry, even with another payload, reserves the key again.
+		reserve_lease(&ctx, &mm, fx_key, "hash 02", FX_LEASE).await?;
+
+		// -- Clean
+		clean_key(&mm, fx_key).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_reserve_ok_lease_takeover() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_key = "test_reserve_ok_lease_takeover";
+		let fx_hash = "hash 01";
+		// The call holding this lease is cancelled (i.e., never completed).
+		let lease_cancelled =
+			reserve_lease(&ctx, &mm, fx_key, fx_hash, FX_LEASE).await?;
+
+		// -- Exec
+		let res_in_progress =
+			IdempotencyKeyBmc::reserve(&ctx, &mm, fx_key, fx_hash, FX_LEASE).await;
+		// With a zero lease, so the cancelled call lease is expired.
+		let lease =
+			reserve_lease(&ctx, &mm, fx_key, fx_hash, Duration::ZERO).await?;
+		let res_complete_cancelled =
+			IdempotencyKeyBmc::complete(&ctx, &mm, &lease_cancelled, &json!(1))
+				.await;
+		IdempotencyKeyBmc::complete(&ctx, &mm, &lease, &json!(2)).await?;
+
+		// -- Check
+		assert!(
+			matches!(res_in_progress, Err(Error::IdempotencyKeyInProgress { .. })),
+			"IdempotencyKeyInProgress not matching"
+		);
+		assert!(
+			matches!(
+				&res_complete_cancelled,
+				Err(Error::IdempotencyKeyLeaseLost { key }) if key == fx_key
+			),
+			"IdempotencyKeyLeaseLost not matching"
+		);
+		let replay =
+			IdempotencyKeyBmc::reserve(&ctx, &mm, fx_key, fx_hash, FX_LEASE).await?;
+		assert_eq!(replay, IdempotencyReservation::Replay(json!(2)));
+
+		// -- Clean
+		clean_key(&mm, fx_key).await?;
+
+		Ok(())
+	}
+
+	/// Reserves the key, and returns its lease (or fails).
+	async fn reserve_lease(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		key: &str,
+		request_hash: &str,
+		lease: Duration,
+	) -> Result<IdempotencyLease> {
+		match IdempotencyKeyBmc::reserve(ctx, mm, key, request_hash, lease).await? {
+			IdempotencyReservation::Reserved(lease) => Ok(lease),
+			IdempotencyReservation::Replay(_) => {
+				Err(anyhow::anyhow!("key '{key}' should be reserved"))
+			}
+		}
+	}
+
+	async fn clean_key(mm: &ModelManager, key: &str) -> Result<()> {
+		sqlx::query("DELETE FROM idempotency_key WHERE key = $1")
+			.bind(key)
+			.execute(mm.db())
+			.await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -19,29 +19,44 @@
 
 // region:    --- Modules
 
+pub mod attachment;
+pub mod audit;
 mod base;
+pub mod blob;
+pub mod comment;
 mod error;
+pub mod idempotency;
+pub mod notify;
+pub mod outbox;
+pub mod schema;
 mod store;
+pub mod tag;
 pub mod task;
 pub mod user;
 
+pub use self::base::BulkTarget;
 pub use self::error::{Error, Result};
 
+use crate::core_config;
+use crate::model::blob::{new_blob_store, BlobStore};
 use crate::model::store::{new_db_pool, Db};
+use std::sync::Arc;
 
 // endregion: --- Modules
 
 #[derive(Clone)]
 pub struct ModelManager {
 	db: Db,
+	blob_store: Arc<dyn BlobStore>,
 }
 
 impl ModelManager {
 	/// Constructor
 	pub async fn new() -> Result<Self> {
 		let db = new_db_pool().await?;
+		let blob_store = new_blob_store(&core_config().BLOB_STORE)?;
 
-		Ok(ModelManager { db })
+		Ok(ModelManager { db, blob_store })
 	}
 
 	/// Returns the sqlx db pool reference.
@@ -49,4 +64,10 @@ impl ModelManager {
 	pub(in crate::model) fn db(&self) -> &Db {
 		&self.db
 	}
+
+	/// Returns the blob store for the entities binary content.
+	/// (Only for the model layer)
+	pub(in crate::model) fn blob_store(&self) -> &dyn BlobStore {
+		self.blob_store.as_ref()
+	}
 }
@@ -0,0 +1,94 @@
+//! The postgres `NOTIFY` of the task changes.
+//!
+//! The task domain events are notified by the `base` writes, in the same
+//! transaction (so, only on commit), and received with a `TaskChangeListener`
+//! (e.g., the web-server `/api/events` fan out).
+
+use crate::core_config;
+use crate::model::outbox::DomainEvent;
+use crate::model::Result;
+use sqlx::postgres::PgListener;
+
+/// The postgres channel of the task changes (with the `DomainEvent` json as payload).
+pub(in crate::model) const TASK_CHANGES_CHANNEL: &str = "task_changes";
+
+/// A dedicated `LISTEN` connection on the task changes.
+pub struct TaskChangeListener {
+	listener: PgListener,
+}
+
+impl TaskChangeListener {
+	/// Note: The connection is not from the `ModelManager` pool, as it is
+	///       held for as long as the listener.
+	pub async fn connect() -> Result<Self> {
+		let mut listener = PgListener::connect(&core_config().DB_URL).await?;
+		listener.listen(TASK_CHANGES_CHANNEL).await?;
+
+		Ok(TaskChangeListener { listener })
+	}
+
+	/// Waits for the next task change.
+	///
+	/// Note: When the connection is lost, it is reconnected on the next call,
+	///       and the changes notified in between are lost (i.e., the receivers
+	///       should refetch their tasks after an error).
+	pub async fn recv(&mut self) -> Result<DomainEvent> {
+		let notification = self.listener.recv().await?;
+		let event = serde_json::from_str(notification.payload())?;
+
+		Ok(event)
+	}
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::ctx::Ctx;
+	use crate::model::task::{TaskBmc, TaskForUpdate};
+	use anyhow::Result;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_recv_task_changes_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let mut listener = TaskChangeListener::connect().await?;
+
+		// -- Exec
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, &["test_notify 01"])
+			.await?
+			.remove(0);
+		let task_u = TaskForUpdate {
+			title: Some("test_notify 01 - new".to_string()),
+			..Default::default()
+		};
+		TaskBmc::update(&ctx, &mm, fx_task.id, task_u).await?;
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		// -- Check
+		let task_id = fx_task.id;
+		assert_eq!(listener.recv().await?, DomainEvent::TaskCreated { task_id });
+		assert_eq!(
+			listener.recv().await?,
+			DomainEvent::TaskUpdated {
+				task_id,
+				changed_fields: vec!["title".to_string()]
+			}
+		);
+		assert_eq!(
+			listener.recv().await?,
+			DomainEvent::TaskDeleted {
+				task_id,
+				owner_id: fx_task.owner_id,
+				assignee_id: None
+			}
+		);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,319 @@
+//! The transactional outbox of the domain events.
+//!
+//! The events are appended by the `base` writes, in the same transaction
+//! (see `DbBmc::domain_event`), and are delivered by a relay which tracks
+//! its `OutboxOffset` (e.g., the web-server outbox relay job).
+
+use crate::ctx::Ctx;
+use crate::model::ModelManager;
+use crate::model::Result;
+use lib_utils::time::Rfc3339;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::serde_as;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+// region:    --- Outbox Types
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum DomainEvent {
+	TaskCreated {
+		task_id: i64,
+	},
+	/// The `changed_fields` are the task columns, and the `tags` and `watchers`
+	/// relations.
+	TaskUpdated {
+		task_id: i64,
+		changed_fields: Vec<String>,
+	},
+	TaskDeleted {
+		task_id: i64,
+		owner_id: i64,
+		assignee_id: Option<i64>,
+	},
+	/// The ranks of all the owner tasks were rewritten (i.e., their order is
+	/// the same, but the clients must refetch the ranks).
+	TaskRanksRebalanced {
+		owner_id: i64,
+	},
+}
+
+impl DomainEvent {
+	/// The task of the event (`None` for the events on many tasks).
+	pub fn task_id(&self) -> Option<i64> {
+		match self {
+			DomainEvent::TaskCreated { task_id }
+			| DomainEvent::TaskUpdated { task_id, .. }
+			| DomainEvent::TaskDeleted { task_id, .. } => Some(*task_id),
+			DomainEvent::TaskRanksRebalanced { .. } => None,
+		}
+	}
+}
+
+#[serde_as]
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct OutboxEvent {
+	pub id: i64,
+	/// The id of the transaction which appended the event.
+	pub txid: i64,
+
+	#[sqlx(json)]
+	pub event: DomainEvent,
+
+	#[serde_as(as = "Rfc3339")]
+	pub ctime: OffsetDateTime,
+}
+
+/// The position of a relay in the outbox, which is the last delivered event.
+///
+/// Note: The events are ordered by `(txid, id)`, and listed only once all the
+///       transactions before them are done, so that an event committed after
+///       an event with a greater id is not skipped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, FromRow)]
+pub struct OutboxOffset {
+	pub txid: i64,
+	pub id: i64,
+}
+
+impl From<&OutboxEvent> for OutboxOffset {
+	fn from(event: &OutboxEvent) -> Self {
+		OutboxOffset {
+			txid: event.txid,
+			id: event.id,
+		}
+	}
+}
+
+/// Returns the changed fields of an audit diff (i.e., its keys).
+pub(in crate::model) fn diff_fields(diff: &Value) -> Vec<String> {
+	diff.as_object()
+		.map(|diff| diff.keys().cloned().collect())
+		.unwrap_or_default()
+}
+
+/// Returns the old integer value of the field in an audit diff
+/// (e.g., the `owner_id` of a deleted row).
+pub(in crate::model) fn diff_old_i64(diff: &Value, name: &str) -> Option<i64> {
+	diff.get(name)?.get("old")?.as_i64()
+}
+
+// endregion: --- Outbox Types
+
+// region:    --- OutboxBmc
+pub struct OutboxBmc;
+
+/// Note: The outbox rows are only appended by the `base` writes,
+///       and the relay offsets are not audited (as not application data).
+impl OutboxBmc {
+	/// Lists the events after the offset, in delivery order.
+	///
+	/// Note: Only the events of the transactions before the oldest transaction
+	///       in progress (in the whole db) are listed, so a long transaction
+	///       delays all the events after it (until it ends). The idle ones are
+	///       bounded by the `idle_in_transaction_session_timeout` of the app pool
+	///       sessions (see `new_db_pool`, the other clients must set it too), and
+	///       the `WriteTx` are short, but a long running query (e.g., a manual one)
+	///       still delays the relays.
+	pub async fn list_after(
+		_ctx: &Ctx,
+		mm: &ModelManager,
+		offset: OutboxOffset,
+		limit: i64,
+	) -> Result<Vec<OutboxEvent>> {
+		let events = sqlx::query_as(
+			"SELECT id, txid::text::bigint AS txid, event, ctime FROM outbox
+			 WHERE (txid, id) > ($1::text::xid8, $2)
+			   AND txid < pg_snapshot_xmin(pg_current_snapshot())
+			 ORDER BY txid, id
+			 LIMIT $3",
+		)
+		.bind(offset.txid.to_string())
+		.bind(offset.id)
+		.bind(limit)
+		.fetch_all(mm.db())
+		.await?;
+
+		Ok(events)
+	}
+
+	/// Returns the offset of the relay (the default offset when new).
+	pub async fn get_offset(
+		_ctx: &Ctx,
+		mm: &ModelManager,
+		relay: &str,
+	) -> Result<OutboxOffset> {
+		let offset =
+			sqlx::query_as("SELECT txid, id FROM outbox_offset WHERE relay = $1")
+				.bind(relay)
+				.fetch_optional(mm.db())
+				.await?;
+
+		Ok(offset.unwrap_or_default())
+	}
+
+	pub async fn set_offset(
+		_ctx: &Ctx,
+		mm: &ModelManager,
+		relay: &str,
+		offset: OutboxOffset,
+	) -> Result<()> {
+		sqlx::query(
+			"INSERT INTO outbox_offset (relay, txid, id) VALUES ($1, $2, $3)
+			 ON CONFLICT (relay) DO UPDATE SET txid = $2, id = $3",
+		)
+		.bind(relay)
+		.bind(offset.txid)
+		.bind(offset.id)
+		.execute(mm.db())
+		.await?;
+
+		Ok(())
+	}
+}
+// endregion: --- OutboxBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::tag::TagBmc;
+	use crate::model::task::{TaskBmc, TaskForUpdate};
+	use anyhow::Result;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_after_task_events_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		// Note: With its own pool, so no other test transaction can hold the events.
+		_dev_utils::init_test().await;
+		let mm = ModelManager::new().await?;
+		let ctx = Ctx::root_ctx();
+		let fx_relay = "test_list_after_task_events_ok";
+		let offset = last_offset(&mm).await?;
+		OutboxBmc::set_offset(&ctx, &mm, fx_relay, offset).await?;
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, &["test_outbox 01"])
+			.await?
+			.remove(0);
+		let fx_tag = _dev_utils::seed_tags(&ctx, &mm, &["test_outbox"])
+			.await?
+			.remove(0);
+
+		// -- Exec
+		let task_u = TaskForUpdate {
+			title: Some("test_outbox 01 - new".to_string()),
+			..Default::default()
+		};
+		TaskBmc::update(&ctx, &mm, fx_task.id, task_u.clone()).await?;
+		// Same title, so no changed fields, and no event.
+		TaskBmc::update(&ctx, &mm, fx_task.id, task_u).await?;
+		TagBmc::attach(&ctx, &mm, fx_task.id, fx_tag.id).await?;
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		// -- Check
+		let offset = OutboxBmc::get_offset(&ctx, &mm, fx_relay).await?;
+		let events = list_task_events(&ctx, &mm, offset, fx_task.id).await?;
+		let task_id = fx_task.id;
+		assert_eq!(
+			events,
+			&[
+				DomainEvent::TaskCreated { task_id },
+				DomainEvent::TaskUpdated {
+					task_id,
+					changed_fields: vec!["title".to_string()]
+				},
+				DomainEvent::TaskUpdated {
+					task_id,
+					changed_fields: vec!["tags".to_string()]
+				},
+				DomainEvent::TaskDeleted {
+					task_id,
+					owner_id: fx_task.owner_id,
+					assignee_id: None
+				},
+			]
+		);
+
+		// -- Clean
+		TagBmc::delete(&ctx, &mm, fx_tag.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_after_ok_held_by_tx_in_progress() -> Result<()> {
+		// -- Setup & Fixtures
+		_dev_utils::init_test().await;
+		let mm = ModelManager::new().await?;
+		let mm_other = ModelManager::new().await?;
+		let ctx = Ctx::root_ctx();
+		let offset = last_offset(&mm).await?;
+		// A transaction (with a txid), in progress before the task create.
+		let mut tx_other = mm_other.db().begin().await?;
+		sqlx::query("SELECT pg_current_xact_id()")
+			.execute(&mut *tx_other)
+			.await?;
+
+		// -- Exec
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, &["test_outbox_held 01"])
+			.await?
+			.remove(0);
+		let events_held = list_task_events(&ctx, &mm, offset, fx_task.id).await?;
+		tx_other.rollback().await?;
+		let events = list_task_events(&ctx, &mm, offset, fx_task.id).await?;
+
+		// -- Check
+		assert!(events_held.is_empty(), "Should be held by the other tx");
+		assert_eq!(
+			events,
+			&[DomainEvent::TaskCreated {
+				task_id: fx_task.id
+			}]
+		);
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	/// Returns the offset of the last appended event (to skip the events of
+	/// the other tests), which is committed, as the tests are serial.
+	async fn last_offset(mm: &ModelManager) -> Result<OutboxOffset> {
+		let offset = sqlx::query_as(
+			"SELECT txid::text::bigint AS txid, id FROM outbox ORDER BY id DESC LIMIT 1",
+		)
+		.fetch_optional(mm.db())
+		.await?;
+
+		Ok(offset.unwrap_or_default())
+	}
+
+	/// Returns the listed events of the task, after the offset.
+	async fn list_task_events(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		mut offset: OutboxOffset,
+		task_id: i64,
+	) -> Result<Vec<DomainEvent>> {
+		let mut task_events = Vec::new();
+		loop {
+			let events = OutboxBmc::list_after(ctx, mm, offset, 100).await?;
+			let Some(last) = events.last() else {
+				return Ok(task_events);
+			};
+			offset = last.into();
+			task_events.extend(
+				events
+					.into_iter()
+					.map(|e| e.event)
+					.filter(|e| e.task_id() == Some(task_id)),
+			);
+		}
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,220 @@
+//! The JSON schemas of the model types serialized by other crates
+//! (e.g., the `modql` filter operators, and the RFC3339 times),
+//! used by the `JsonSchema` derives with `#[schemars(with = "...")]`.
+//!
+//! Note: These types are only schemas (never constructed), and describe
+//!       the json accepted by their respective deserializers.
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde_json::{json, Value};
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+/// The operators common to all the op values.
+const OPS_COMMON: &[&str] = &["$eq", "$not", "$lt", "$lte", "$gt", "$gte"];
+/// The operators with a list of values.
+const OPS_IN: &[&str] = &["$in", "$notIn"];
+const OPS_STRING: &[&str] = &[
+	"$contains",
+	"$notContains",
+	"$startsWith",
+	"$notStartsWith",
+	"$endsWith",
+	"$notEndsWith",
+];
+const OPS_STRING_ANY: &[&str] = &[
+	"$containsAny",
+	"$containsAll",
+	"$notContainsAny",
+	"$startsWithAny",
+	"$notStartsWithAny",
+	"$endsWithAny",
+	"$notEndsWithAny",
+];
+
+/// `modql::filter::OpValsInt64`, e.g., `1000` or `{"$in": [1000, 1001]}`.
+pub struct OpValsInt64Schema;
+
+impl JsonSchema for OpValsInt64Schema {
+	fn schema_name() -> Cow<'static, str> {
+		"OpValsInt64".into()
+	}
+
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		let val = json!({"type": "integer"});
+		op_vals_schema(&val, &[(OPS_COMMON, &val)], &[(OPS_IN, &val)])
+	}
+}
+
+/// `modql::filter::OpValsString`, e.g., `"Hello"` or `{"$contains": "World"}`.
+pub struct OpValsStringSchema;
+
+impl JsonSchema for OpValsStringSchema {
+	fn schema_name() -> Cow<'static, str> {
+		"OpValsString".into()
+	}
+
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		let val = json!({"type": "string"});
+		let bool_val = json!({"type": "boolean"});
+		op_vals_schema(
+			&val,
+			&[
+				(OPS_COMMON, &val),
+				(OPS_STRING, &val),
+				(&["$empty", "$null"], &bool_val),
+			],
+			&[(OPS_IN, &val), (OPS_STRING_ANY, &val)],
+		)
+	}
+}
+
+/// `modql::filter::OpValsBool`, e.g., `true` or `{"$not": true}`.
+pub struct OpValsBoolSchema;
+
+impl JsonSchema for OpValsBoolSchema {
+	fn schema_name() -> Cow<'static, str> {
+		"OpValsBool".into()
+	}
+
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		let val = json!({"type": "boolean"});
+		op_vals_schema(&val, &[(&["$eq", "$not"], &val)], &[])
+	}
+}
+
+/// `modql::filter::OpValsValue` of the time fields (see `base::time_to_sea_value`),
+/// e.g., `{"$gte": "2024-01-01T00:00:00Z", "$lt": "2024-02-01T00:00:00Z"}`.
+pub struct OpValsTimeSchema;
+
+impl JsonSchema for OpValsTimeSchema {
+	fn schema_name() -> Cow<'static, str> {
+		"OpValsTime".into()
+	}
+
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		let val = json!({"type": "string", "format": "date-time"});
+		op_vals_schema(&val, &[(OPS_COMMON, &val)], &[(OPS_IN, &val)])
+	}
+}
+
+/// Returns the schema of the value (for `$eq`), or the object of operators.
+fn op_vals_schema(
+	val: &Value,
+	ops: &[(&[&str], &Value)],
+	ops_list: &[(&[&str], &Value)],
+) -> Schema {
+	let mut properties = serde_json::Map::new();
+	for (names, op_val) in ops {
+		for name in names.iter() {
+			properties.insert(name.to_string(), (*op_val).clone());
+		}
+	}
+	for (names, op_val) in ops_list {
+		for name in names.iter() {
+			let list = json!({"type": "array", "items": op_val});
+			properties.insert(name.to_string(), list);
+		}
+	}
+
+	json_schema!({
+		"anyOf": [
+			val,
+			{
+				"type": "object",
+				"properties": properties,
+				"additionalProperties": false
+			}
+		]
+	})
+}
+
+/// `modql::filter::ListOptions`, where `order_bys` are the field names,
+/// prefixed by `!` for the descending order (e.g., `["!due_at", "title"]`).
+pub struct ListOptionsSchema;
+
+impl JsonSchema for ListOptionsSchema {
+	fn schema_name() -> Cow<'static, str> {
+		"ListOptions".into()
+	}
+
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		json_schema!({
+			"type": "object",
+			"properties": {
+				"limit": {"type": "integer"},
+				"offset": {"type": "integer"},
+				"order_bys": {
+					"anyOf": [
+						{"type": "string"},
+						{"type": "array", "items": {"type": "string"}}
+					]
+				}
+			}
+		})
+	}
+}
+
+/// `lib_utils::time::Rfc3339` (the `serde_as` of the `OffsetDateTime` fields).
+pub struct Rfc3339Schema;
+
+impl JsonSchema for Rfc3339Schema {
+	fn schema_name() -> Cow<'static, str> {
+		"Rfc3339".into()
+	}
+
+	fn inline_schema() -> bool {
+		true
+	}
+
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		json_schema!({"type": "string", "format": "date-time"})
+	}
+}
+
+/// `serde_with::OneOrMany`, a single value or a list.
+pub struct OneOrManySchema<T>(PhantomData<T>);
+
+impl<T: JsonSchema> JsonSchema for OneOrManySchema<T> {
+	fn schema_name() -> Cow<'static, str> {
+		format!("OneOrMany_{}", T::schema_name()).into()
+	}
+
+	fn inline_schema() -> bool {
+		true
+	}
+
+	fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+		let item = generator.subschema_for::<T>();
+		json_schema!({
+			"anyOf": [item, {"type": "array", "items": item}]
+		})
+	}
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::model::task::TaskFilter;
+	use anyhow::Result;
+	use schemars::schema_for;
+
+	#[test]
+	fn test_task_filter_schema_ok() -> Result<()> {
+		// -- Exec
+		let schema = schema_for!(TaskFilter).to_value();
+
+		// -- Check
+		let properties = &schema["properties"];
+		assert!(properties["title"].to_string().contains("OpValsString"));
+		assert!(properties["tags"].to_string().contains("TaskTagsFilter"));
+		assert!(properties.get("assigned_to").is_none());
+		let op_vals = &schema["$defs"]["OpValsString"]["anyOf"][1]["properties"];
+		assert_eq!(op_vals["$containsAny"]["items"]["type"], "string");
+		assert_eq!(op_vals["$null"]["type"], "boolean");
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
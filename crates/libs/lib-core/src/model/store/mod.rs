@@ -18,6 +18,15 @@ pub async fn new_db_pool() -> Result<Db> {
 
 	PgPoolOptions::new()
 		.max_connections(max_connections)
+		// * See NOTE 2) below
+		.after_connect(|conn, _meta| {
+			Box::pin(async move {
+				sqlx::query("SET idle_in_transaction_session_timeout = '1min'")
+					.execute(conn)
+					.await?;
+				Ok(())
+			})
+		})
 		.connect(&core_config().DB_URL)
 		.await
 		.map_err(|ex| Error::FailToCreatePool(ex.to_string()))
@@ -31,3 +40,8 @@ pub async fn new_db_pool() -> Result<Db> {
 //         while not ideal, it should serve as an acceptable temporary solution.
 //         It's a very challenging issue to investigate and narrow down. The alternative would have been to stick with sqlx 0.6.x, which
 //         is potentially less ideal and might lead to confusion as to why we are maintaining the older version in this blueprint.
+
+// NOTE 2) The outbox events are only listed once all the transactions before them are done
+//         (see `OutboxBmc::list_after`), so a transaction left idle stalls the relays. This bounds it
+//         for the sessions of the app pool. Any other client writing to the db (e.g., a manual session)
+//         should set the same timeout, as it is per session (and not in the db schema).
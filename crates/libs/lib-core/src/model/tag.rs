@@ -0,0 +1,641 @@
+use crate::ctx::Ctx;
+use crate::model::audit::AuditOp;
+use crate::model::base::{self, DbBmc};
+use crate::model::outbox::DomainEvent;
+use crate::model::schema::{OpValsInt64Schema, OpValsStringSchema};
+use crate::model::task::{visible_task_ids_query, TaskBmc};
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use lib_utils::validate::Validate;
+use modql::field::{Fields, HasFields};
+use modql::filter::{
+	FilterGroups, FilterNode, FilterNodeOptions, FilterNodes, IntoSeaError,
+	ListOptions, OpValValue, OpValsInt64, OpValsString, SeaResult, ToSeaConditionFn,
+	ToSeaConditionFnHolder,
+};
+use schemars::JsonSchema;
+use sea_query::{
+	ColumnRef, Condition, ConditionExpression, Expr, Iden, Order,
+	PostgresQueryBuilder, Query,
+};
+use sea_query_binder::SqlxBinder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+
+// region:    --- Tag Types
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct Tag {
+	pub id: i64,
+	pub owner_id: i64,
+
+	pub name: String,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct TagWithCount {
+	#[sqlx(flatten)]
+	#[serde(flatten)]
+	pub tag: Tag,
+
+	/// Number of tasks with this tag.
+	pub task_count: i64,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Validate)]
+pub struct TagForCreate {
+	#[validate(non_empty, trimmed, length(max = 64))]
+	pub name: String,
+}
+
+#[derive(Fields)]
+struct TagForInsert {
+	owner_id: i64,
+	name: String,
+}
+
+#[derive(Fields, Default, Serialize, Deserialize, JsonSchema, Validate)]
+pub struct TagForUpdate {
+	#[validate(non_empty, trimmed, length(max = 64))]
+	pub name: Option<String>,
+}
+
+/// The `task_tag` relation row.
+#[derive(Fields)]
+pub(in crate::model) struct TaskTagForLink {
+	pub task_id: i64,
+	pub tag_id: i64,
+}
+
+#[derive(FilterNodes, Deserialize, Default, Debug, JsonSchema)]
+pub struct TagFilter {
+	#[schemars(with = "Option<OpValsInt64Schema>")]
+	id: Option<OpValsInt64>,
+
+	#[schemars(with = "Option<OpValsStringSchema>")]
+	name: Option<OpValsString>,
+}
+
+#[derive(Iden)]
+enum TagIden {
+	#[iden = "tag"]
+	Table,
+	Id,
+	OwnerId,
+	TaskCount,
+}
+
+#[derive(Iden)]
+enum TaskTagIden {
+	#[iden = "task_tag"]
+	Table,
+	TaskId,
+	TagId,
+}
+// endregion: --- Tag Types
+
+// region:    --- Task Tags Filter
+
+/// The `TaskFilter` on the task tag ids.
+/// e.g., `{"tags": {"$hasAny": [1000, 1001]}}` or `{"tags": {"$hasAll": [1000, 1001]}}`
+///
+/// Note: `$hasAny` of no tag ids matches no task, and `$hasAll` of no tag ids
+///       matches all the tasks.
+#[derive(Deserialize, Default, Debug, JsonSchema)]
+pub struct TaskTagsFilter {
+	#[serde(rename = "$hasAny")]
+	pub has_any: Option<Vec<i64>>,
+	#[serde(rename = "$hasAll")]
+	pub has_all: Option<Vec<i64>>,
+}
+
+impl TaskTagsFilter {
+	/// Returns the `FilterNode`s on the task `id`, with their `task_tag` subquery condition.
+	pub(in crate::model) fn filter_nodes(self) -> Vec<FilterNode> {
+		let mut nodes = Vec::new();
+		if let Some(tag_ids) = self.has_any {
+			nodes.push(tag_ids_node(tag_ids, tags_has_any_cond));
+		}
+		// Note: Without a node, as all the tasks have all of no tags.
+		if let Some(tag_ids) = self.has_all.filter(|ids| !ids.is_empty()) {
+			nodes.push(tag_ids_node(tag_ids, tags_has_all_cond));
+		}
+		nodes
+	}
+}
+
+fn tag_ids_node(
+	tag_ids: Vec<i64>,
+	to_sea_condition: ToSeaConditionFn,
+) -> FilterNode {
+	let tag_ids: Vec<Value> = tag_ids.into_iter().map(Value::from).collect();
+
+	FilterNode {
+		context_path: None,
+		name: "id".to_string(),
+		opvals: vec![OpValValue::In(tag_ids).into()],
+		options: FilterNodeOptions::default(),
+		for_sea_condition: Some(
+			ToSeaConditionFnHolder::new(to_sea_condition).into(),
+		),
+	}
+}
+
+fn tag_ids_from_op_value(op_value: OpValValue) -> SeaResult<Vec<i64>> {
+	let OpValValue::In(values) = op_value else {
+		return Err(IntoSeaError::custom(
+			"tags filter must have a list of tag ids",
+		));
+	};
+
+	values
+		.into_iter()
+		.map(|v| {
+			v.as_i64().ok_or_else(|| {
+				IntoSeaError::custom(format!("tag id must be an integer, was: {v}"))
+			})
+		})
+		.collect()
+}
+
+/// `id IN (SELECT task_id FROM task_tag WHERE tag_id IN (..))`
+fn tags_has_any_cond(
+	col: &ColumnRef,
+	op_value: OpValValue,
+) -> SeaResult<ConditionExpression> {
+	let tag_ids = tag_ids_from_op_value(op_value)?;
+
+	let mut subquery = Query::select();
+	subquery
+		.column(TaskTagIden::TaskId)
+		.from(TaskTagIden::Table)
+		.and_where(Expr::col(TaskTagIden::TagId).is_in(tag_ids));
+
+	Ok(Expr::col(col.clone()).in_subquery(subquery).into())
+}
+
+/// `id IN (SELECT task_id FROM task_tag WHERE tag_id IN (..)
+///         GROUP BY task_id HAVING COUNT(DISTINCT tag_id) = tag_ids_count)`
+fn tags_has_all_cond(
+	col: &ColumnRef,
+	op_value: OpValValue,
+) -> SeaResult<ConditionExpression> {
+	let mut tag_ids = tag_ids_from_op_value(op_value)?;
+	tag_ids.sort_unstable();
+	tag_ids.dedup();
+	let tag_ids_count = tag_ids.len() as i64;
+
+	let mut subquery = Query::select();
+	subquery
+		.column(TaskTagIden::TaskId)
+		.from(TaskTagIden::Table)
+		.and_where(Expr::col(TaskTagIden::TagId).is_in(tag_ids))
+		.group_by_col(TaskTagIden::TaskId)
+		.and_having(Expr::cust_with_values(
+			"COUNT(DISTINCT tag_id) = $1",
+			[tag_ids_count],
+		));
+
+	Ok(Expr::col(col.clone()).in_subquery(subquery).into())
+}
+
+// endregion: --- Task Tags Filter
+
+// region:    --- TagBmc
+pub struct TagBmc;
+
+impl DbBmc for TagBmc {
+	const TABLE: &'static str = "tag";
+}
+
+/// Note: Tags are per owner (the ctx user), and the tags of
+///       other owners are reported as not found.
+impl TagBmc {
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		tag_c: TagForCreate,
+	) -> Result<i64> {
+		let tag_i = TagForInsert {
+			owner_id: ctx.user_id(),
+			name: tag_c.name,
+		};
+
+		base::create::<Self, _>(ctx, mm, tag_i).await
+	}
+
+	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Tag> {
+		let tag: Tag = base::get::<Self, _>(ctx, mm, id).await?;
+
+		if tag.owner_id != ctx.user_id() {
+			return Err(Error::EntityNotFound {
+				entity: Self::TABLE,
+				id,
+			});
+		}
+
+		Ok(tag)
+	}
+
+	/// Lists the tags of the ctx user, with their task counts.
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filters: Option<Vec<TagFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<TagWithCount>> {
+		let db = mm.db();
+
+		// -- Build query
+		// Note: Only the tasks visible to the ctx user are counted.
+		let mut join_cond = Condition::all().add(
+			Expr::col((TaskTagIden::Table, TaskTagIden::TagId))
+				.equals((TagIden::Table, TagIden::Id)),
+		);
+		if let Some(visible_ids) = visible_task_ids_query(ctx) {
+			join_cond = join_cond.add(
+				Expr::col((TaskTagIden::Table, TaskTagIden::TaskId))
+					.in_subquery(visible_ids),
+			);
+		}
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.columns(Tag::field_column_refs_with_rel(TagIden::Table))
+			.expr_as(
+				Expr::col((TaskTagIden::Table, TaskTagIden::TaskId)).count(),
+				TagIden::TaskCount,
+			)
+			.left_join(TaskTagIden::Table, join_cond)
+			.and_where(
+				Expr::col((TagIden::Table, TagIden::OwnerId)).eq(ctx.user_id()),
+			)
+			.group_by_col((TagIden::Table, TagIden::Id));
+
+		// condition from filter
+		if let Some(filters) = filters {
+			let filters: FilterGroups = filters.into();
+			let cond: Condition = filters.try_into()?;
+			query.cond_where(cond);
+		}
+
+		// list options
+		let list_options = base::finalize_list_options(list_options)?;
+		list_options.apply_to_sea_query(&mut query);
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let tags = sqlx::query_as_with::<_, TagWithCount, _>(&sql, values)
+			.fetch_all(db)
+			.await?;
+
+		Ok(tags)
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		tag_u: TagForUpdate,
+	) -> Result<()> {
+		Self::get(ctx, mm, id).await?;
+
+		base::update::<Self, _>(ctx, mm, id, tag_u).await
+	}
+
+	/// Deletes the tag, detached from its tasks first (in the same transaction),
+	/// so each task has its `TaskUpdated` event on its `tags`.
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		Self::get(ctx, mm, id).await?;
+
+		let mut wtx = base::begin(ctx, mm).await?;
+
+		// -- Lock the tag
+		// Note: Blocks the concurrent attaches (by their foreign key check),
+		//       so the tasks below are all the tagged tasks.
+		wtx.get_for_update::<Self, Tag>(id).await?;
+
+		// -- Detach the tag
+		let mut query = Query::select();
+		query
+			.from(TaskTagIden::Table)
+			.column(TaskTagIden::TaskId)
+			.and_where(Expr::col(TaskTagIden::TagId).eq(id))
+			.order_by(TaskTagIden::TaskId, Order::Asc);
+		let task_ids: Vec<(i64,)> = wtx.fetch_all(&query).await?;
+		for (task_id,) in task_ids {
+			let task_tag = TaskTagForLink {
+				task_id,
+				tag_id: id,
+			};
+			wtx.unlink::<TaskTagBmc>(task_id, task_tag.all_fields())
+				.await?;
+		}
+
+		wtx.delete::<Self>(id).await?;
+		wtx.commit().await
+	}
+
+	/// Attaches the tag to the task (no-op if already attached).
+	pub async fn attach(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		task_id: i64,
+		tag_id: i64,
+	) -> Result<()> {
+		// -- Check tag and task
+		Self::get(ctx, mm, tag_id).await?;
+		TaskBmc::get(ctx, mm, task_id).await?;
+
+		let task_tag = TaskTagForLink { task_id, tag_id };
+		base::link::<TaskTagBmc, _>(ctx, mm, task_id, task_tag).await?;
+
+		Ok(())
+	}
+
+	/// Detaches the tag from the task (no-op if not attached).
+	pub async fn detach(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		task_id: i64,
+		tag_id: i64,
+	) -> Result<()> {
+		// -- Check tag and task
+		Self::get(ctx, mm, tag_id).await?;
+		TaskBmc::get(ctx, mm, task_id).await?;
+
+		let task_tag = TaskTagForLink { task_id, tag_id };
+		base::unlink::<TaskTagBmc, _>(ctx, mm, task_id, task_tag).await?;
+
+		Ok(())
+	}
+}
+
+/// The `task_tag` relation, written with `base::link` and `base::unlink`.
+pub(in crate::model) struct TaskTagBmc;
+
+impl DbBmc for TaskTagBmc {
+	const TABLE: &'static str = "task_tag";
+
+	fn domain_event(
+		_op: AuditOp,
+		task_id: i64,
+		_diff: &Value,
+	) -> Option<DomainEvent> {
+		Some(DomainEvent::TaskUpdated {
+			task_id,
+			changed_fields: vec!["tags".to_string()],
+		})
+	}
+}
+// endregion: --- TagBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::user::{User, UserBmc};
+	use anyhow::{Context, Result};
+	use serde_json::json;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_with_counts_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_tags = _dev_utils::seed_tags(
+			&ctx,
+			&mm,
+			&[
+				"test_list_with_counts_ok-tag 01",
+				"test_list_with_counts_ok-tag 02",
+			],
+		)
+		.await?;
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&[
+				"test_list_with_counts_ok-task 01",
+				"test_list_with_counts_ok-task 02",
+			],
+		)
+		.await?;
+		for task in fx_tasks.iter() {
+			TagBmc::attach(&ctx, &mm, task.id, fx_tags[0].id).await?;
+		}
+		// attaching twice is a no-op.
+		TagBmc::attach(&ctx, &mm, fx_tasks[0].id, fx_tags[0].id).await?;
+
+		// -- Exec
+		let filters: Vec<TagFilter> = serde_json::from_value(json!([{
+			"name": {"$startsWith": "test_list_with_counts_ok"}
+		}]))?;
+		let tags = TagBmc::list(&ctx, &mm, Some(filters), None).await?;
+
+		// -- Check
+		let counts: Vec<i64> = tags.iter().map(|t| t.task_count).collect();
+		assert_eq!(counts, &[2, 0]);
+
+		// -- Clean
+		for task in fx_tasks.iter() {
+			TaskBmc::delete(&ctx, &mm, task.id).await?;
+		}
+		for tag in fx_tags.iter() {
+			TagBmc::delete(&ctx, &mm, tag.id).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_detach_ok_visible_tasks_only() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let fx_user: User =
+			UserBmc::first_by_username(&Ctx::root_ctx(), &mm, "demo1")
+				.await?
+				.context("Should have user 'demo1'")?;
+		let ctx = Ctx::new(fx_user.id)?;
+		let other_ctx = Ctx::new(9040)?;
+		let fx_tag = _dev_utils::seed_tags(
+			&ctx,
+			&mm,
+			&["test_list_detach_ok_visible_tasks_only"],
+		)
+		.await?
+		.remove(0);
+		let fx_task = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&["test_list_detach_ok_visible_tasks_only-task 01"],
+		)
+		.await?
+		.remove(0);
+		// Note: Tagged while assigned to the user, then not visible once unassigned.
+		let fx_other_task = _dev_utils::seed_tasks(
+			&other_ctx,
+			&mm,
+			&["test_list_detach_ok_visible_tasks_only-task 02"],
+		)
+		.await?
+		.remove(0);
+		TaskBmc::assign(&other_ctx, &mm, fx_other_task.id, fx_user.id).await?;
+		for task_id in [fx_task.id, fx_other_task.id] {
+			TagBmc::attach(&ctx, &mm, task_id, fx_tag.id).await?;
+		}
+		TaskBmc::unassign(&other_ctx, &mm, fx_other_task.id).await?;
+
+		// -- Exec
+		let filters: Vec<TagFilter> = serde_json::from_value(json!([{
+			"id": fx_tag.id
+		}]))?;
+		let tags = TagBmc::list(&ctx, &mm, Some(filters), None).await?;
+		let res_detach =
+			TagBmc::detach(&ctx, &mm, fx_other_task.id, fx_tag.id).await;
+
+		// -- Check
+		let counts: Vec<i64> = tags.iter().map(|t| t.task_count).collect();
+		assert_eq!(counts, &[1]);
+		assert!(
+			matches!(res_detach, Err(Error::TaskNotVisible { id, .. }) if id == fx_other_task.id),
+			"TaskNotVisible not matching"
+		);
+		let (count,): (i64,) = sqlx::query_as(
+			"SELECT count(*) FROM task_tag WHERE task_id = $1 AND tag_id = $2",
+		)
+		.bind(fx_other_task.id)
+		.bind(fx_tag.id)
+		.fetch_one(mm.db())
+		.await?;
+		assert_eq!(count, 1, "the tag should still be attached");
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+		TaskBmc::delete(&other_ctx, &mm, fx_other_task.id).await?;
+		TagBmc::delete(&ctx, &mm, fx_tag.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_delete_ok_task_events() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_tag =
+			_dev_utils::seed_tags(&ctx, &mm, &["test_delete_ok_task_events"])
+				.await?
+				.remove(0);
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&[
+				"test_delete_ok_task_events-task 01",
+				"test_delete_ok_task_events-task 02",
+			],
+		)
+		.await?;
+		for task in fx_tasks.iter() {
+			TagBmc::attach(&ctx, &mm, task.id, fx_tag.id).await?;
+		}
+
+		let (last_event_id,): (i64,) =
+			sqlx::query_as("SELECT COALESCE(MAX(id), 0) FROM outbox")
+				.fetch_one(mm.db())
+				.await?;
+
+		// -- Exec
+		TagBmc::delete(&ctx, &mm, fx_tag.id).await?;
+
+		// -- Check
+		let task_ids: Vec<i64> = sqlx::query_scalar(
+			"SELECT (event->'data'->>'task_id')::bigint FROM outbox
+			 WHERE id > $1 AND event->>'type' = 'TaskUpdated'
+			   AND event->'data'->'changed_fields' = '[\"tags\"]'
+			 ORDER BY id",
+		)
+		.bind(last_event_id)
+		.fetch_all(mm.db())
+		.await?;
+		let fx_task_ids: Vec<i64> = fx_tasks.iter().map(|t| t.id).collect();
+		assert_eq!(task_ids, fx_task_ids);
+		let res = TagBmc::get(&ctx, &mm, fx_tag.id).await;
+		assert!(
+			matches!(res, Err(Error::EntityNotFound { entity: "tag", .. })),
+			"EntityNotFound not matching"
+		);
+
+		// -- Clean
+		for task in fx_tasks.iter() {
+			TaskBmc::delete(&ctx, &mm, task.id).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_get_err_other_owner() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_tag = _dev_utils::seed_tags(&ctx, &mm, &["test_get_err_other_owner"])
+			.await?
+			.remove(0);
+
+		// -- Exec
+		let other_ctx = Ctx::new(fx_tag.owner_id + 1)?;
+		let res = TagBmc::get(&other_ctx, &mm, fx_tag.id).await;
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::EntityNotFound { entity: "tag", .. })),
+			"EntityNotFound not matching"
+		);
+
+		// -- Clean
+		TagBmc::delete(&ctx, &mm, fx_tag.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_err_name_duplicate() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_name = "test_create_err_name_duplicate";
+		let fx_tag = _dev_utils::seed_tags(&ctx, &mm, &[fx_name])
+			.await?
+			.remove(0);
+
+		// -- Exec
+		let tag_c = TagForCreate {
+			name: fx_name.to_string(),
+		};
+		let res = TagBmc::create(&ctx, &mm, tag_c).await;
+
+		// -- Check
+		assert!(
+			matches!(
+				&res,
+				Err(Error::UniqueViolation { entity, field })
+					if entity == "tag" && field == "name"
+			),
+			"UniqueViolation not matching"
+		);
+
+		// -- Clean
+		TagBmc::delete(&ctx, &mm, fx_tag.id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -1,131 +1,3513 @@
 use crate::ctx::Ctx;
-use crate::model::base::{self, DbBmc};
+use crate::model::audit::AuditOp;
+use crate::model::base::{
+	self, time_to_sea_value, BulkTarget, CommonIden, DbBmc, WriteTx,
+};
+use crate::model::outbox::{diff_fields, diff_old_i64, DomainEvent};
+use crate::model::schema::{
+	OpValsBoolSchema, OpValsInt64Schema, OpValsStringSchema, OpValsTimeSchema,
+	Rfc3339Schema,
+};
+use crate::model::tag::{TaskTagBmc, TaskTagForLink, TaskTagsFilter};
+use crate::model::user::{User, UserBmc};
 use crate::model::ModelManager;
-use crate::model::Result;
-use modql::field::Fields;
+use crate::model::{Error, Result};
+use lib_utils::rank;
+use lib_utils::time::{now_utc, parse_utc, Recurrence, Rfc3339};
+use lib_utils::validate::Validate;
+use modql::field::{Field, Fields, HasFields};
 use modql::filter::{
-	FilterNodes, ListOptions, OpValsBool, OpValsInt64, OpValsString,
+	FilterGroups, FilterNode, FilterNodeOptions, FilterNodes, IntoFilterNodes,
+	IntoSeaError, ListOptions, OpValInt64, OpValValue, OpValsBool, OpValsInt64,
+	OpValsString, OpValsValue, SeaResult, ToSeaConditionFnHolder,
 };
+use modql::SIden;
+use schemars::JsonSchema;
+use sea_query::{
+	ColumnRef, Condition, ConditionExpression, Expr, IntoColumnRef, Order,
+	PostgresQueryBuilder, Query, SelectStatement,
+};
+use sea_query_binder::SqlxBinder;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::serde_as;
 use sqlx::FromRow;
+use std::collections::{HashMap, HashSet};
+use time::OffsetDateTime;
+
+// region:    --- Task Types
+/// Note: `Deserialize` for the task revision states (see `TaskRevision`).
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct Task {
+	pub id: i64,
+	pub owner_id: i64,
+	/// The user responsible for the task (see `TaskBmc::assign`).
+	pub assignee_id: Option<i64>,
+
+	pub title: String,
+	pub description: Option<String>,
+	pub priority: TaskPriority,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<Rfc3339Schema>")]
+	pub due_at: Option<OffsetDateTime>,
+	pub status: TaskStatus,
+	/// The RRULE of a recurring task (see `lib_utils::time::Recurrence`),
+	/// e.g., `FREQ=WEEKLY;BYDAY=MO,FR`.
+	pub recurrence: Option<String>,
+
+	/// Derived from the status (i.e., `status == done`).
+	pub done: bool,
+
+	pub parent_id: Option<i64>,
+	/// When true, the task is done when all of its subtasks are done.
+	pub close_with_subtasks: bool,
+
+	/// The manual order key of the task, among the tasks of its owner.
+	pub rank: String,
+}
+
+#[serde_as]
+#[derive(Default, Serialize, Deserialize, JsonSchema, Validate)]
+pub struct TaskForCreate {
+	#[validate(non_empty, trimmed, length(max = 256))]
+	pub title: String,
+	pub description: Option<String>,
+	pub priority: Option<TaskPriority>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<Rfc3339Schema>")]
+	pub due_at: Option<OffsetDateTime>,
+	pub status: Option<TaskStatus>,
+	#[validate(length(max = 256))]
+	pub recurrence: Option<String>,
+	pub parent_id: Option<i64>,
+	pub close_with_subtasks: Option<bool>,
+}
+
+/// The `TaskForCreate` with its owner (from the ctx) and rank.
+#[derive(Fields)]
+struct TaskForInsert {
+	owner_id: i64,
+	rank: String,
+	assignee_id: Option<i64>,
+	title: String,
+	description: Option<String>,
+	#[field(cast_as = "task_priority")]
+	priority: Option<TaskPriority>,
+	due_at: Option<OffsetDateTime>,
+	#[field(cast_as = "task_status")]
+	status: Option<TaskStatus>,
+	recurrence: Option<String>,
+	parent_id: Option<i64>,
+	close_with_subtasks: Option<bool>,
+}
+
+impl TaskForInsert {
+	fn new(task_c: TaskForCreate, owner_id: i64, rank: String) -> Self {
+		TaskForInsert {
+			owner_id,
+			rank,
+			assignee_id: None,
+			title: task_c.title,
+			description: task_c.description,
+			priority: task_c.priority,
+			due_at: task_c.due_at,
+			status: task_c.status,
+			recurrence: task_c.recurrence,
+			parent_id: task_c.parent_id,
+			close_with_subtasks: task_c.close_with_subtasks,
+		}
+	}
+}
+
+/// Note: `done` is kept for compatibility, and is set as the `status`
+///       (`true` as `done`, `false` as `todo`), unless a `status` is given.
+///       The `parent_id` is changed with `TaskBmc::set_parent`.
+#[serde_as]
+#[derive(Fields, Clone, Default, Serialize, Deserialize, JsonSchema, Validate)]
+pub struct TaskForUpdate {
+	#[validate(non_empty, trimmed, length(max = 256))]
+	pub title: Option<String>,
+	pub description: Option<String>,
+	#[field(cast_as = "task_priority")]
+	pub priority: Option<TaskPriority>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<Rfc3339Schema>")]
+	pub due_at: Option<OffsetDateTime>,
+	#[field(cast_as = "task_status")]
+	pub status: Option<TaskStatus>,
+	#[field(skip)]
+	pub done: Option<bool>,
+	/// Note: A recurrence is ended with a `COUNT=1` rule, or by editing
+	///       `this` occurrence (see `TaskBmc::update_occurrence`).
+	#[validate(length(max = 256))]
+	pub recurrence: Option<String>,
+	pub close_with_subtasks: Option<bool>,
+}
+
+/// Written with its `all_fields`, as `None` makes the task a root task.
+#[derive(Fields)]
+struct TaskForSetParent {
+	parent_id: Option<i64>,
+}
+
+/// The `task_watcher` relation row.
+#[derive(Fields)]
+struct TaskWatcherForLink {
+	task_id: i64,
+	user_id: i64,
+}
+
+#[derive(
+	Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize, JsonSchema,
+)]
+#[sqlx(type_name = "task_priority", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TaskPriority {
+	Low,
+	Medium,
+	High,
+	Urgent,
+}
+
+#[derive(
+	Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize, JsonSchema,
+)]
+#[sqlx(type_name = "task_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+	Todo,
+	InProgress,
+	Blocked,
+	Done,
+}
+
+impl TaskStatus {
+	fn as_str(&self) -> &'static str {
+		match self {
+			TaskStatus::Todo => "todo",
+			TaskStatus::InProgress => "in_progress",
+			TaskStatus::Blocked => "blocked",
+			TaskStatus::Done => "done",
+		}
+	}
+
+	/// The status workflow. (Staying in the same status is always allowed)
+	pub fn can_transition_to(&self, to: TaskStatus) -> bool {
+		use TaskStatus::*;
+
+		*self == to
+			|| matches!(
+				(self, to),
+				(Todo, InProgress | Blocked | Done)
+					| (InProgress, Todo | Blocked | Done)
+					| (Blocked, Todo | InProgress)
+					| (Done, Todo | InProgress)
+			)
+	}
+}
+
+impl TaskPriority {
+	fn as_str(&self) -> &'static str {
+		match self {
+			TaskPriority::Low => "low",
+			TaskPriority::Medium => "medium",
+			TaskPriority::High => "high",
+			TaskPriority::Urgent => "urgent",
+		}
+	}
+}
+
+// Note: The sea-query values are bound as text, hence the `cast_as` on the fields.
+impl From<TaskStatus> for sea_query::Value {
+	fn from(val: TaskStatus) -> Self {
+		val.as_str().into()
+	}
+}
+
+impl From<TaskPriority> for sea_query::Value {
+	fn from(val: TaskPriority) -> Self {
+		val.as_str().into()
+	}
+}
+
+impl sea_query::Nullable for TaskStatus {
+	fn null() -> sea_query::Value {
+		sea_query::Value::String(None)
+	}
+}
+
+impl sea_query::Nullable for TaskPriority {
+	fn null() -> sea_query::Value {
+		sea_query::Value::String(None)
+	}
+}
+
+#[derive(FilterNodes, Deserialize, Default, Debug, JsonSchema)]
+pub struct TaskFieldsFilter {
+	#[schemars(with = "Option<OpValsInt64Schema>")]
+	id: Option<OpValsInt64>,
+	#[schemars(with = "Option<OpValsInt64Schema>")]
+	owner_id: Option<OpValsInt64>,
+	#[schemars(with = "Option<OpValsInt64Schema>")]
+	assignee_id: Option<OpValsInt64>,
+
+	#[schemars(with = "Option<OpValsStringSchema>")]
+	title: Option<OpValsString>,
+	#[schemars(with = "Option<OpValsStringSchema>")]
+	description: Option<OpValsString>,
+	#[modql(cast_as = "task_priority")]
+	#[schemars(with = "Option<OpValsStringSchema>")]
+	priority: Option<OpValsString>,
+	/// RFC3339 values, e.g., `{"$gte": "2024-01-01T00:00:00Z", "$lt": "2024-02-01T00:00:00Z"}`
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[schemars(with = "Option<OpValsTimeSchema>")]
+	due_at: Option<OpValsValue>,
+	#[modql(cast_as = "task_status")]
+	#[schemars(with = "Option<OpValsStringSchema>")]
+	status: Option<OpValsString>,
+	#[schemars(with = "Option<OpValsBoolSchema>")]
+	done: Option<OpValsBool>,
+	#[schemars(with = "Option<OpValsInt64Schema>")]
+	parent_id: Option<OpValsInt64>,
+}
+
+/// The task fields filter, extended with the `tags` relation filter,
+/// and the `assigned_to_me` flag (resolved from the ctx).
+/// e.g., `{"done": false, "tags": {"$hasAll": [1000, 1001]}, "assigned_to_me": true}`
+#[derive(Deserialize, Default, Debug, JsonSchema)]
+pub struct TaskFilter {
+	#[serde(flatten)]
+	fields: TaskFieldsFilter,
+
+	tags: Option<TaskTagsFilter>,
+
+	#[serde(default)]
+	assigned_to_me: bool,
+	/// The ctx user id of `assigned_to_me` (see `TaskFilter::resolve_ctx`).
+	#[serde(skip)]
+	assigned_to: Option<i64>,
+	/// The ctx user the tasks must be visible to (see `visible_user_id`).
+	#[serde(skip)]
+	visible_to: Option<i64>,
+}
+
+impl TaskFilter {
+	/// Resolves the ctx dependent filters, which must be done by the `TaskBmc`
+	/// before building the query (otherwise, `assigned_to_me` is ignored,
+	/// and the tasks not visible to the ctx user are not filtered out).
+	fn resolve_ctx(mut self, ctx: &Ctx) -> Self {
+		if self.assigned_to_me {
+			self.assigned_to = Some(ctx.user_id());
+		}
+		self.visible_to = visible_user_id(ctx);
+		self
+	}
+}
+
+/// Note: The filters are OR-ed, so the visibility is in each filter,
+///       with a visibility only filter when there are none.
+fn resolve_filters_ctx(
+	ctx: &Ctx,
+	filters: Option<Vec<TaskFilter>>,
+) -> Option<Vec<TaskFilter>> {
+	let mut filters = match filters {
+		None if visible_user_id(ctx).is_none() => return None,
+		filters => filters.unwrap_or_default(),
+	};
+	if filters.is_empty() {
+		filters.push(TaskFilter::default());
+	}
+
+	Some(filters.into_iter().map(|f| f.resolve_ctx(ctx)).collect())
+}
+
+fn resolve_target_ctx(
+	ctx: &Ctx,
+	target: BulkTarget<Vec<TaskFilter>>,
+) -> BulkTarget<Vec<TaskFilter>> {
+	match target {
+		BulkTarget::Filters(filters) => BulkTarget::Filters(
+			filters.into_iter().map(|f| f.resolve_ctx(ctx)).collect(),
+		),
+		ids => ids,
+	}
+}
+
+impl IntoFilterNodes for TaskFilter {
+	fn filter_nodes(self, context_path: Option<String>) -> Vec<FilterNode> {
+		let mut nodes = self.fields.filter_nodes(context_path);
+		if let Some(tags) = self.tags {
+			nodes.extend(tags.filter_nodes());
+		}
+		if let Some(user_id) = self.assigned_to {
+			nodes.push(("assignee_id", user_id).into());
+		}
+		if let Some(user_id) = self.visible_to {
+			nodes.push(visible_to_node(user_id));
+		}
+		nodes
+	}
+}
+// endregion: --- Task Types
+
+// region:    --- Task Visibility
+/// The user the tasks must be visible to, which is the ctx user,
+/// or `None` for the root ctx (which sees all the tasks).
+fn visible_user_id(ctx: &Ctx) -> Option<i64> {
+	let user_id = ctx.user_id();
+	(user_id != Ctx::root_ctx().user_id()).then_some(user_id)
+}
+
+/// The `FilterNode` on the task `id`, with the `visible_to_cond` of the user.
+fn visible_to_node(user_id: i64) -> FilterNode {
+	FilterNode {
+		context_path: None,
+		name: "id".to_string(),
+		opvals: vec![OpValValue::Eq(Value::from(user_id)).into()],
+		options: FilterNodeOptions::default(),
+		for_sea_condition: Some(
+			ToSeaConditionFnHolder::new(visible_to_sea_cond).into(),
+		),
+	}
+}
+
+fn visible_to_sea_cond(
+	col: &ColumnRef,
+	op_value: OpValValue,
+) -> SeaResult<ConditionExpression> {
+	let user_id = match op_value {
+		OpValValue::Eq(user_id) => user_id.as_i64(),
+		_ => None,
+	}
+	.ok_or_else(|| IntoSeaError::custom("visible to must be a user id"))?;
+
+	Ok(visible_to_cond(col.clone(), user_id).into())
+}
+
+/// The tasks visible to the user, which are the tasks owned by,
+/// assigned to, or watched by the user:
+/// `owner_id = user_id OR assignee_id = user_id
+///  OR id IN (SELECT task_id FROM task_watcher WHERE user_id = user_id)`
+fn visible_to_cond(id_col: impl IntoColumnRef, user_id: i64) -> Condition {
+	let mut watched = Query::select();
+	watched
+		.column(SIden("task_id"))
+		.from(TaskWatcherBmc::table_ref())
+		.and_where(Expr::col(SIden("user_id")).eq(user_id));
+
+	Condition::any()
+		.add(Expr::col(SIden("owner_id")).eq(user_id))
+		.add(Expr::col(SIden("assignee_id")).eq(user_id))
+		.add(Expr::col(id_col).in_subquery(watched))
+}
+
+/// The select of the ids of the tasks visible to the ctx user (see `visible_to_cond`),
+/// for the conditions on the task ids of the other entities (e.g., the tag counts).
+///
+/// Returns `None` for the root ctx, which sees all of the tasks.
+pub(in crate::model) fn visible_task_ids_query(
+	ctx: &Ctx,
+) -> Option<SelectStatement> {
+	let user_id = visible_user_id(ctx)?;
+
+	let mut query = Query::select();
+	query
+		.column(CommonIden::Id)
+		.from(TaskBmc::table_ref())
+		.cond_where(visible_to_cond(CommonIden::Id, user_id));
+
+	Some(query)
+}
+// endregion: --- Task Visibility
+
+// region:    --- Task Search Types
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct TaskSearchHit {
+	#[sqlx(flatten)]
+	#[serde(flatten)]
+	pub task: Task,
+
+	/// The full-text search rank (`ts_rank`), higher for the title matches.
+	pub score: f32,
+	/// The title and description excerpt, with the matching words wrapped
+	/// in `<mark>` tags.
+	/// (Note: The text is not html escaped)
+	pub snippet: String,
+}
+
+/// Supports the web search syntax (e.g., `"quoted phrase"`, `or`, `-excluded`).
+const SEARCH_TS_QUERY: &str = "websearch_to_tsquery('english', $1)";
+const SEARCH_HEADLINE_OPTIONS: &str = "StartSel=<mark>, StopSel=</mark>";
+// endregion: --- Task Search Types
+
+// region:    --- Task Tree Types
+
+/// The max depth of a task in its tree (the root tasks are at depth 0).
+pub const TASK_TREE_DEPTH_MAX: i32 = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TaskNode {
+	#[serde(flatten)]
+	pub task: Task,
+
+	pub children: Vec<TaskNode>,
+}
+
+/// What happens to the subtasks of a deleted task.
+#[derive(
+	Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskDeletePolicy {
+	/// The subtasks are deleted with their parent (the whole subtree).
+	Cascade,
+	/// The subtasks are moved to the parent of the deleted task.
+	#[default]
+	Reparent,
+}
+
+/// Builds the nested nodes from the rows ordered by depth, given the root ids.
+fn build_task_nodes(root_ids: &[i64], tasks: Vec<Task>) -> Vec<TaskNode> {
+	let mut children_by_parent: HashMap<i64, Vec<Task>> = HashMap::new();
+	let mut roots: Vec<Task> = Vec::new();
+	for task in tasks {
+		match task.parent_id {
+			Some(parent_id) if !root_ids.contains(&task.id) => {
+				children_by_parent.entry(parent_id).or_default().push(task)
+			}
+			_ => roots.push(task),
+		}
+	}
+
+	fn into_node(
+		task: Task,
+		children_by_parent: &mut HashMap<i64, Vec<Task>>,
+	) -> TaskNode {
+		let children = children_by_parent
+			.remove(&task.id)
+			.unwrap_or_default()
+			.into_iter()
+			.map(|child| into_node(child, children_by_parent))
+			.collect();
+		TaskNode { task, children }
+	}
+
+	roots
+		.into_iter()
+		.map(|task| into_node(task, &mut children_by_parent))
+		.collect()
+}
+
+// endregion: --- Task Tree Types
+
+// region:    --- Task Recurrence Types
+/// Max number of occurrences returned by `TaskBmc::list_occurrences`.
+pub const TASK_OCCURRENCES_MAX: usize = 1000;
+
+/// Which occurrences of a recurring task an edit applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskOccurrenceScope {
+	/// Only this occurrence, which is detached from the recurrence
+	/// (the next occurrence is created with the previous values).
+	This,
+	/// This occurrence and the next ones
+	/// (as the next occurrence is created from this one).
+	AllFuture,
+}
+
+/// An occurrence of a task for calendar views, being the task itself,
+/// or an upcoming occurrence of a recurring task (not created yet).
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TaskOccurrence {
+	pub task_id: i64,
+	pub title: String,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "Rfc3339Schema")]
+	pub due_at: OffsetDateTime,
+	/// True for the upcoming occurrences.
+	pub expanded: bool,
+}
+
+/// Returns the rule in its canonical form, or the error when invalid.
+fn canonical_recurrence(rule: Option<String>) -> Result<Option<String>> {
+	rule.map(|rule| Ok(rule.parse::<Recurrence>()?.to_string()))
+		.transpose()
+}
+// endregion: --- Task Recurrence Types
+
+// region:    --- Task Status Workflow
+impl TaskForUpdate {
+	/// Sets the compatibility `done` as the `status` (when no `status`).
+	fn with_done_as_status(mut self) -> Self {
+		if let Some(done) = self.done.take() {
+			self.status.get_or_insert(if done {
+				TaskStatus::Done
+			} else {
+				TaskStatus::Todo
+			});
+		}
+		self
+	}
+}
+
+fn check_status_transition(task: &Task, to: TaskStatus) -> Result<()> {
+	if task.status.can_transition_to(to) {
+		Ok(())
+	} else {
+		Err(Error::TaskStatusTransitionInvalid {
+			id: task.id,
+			from: task.status,
+			to,
+		})
+	}
+}
+// endregion: --- Task Status Workflow
+
+// region:    --- TaskBmc
+pub struct TaskBmc;
+
+impl DbBmc for TaskBmc {
+	const TABLE: &'static str = "task";
+	const REVISIONED: bool = true;
+
+	fn domain_event(op: AuditOp, task_id: i64, diff: &Value) -> Option<DomainEvent> {
+		match op {
+			AuditOp::Create => Some(DomainEvent::TaskCreated { task_id }),
+			AuditOp::Update => {
+				let changed_fields = diff_fields(diff);
+				(!changed_fields.is_empty()).then_some(DomainEvent::TaskUpdated {
+					task_id,
+					changed_fields,
+				})
+			}
+			// Note: The owner and assignee are for the event visibility
+			//       (see `TaskBmc::is_event_visible`).
+			AuditOp::Delete => Some(DomainEvent::TaskDeleted {
+				task_id,
+				owner_id: diff_old_i64(diff, "owner_id")?,
+				assignee_id: diff_old_i64(diff, "assignee_id"),
+			}),
+		}
+	}
+}
+
+impl TaskBmc {
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		mut task_c: TaskForCreate,
+	) -> Result<i64> {
+		task_c.recurrence = canonical_recurrence(task_c.recurrence)?;
+
+		let owner_id = ctx.user_id();
+		let mut wtx = base::begin(ctx, mm).await?;
+		if let Some(parent_id) = task_c.parent_id {
+			Self::check_parent_tx(ctx, &mut wtx, None, parent_id, 0).await?;
+		}
+		let rank = Self::next_ranks(&mut wtx, owner_id, 1).await?.remove(0);
+		let task_i = TaskForInsert::new(task_c, owner_id, rank);
+		let id = wtx.create::<Self>(task_i.not_none_fields()).await?;
+		wtx.commit().await?;
+
+		Ok(id)
+	}
+
+	pub async fn create_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		mut tasks_c: Vec<TaskForCreate>,
+	) -> Result<Vec<i64>> {
+		for task_c in tasks_c.iter_mut() {
+			task_c.recurrence = canonical_recurrence(task_c.recurrence.take())?;
+		}
+		let mut parent_ids: Vec<i64> =
+			tasks_c.iter().filter_map(|t| t.parent_id).collect();
+		parent_ids.sort_unstable();
+		parent_ids.dedup();
+
+		let owner_id = ctx.user_id();
+		let mut wtx = base::begin(ctx, mm).await?;
+		for parent_id in parent_ids {
+			Self::check_parent_tx(ctx, &mut wtx, None, parent_id, 0).await?;
+		}
+		let ranks = Self::next_ranks(&mut wtx, owner_id, tasks_c.len()).await?;
+		let rows = tasks_c
+			.into_iter()
+			.zip(ranks)
+			.map(|(task_c, rank)| {
+				TaskForInsert::new(task_c, owner_id, rank).not_none_fields()
+			})
+			.collect();
+		let ids = wtx.create_many::<Self>(rows).await?;
+		wtx.commit().await?;
+
+		Ok(ids)
+	}
+
+	/// Returns the task, when visible to the ctx user
+	/// (otherwise, `Error::TaskNotVisible`).
+	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Task> {
+		Self::check_visible(ctx, mm, id).await?;
+
+		base::get::<Self, _>(ctx, mm, id).await
+	}
+
+	pub async fn list_by_ids(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		ids: &[i64],
+	) -> Result<Vec<Task>> {
+		base::list_by_ids::<Self, _>(ctx, mm, ids).await
+	}
+
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filters: Option<Vec<TaskFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<Task>> {
+		let filters = resolve_filters_ctx(ctx, filters);
+
+		base::list::<Self, _, _>(ctx, mm, filters, list_options).await
+	}
+
+	/// Returns the task with only the `fields` (see `base::get_projected`).
+	pub async fn get_projected(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		fields: &[String],
+	) -> Result<Value> {
+		Self::check_visible(ctx, mm, id).await?;
+
+		base::get_projected::<Self, Task>(ctx, mm, id, fields).await
+	}
+
+	/// Same as `list`, but with only the `fields` of each task.
+	pub async fn list_projected(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filters: Option<Vec<TaskFilter>>,
+		list_options: Option<ListOptions>,
+		fields: &[String],
+	) -> Result<Vec<Value>> {
+		let filters = resolve_filters_ctx(ctx, filters);
+
+		base::list_projected::<Self, Task, _>(ctx, mm, filters, list_options, fields)
+			.await
+	}
+
+	/// Full-text search on the task title and description,
+	/// which can be combined with the `TaskFilter`.
+	/// When `list_options.order_bys` is not given, the hits are ordered by score.
+	pub async fn search(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		search: &str,
+		filters: Option<Vec<TaskFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<TaskSearchHit>> {
+		let db = mm.db();
+
+		// -- Build query
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.columns(Task::field_column_refs())
+			.expr_as(
+				Expr::cust_with_values(
+					format!("ts_rank(search_tsv, {SEARCH_TS_QUERY})"),
+					[search],
+				),
+				SIden("score"),
+			)
+			.expr_as(
+				Expr::cust_with_values(
+					format!(
+						"ts_headline('english', concat_ws(' ', title, description), {SEARCH_TS_QUERY}, '{SEARCH_HEADLINE_OPTIONS}')"
+					),
+					[search],
+				),
+				SIden("snippet"),
+			);
+
+		// condition from search and filter
+		let mut cond = Condition::all().add(Expr::cust_with_values(
+			format!("search_tsv @@ {SEARCH_TS_QUERY}"),
+			[search],
+		));
+		if let Some(filters) = resolve_filters_ctx(ctx, filters) {
+			let filters: FilterGroups = filters.into();
+			cond = cond.add(Condition::try_from(filters)?);
+		}
+		query.cond_where(cond);
+
+		// list options (ordered by score by default)
+		let order_by_rank = list_options
+			.as_ref()
+			.is_none_or(|options| options.order_bys.is_none());
+		let mut list_options = base::finalize_list_options(list_options)?;
+		if order_by_rank {
+			list_options.order_bys = None;
+			query
+				.order_by(SIden("score"), Order::Desc)
+				.order_by(CommonIden::Id, Order::Asc);
+		}
+		list_options.apply_to_sea_query(&mut query);
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let hits = sqlx::query_as_with::<_, TaskSearchHit, _>(&sql, values)
+			.fetch_all(db)
+			.await?;
+
+		Ok(hits)
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		task_u: TaskForUpdate,
+	) -> Result<()> {
+		Self::update_many(ctx, mm, BulkTarget::Ids(vec![id]), task_u).await?;
+
+		Ok(())
+	}
+
+	pub async fn update_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		target: BulkTarget<Vec<TaskFilter>>,
+		task_u: TaskForUpdate,
+	) -> Result<Vec<i64>> {
+		let target = resolve_target_ctx(ctx, target);
+
+		let mut wtx = base::begin(ctx, mm).await?;
+		let ids = Self::update_tx(ctx, &mut wtx, target, task_u).await?;
+		wtx.commit().await?;
+
+		Ok(ids)
+	}
+
+	/// Updates the targeted tasks in the write transaction, with their visibility
+	/// and status transitions checked on the locked tasks (so that they cannot
+	/// change in between), the completion rolled up to their ancestors, and the
+	/// next occurrences of the recurring tasks done by this update.
+	/// Returns the updated ids.
+	async fn update_tx(
+		ctx: &Ctx,
+		wtx: &mut WriteTx,
+		target: BulkTarget<Vec<TaskFilter>>,
+		task_u: TaskForUpdate,
+	) -> Result<Vec<i64>> {
+		let mut task_u = task_u.with_done_as_status();
+		task_u.recurrence = canonical_recurrence(task_u.recurrence)?;
+		let status = task_u.status;
+
+		Self::update_fields_tx(ctx, wtx, target, status, task_u.not_none_fields())
+			.await
+	}
+
+	/// Same as `update_tx`, with the fields as given (e.g., with null values),
+	/// and the `status` of the fields (if any).
+	async fn update_fields_tx(
+		ctx: &Ctx,
+		wtx: &mut WriteTx,
+		target: BulkTarget<Vec<TaskFilter>>,
+		status: Option<TaskStatus>,
+		fields: Fields,
+	) -> Result<Vec<i64>> {
+		// -- Lock the tasks, and check the status transitions
+		let tasks = Self::list_visible_for_update(ctx, wtx, target).await?;
+		let mut done_now_ids = Vec::new();
+		if let Some(status) = status {
+			for task in tasks.iter() {
+				check_status_transition(task, status)?;
+				if status == TaskStatus::Done && !task.done {
+					done_now_ids.push(task.id);
+				}
+			}
+		}
+
+		// -- Update the locked tasks
+		let ids: Vec<i64> = tasks.iter().map(|task| task.id).collect();
+		let target = BulkTarget::<Vec<TaskFilter>>::Ids(ids);
+		let ids = wtx.update_many::<Self, _>(target, fields).await?;
+
+		// -- Roll up the completion
+		if status == Some(TaskStatus::Done) {
+			for id in ids.iter() {
+				Self::roll_up_completion(wtx, *id).await?;
+			}
+		}
+
+		// -- Create the next occurrences
+		for id in done_now_ids {
+			let task: Task = wtx.get_for_update::<Self, _>(id).await?;
+			Self::create_next_occurrence(wtx, &task).await?;
+		}
+
+		Ok(ids)
+	}
+
+	/// Deletes the task with the default `TaskDeletePolicy`.
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		Self::delete_with_policy(ctx, mm, id, TaskDeletePolicy::default()).await
+	}
+
+	pub async fn delete_with_policy(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		policy: TaskDeletePolicy,
+	) -> Result<()> {
+		let target = BulkTarget::Ids(vec![id]);
+		Self::delete_many_with_policy(ctx, mm, target, policy).await?;
+
+		Ok(())
+	}
+
+	/// Deletes the targeted tasks with the default `TaskDeletePolicy`.
+	pub async fn delete_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		target: BulkTarget<Vec<TaskFilter>>,
+	) -> Result<Vec<i64>> {
+		Self::delete_many_with_policy(ctx, mm, target, TaskDeletePolicy::default())
+			.await
+	}
+
+	/// Deletes the targeted tasks (all or nothing), with the policy applied to
+	/// their subtasks, and returns the deleted ids.
+	///
+	/// Note: With `Reparent`, the subtasks are moved to their closest ancestor
+	///       not deleted (e.g., when a task and its parent are both deleted).
+	pub async fn delete_many_with_policy(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		target: BulkTarget<Vec<TaskFilter>>,
+		policy: TaskDeletePolicy,
+	) -> Result<Vec<i64>> {
+		let target = resolve_target_ctx(ctx, target);
+
+		let mut wtx = base::begin(ctx, mm).await?;
+		let tasks = Self::list_visible_for_update(ctx, &mut wtx, target).await?;
+		let ids: Vec<i64> = tasks.iter().map(|task| task.id).collect();
+
+		// -- Reparent the subtasks (cascade is done by the db)
+		if policy == TaskDeletePolicy::Reparent {
+			let deleted_parent_ids: HashMap<i64, Option<i64>> =
+				tasks.iter().map(|task| (task.id, task.parent_id)).collect();
+			let mut deleted_ids_by_ancestor: HashMap<Option<i64>, Vec<i64>> =
+				HashMap::new();
+			for task in tasks.iter() {
+				let mut ancestor_id = task.parent_id;
+				while let Some(parent_id) =
+					ancestor_id.and_then(|id| deleted_parent_ids.get(&id).copied())
+				{
+					ancestor_id = parent_id;
+				}
+				deleted_ids_by_ancestor
+					.entry(ancestor_id)
+					.or_default()
+					.push(task.id);
+			}
+
+			for (ancestor_id, deleted_ids) in deleted_ids_by_ancestor {
+				let children = BulkTarget::Filters(vec![TaskFieldsFilter {
+					id: Some(OpValInt64::NotIn(ids.clone()).into()),
+					parent_id: Some(OpValInt64::In(deleted_ids).into()),
+					..Default::default()
+				}]);
+				let parent = TaskForSetParent {
+					parent_id: ancestor_id,
+				};
+				wtx.update_many::<Self, _>(children, parent.all_fields())
+					.await?;
+			}
+		}
+
+		// -- Delete the tasks
+		let target = BulkTarget::<Vec<TaskFieldsFilter>>::Ids(ids);
+		let ids = wtx.delete_many::<Self, _>(target).await?;
+		wtx.commit().await?;
+
+		Ok(ids)
+	}
+}
+// endregion: --- TaskBmc
+
+// region:    --- TaskBmc Tree
+impl TaskBmc {
+	/// Moves the task under the parent, or makes it a root task when `None`.
+	pub async fn set_parent(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		parent_id: Option<i64>,
+	) -> Result<()> {
+		let mut wtx = base::begin(ctx, mm).await?;
+		Self::get_visible_for_update(ctx, &mut wtx, id).await?;
+
+		// -- Check parent, cycle and depth
+		if let Some(parent_id) = parent_id {
+			let height = Self::subtree_height_tx(&mut wtx, id).await?;
+			Self::check_parent_tx(ctx, &mut wtx, Some(id), parent_id, height)
+				.await?;
+		}
+
+		// Note: `all_fields`, as a `None` parent must be written (as NULL).
+		let parent = TaskForSetParent { parent_id };
+		wtx.update::<Self>(id, parent.all_fields()).await?;
+		wtx.commit().await
+	}
+
+	/// Returns the tree of the root task, or the trees of all the root tasks when `None`.
+	///
+	/// Note: Only the tasks visible to the ctx user are in the trees, so the
+	///       subtasks of a task not visible are not either, and when `None`,
+	///       the visible tasks with a parent not visible are roots.
+	pub async fn list_tree(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		root_id: Option<i64>,
+	) -> Result<Vec<TaskNode>> {
+		let db = mm.db();
+		if let Some(root_id) = root_id {
+			Self::check_visible(ctx, mm, root_id).await?;
+		}
+
+		// -- Build query
+		let columns = Task::field_names().join(", ");
+		let t_columns = Task::field_names()
+			.iter()
+			.map(|name| format!("t.{name}"))
+			.collect::<Vec<_>>()
+			.join(", ");
+		let root_cond = if root_id.is_some() {
+			"id = $2"
+		} else {
+			"parent_id IS NULL"
+		};
+		let sql = format!(
+			"
+WITH RECURSIVE tree AS (
+	SELECT {columns}, 0 AS depth FROM task WHERE {root_cond}
+	UNION ALL
+	SELECT {t_columns}, tree.depth + 1 FROM task t
+	JOIN tree ON t.parent_id = tree.id
+	WHERE tree.depth < $1
+)
+SELECT {columns} FROM tree ORDER BY depth, id"
+		);
+
+		// -- Exec query
+		let mut query = sqlx::query_as::<_, Task>(&sql).bind(TASK_TREE_DEPTH_MAX);
+		if let Some(root_id) = root_id {
+			query = query.bind(root_id);
+		}
+		let tasks = query.fetch_all(db).await?;
+		let tasks = Self::retain_visible(ctx, mm, tasks).await?;
+
+		// -- Check & build result
+		if let Some(root_id) = root_id {
+			if tasks.is_empty() {
+				return Err(Error::EntityNotFound {
+					entity: Self::TABLE,
+					id: root_id,
+				});
+			}
+		}
+		let root_ids: Vec<i64> = match root_id {
+			Some(root_id) => vec![root_id],
+			None => {
+				let ids: HashSet<i64> = tasks.iter().map(|t| t.id).collect();
+				tasks
+					.iter()
+					.filter(|t| t.parent_id.is_none_or(|p| !ids.contains(&p)))
+					.map(|t| t.id)
+					.collect()
+			}
+		};
+
+		Ok(build_task_nodes(&root_ids, tasks))
+	}
+
+	/// Checks that the parent is visible to the ctx user, that it is not in the
+	/// subtree of the moved task `id` (if any), and that a subtree of `height`
+	/// fits under it.
+	///
+	/// Note: The parent and its ancestors are locked (`FOR UPDATE`) until the
+	///       end of the transaction, so that a concurrent move in the chain
+	///       cannot make a cycle, or a tree over the max depth.
+	async fn check_parent_tx(
+		ctx: &Ctx,
+		wtx: &mut WriteTx,
+		id: Option<i64>,
+		parent_id: i64,
+		height: i32,
+	) -> Result<()> {
+		let parent = Self::get_visible_for_update(ctx, wtx, parent_id).await?;
+
+		// -- Walk the ancestors (the parent being at depth 0 when a root task)
+		let mut ancestor = Some(parent);
+		let mut parent_depth = -1;
+		while let Some(task) = ancestor {
+			if Some(task.id) == id {
+				return Err(Error::TaskParentCycle {
+					id: task.id,
+					parent_id,
+				});
+			}
+			parent_depth += 1;
+			// Note: The chain can only be over the max when written before it.
+			if parent_depth > TASK_TREE_DEPTH_MAX {
+				break;
+			}
+			ancestor = match task.parent_id {
+				Some(ancestor_id) => {
+					Some(wtx.get_for_update::<Self, Task>(ancestor_id).await?)
+				}
+				None => None,
+			};
+		}
+
+		// -- Check depth
+		let depth = parent_depth + 1 + height;
+		if depth > TASK_TREE_DEPTH_MAX {
+			return Err(Error::TaskTreeDepthOverMax {
+				max: TASK_TREE_DEPTH_MAX,
+				actual: depth,
+			});
+		}
+
+		Ok(())
+	}
+
+	/// Returns the height of the subtree of the task (0 without subtasks),
+	/// read in the write transaction (up to over the max depth).
+	async fn subtree_height_tx(wtx: &mut WriteTx, id: i64) -> Result<i32> {
+		let mut height = 0;
+		let mut level_ids = vec![id];
+		while height <= TASK_TREE_DEPTH_MAX {
+			// -- Build query
+			let mut query = Query::select();
+			query
+				.from(Self::table_ref())
+				.column(CommonIden::Id)
+				.and_where(Expr::col(SIden("parent_id")).is_in(level_ids));
+
+			// -- Exec query
+			let child_ids: Vec<(i64,)> = wtx.fetch_all(&query).await?;
+			if child_ids.is_empty() {
+				break;
+			}
+			height += 1;
+			level_ids = child_ids.into_iter().map(|(id,)| id).collect();
+		}
+
+		Ok(height)
+	}
+
+	/// Marks as done the ancestors flagged with `close_with_subtasks`
+	/// once all of their subtasks are done, in the write transaction
+	/// (with each ancestor locked before its subtasks are checked).
+	async fn roll_up_completion(wtx: &mut WriteTx, id: i64) -> Result<()> {
+		let mut task: Task = wtx.get_for_update::<Self, _>(id).await?;
+
+		while let Some(parent_id) = task.parent_id {
+			let parent: Task = wtx.get_for_update::<Self, _>(parent_id).await?;
+			if !parent.close_with_subtasks
+				|| !task.done
+				|| parent.done
+				|| !parent.status.can_transition_to(TaskStatus::Done)
+			{
+				break;
+			}
+
+			let mut query = Query::select();
+			query
+				.from(Self::table_ref())
+				.expr(Expr::cust("bool_and(done)"))
+				.and_where(Expr::col(SIden("parent_id")).eq(parent_id));
+			let (all_done,): (Option<bool>,) = wtx.fetch_one(&query).await?;
+			if all_done != Some(true) {
+				break;
+			}
+
+			let task_u = TaskForUpdate {
+				status: Some(TaskStatus::Done),
+				..Default::default()
+			};
+			wtx.update::<Self>(parent_id, task_u.not_none_fields())
+				.await?;
+
+			task = wtx.get_for_update::<Self, _>(parent_id).await?;
+		}
+
+		Ok(())
+	}
+}
+// endregion: --- TaskBmc Tree
+
+// region:    --- TaskBmc Assignment
+impl TaskBmc {
+	/// Assigns the task to the user (who can then see the task).
+	///
+	/// Note: Only the task owner or its current assignee can (re)assign
+	///       or unassign the task (e.g., not its watchers).
+	pub async fn assign(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		assignee_id: i64,
+	) -> Result<()> {
+		Self::set_assignee(ctx, mm, id, Some(assignee_id)).await
+	}
+
+	pub async fn unassign(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		Self::set_assignee(ctx, mm, id, None).await
+	}
+
+	/// Adds the ctx user to the task watchers (watching twice is a no-op).
+	pub async fn watch(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		Self::get(ctx, mm, id).await?;
+
+		let watcher = TaskWatcherForLink {
+			task_id: id,
+			user_id: ctx.user_id(),
+		};
+		base::link::<TaskWatcherBmc, _>(ctx, mm, id, watcher).await?;
+
+		Ok(())
+	}
+
+	/// Removes the ctx user from the task watchers (if watching).
+	pub async fn unwatch(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		Self::get(ctx, mm, id).await?;
+
+		let watcher = TaskWatcherForLink {
+			task_id: id,
+			user_id: ctx.user_id(),
+		};
+		base::unlink::<TaskWatcherBmc, _>(ctx, mm, id, watcher).await?;
+
+		Ok(())
+	}
+
+	pub async fn list_watcher_ids(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> Result<Vec<i64>> {
+		Self::get(ctx, mm, id).await?;
+
+		let user_ids = sqlx::query_scalar(
+			"SELECT user_id FROM task_watcher WHERE task_id = $1 ORDER BY user_id",
+		)
+		.bind(id)
+		.fetch_all(mm.db())
+		.await?;
+
+		Ok(user_ids)
+	}
+
+	/// Checks that the ctx user can see the task (see `visible_to_cond`),
+	/// or returns `Error::TaskNotVisible` (`Error::EntityNotFound` when none).
+	pub(in crate::model) async fn check_visible(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> Result<()> {
+		let Some(user_id) = visible_user_id(ctx) else {
+			return Ok(());
+		};
+
+		// -- Build query
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.column(CommonIden::Id)
+			.and_where(Expr::col(CommonIden::Id).eq(id))
+			.cond_where(visible_to_cond(CommonIden::Id, user_id));
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let visible = sqlx::query_as_with::<_, (i64,), _>(&sql, values)
+			.fetch_optional(mm.db())
+			.await?
+			.is_some();
+
+		// -- Check result (not found, or not visible)
+		if visible {
+			return Ok(());
+		}
+		base::get::<Self, Task>(ctx, mm, id).await?;
+
+		Err(Error::TaskNotVisible { id, user_id })
+	}
+
+	/// Returns the task locked in the write transaction (see `WriteTx::get_for_update`),
+	/// when visible to the ctx user (otherwise, `Error::TaskNotVisible`).
+	async fn get_visible_for_update(
+		ctx: &Ctx,
+		wtx: &mut WriteTx,
+		id: i64,
+	) -> Result<Task> {
+		let target = BulkTarget::Ids(vec![id]);
+
+		Ok(Self::list_visible_for_update(ctx, wtx, target)
+			.await?
+			.remove(0))
+	}
+
+	/// Returns the targeted tasks locked in the write transaction
+	/// (see `WriteTx::list_for_update`), when all visible to the ctx user
+	/// (otherwise, `Error::TaskNotVisible` for the first one not visible).
+	///
+	/// Note: The filters are already limited to the visible tasks
+	///       (see `resolve_target_ctx`), but not the ids.
+	async fn list_visible_for_update(
+		ctx: &Ctx,
+		wtx: &mut WriteTx,
+		target: BulkTarget<Vec<TaskFilter>>,
+	) -> Result<Vec<Task>> {
+		let tasks: Vec<Task> = wtx.list_for_update::<Self, _, _>(target).await?;
+		let Some(user_id) = visible_user_id(ctx) else {
+			return Ok(tasks);
+		};
+
+		// -- Build query
+		let ids: Vec<i64> = tasks.iter().map(|t| t.id).collect();
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.column(CommonIden::Id)
+			.and_where(Expr::col(CommonIden::Id).is_in(ids))
+			.cond_where(visible_to_cond(CommonIden::Id, user_id));
+
+		// -- Exec query
+		let visible_ids: HashSet<i64> = wtx
+			.fetch_all::<(i64,)>(&query)
+			.await?
+			.into_iter()
+			.map(|(id,)| id)
+			.collect();
+
+		// -- Check result
+		if let Some(task) = tasks.iter().find(|t| !visible_ids.contains(&t.id)) {
+			return Err(Error::TaskNotVisible {
+				id: task.id,
+				user_id,
+			});
+		}
+
+		Ok(tasks)
+	}
+
+	/// Returns the tasks visible to the ctx user (in the same order).
+	async fn retain_visible(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		tasks: Vec<Task>,
+	) -> Result<Vec<Task>> {
+		let Some(user_id) = visible_user_id(ctx) else {
+			return Ok(tasks);
+		};
+
+		// -- Build query
+		let ids: Vec<i64> = tasks.iter().map(|t| t.id).collect();
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.column(CommonIden::Id)
+			.and_where(Expr::col(CommonIden::Id).is_in(ids))
+			.cond_where(visible_to_cond(CommonIden::Id, user_id));
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let visible_ids: HashSet<i64> =
+			sqlx::query_as_with::<_, (i64,), _>(&sql, values)
+				.fetch_all(mm.db())
+				.await?
+				.into_iter()
+				.map(|(id,)| id)
+				.collect();
+
+		Ok(tasks
+			.into_iter()
+			.filter(|t| visible_ids.contains(&t.id))
+			.collect())
+	}
+
+	/// Returns true when the ctx user can see the task of the event
+	/// (e.g., to filter the task changes sent to a user).
+	///
+	/// Note: The task of a `TaskDeleted` cannot be read anymore, so the event
+	///       is visible to its owner and assignee (but not to its watchers,
+	///       which are deleted with the task).
+	///       A `TaskRanksRebalanced` is only visible to the owner.
+	pub async fn is_event_visible(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		event: &DomainEvent,
+	) -> Result<bool> {
+		let Some(user_id) = visible_user_id(ctx) else {
+			return Ok(true);
+		};
+
+		match event {
+			DomainEvent::TaskDeleted {
+				owner_id,
+				assignee_id,
+				..
+			} => Ok(*owner_id == user_id || *assignee_id == Some(user_id)),
+			DomainEvent::TaskRanksRebalanced { owner_id } => {
+				Ok(*owner_id == user_id)
+			}
+			DomainEvent::TaskCreated { task_id }
+			| DomainEvent::TaskUpdated { task_id, .. } => {
+				match Self::check_visible(ctx, mm, *task_id).await {
+					Ok(()) => Ok(true),
+					Err(
+						Error::EntityNotFound { .. } | Error::TaskNotVisible { .. },
+					) => Ok(false),
+					Err(ex) => Err(ex),
+				}
+			}
+		}
+	}
+
+	/// Sets the `assignee_id` (not in the `TaskForUpdate`), when the ctx user
+	/// is the owner or the current assignee of the locked task.
+	async fn set_assignee(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		assignee_id: Option<i64>,
+	) -> Result<()> {
+		// -- Check assignee
+		if let Some(assignee_id) = assignee_id {
+			UserBmc::get::<User>(ctx, mm, assignee_id).await?;
+		}
+
+		// -- Check task
+		let mut wtx = base::begin(ctx, mm).await?;
+		let task = Self::get_visible_for_update(ctx, &mut wtx, id).await?;
+		if let Some(user_id) = visible_user_id(ctx) {
+			if task.owner_id != user_id && task.assignee_id != Some(user_id) {
+				return Err(Error::AccessDenied {
+					entity: Self::TABLE,
+					id,
+				});
+			}
+		}
+
+		let value: sea_query::Value = assignee_id.into();
+		let fields =
+			Fields::new(vec![Field::new(SIden("assignee_id"), value.into())]);
+		wtx.update::<Self>(id, fields).await?;
+		wtx.commit().await
+	}
+}
+/// The `task_watcher` relation, written with `base::link` and `base::unlink`.
+struct TaskWatcherBmc;
+
+impl DbBmc for TaskWatcherBmc {
+	const TABLE: &'static str = "task_watcher";
+
+	fn domain_event(
+		_op: AuditOp,
+		task_id: i64,
+		_diff: &Value,
+	) -> Option<DomainEvent> {
+		Some(DomainEvent::TaskUpdated {
+			task_id,
+			changed_fields: vec!["watchers".to_string()],
+		})
+	}
+}
+// endregion: --- TaskBmc Assignment
+
+// region:    --- TaskBmc Recurrence
+impl TaskBmc {
+	/// Updates an occurrence of a recurring task, with the edit applying
+	/// to this occurrence only, or to all the future ones.
+	/// (For a non recurring task, both are the same as `TaskBmc::update`)
+	pub async fn update_occurrence(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		task_u: TaskForUpdate,
+		scope: TaskOccurrenceScope,
+	) -> Result<()> {
+		let mut wtx = base::begin(ctx, mm).await?;
+		let task = Self::get_visible_for_update(ctx, &mut wtx, id).await?;
+
+		let mut task_u = task_u;
+		if scope == TaskOccurrenceScope::This && task.recurrence.is_some() {
+			// -- Detach this occurrence, and continue the recurrence without the edit
+			// Note: All in the transaction, so nothing is detached when the update fails.
+			let recurrence = Option::<String>::None;
+			let fields = Fields::new(vec![Field::new(
+				SIden("recurrence"),
+				recurrence.into(),
+			)]);
+			wtx.update::<Self>(id, fields).await?;
+			Self::create_next_occurrence(&mut wtx, &task).await?;
+
+			task_u.recurrence = None;
+		}
+
+		Self::update_tx(ctx, &mut wtx, BulkTarget::Ids(vec![id]), task_u).await?;
+		wtx.commit().await
+	}
+
+	/// Returns the occurrences due in `[from, to)` of the tasks not done,
+	/// with the upcoming occurrences of the recurring tasks, ordered by `due_at`.
+	pub async fn list_occurrences(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		from: OffsetDateTime,
+		to: OffsetDateTime,
+		filters: Option<Vec<TaskFilter>>,
+	) -> Result<Vec<TaskOccurrence>> {
+		// -- Build query
+		let mut cond = Condition::all()
+			.add(Expr::col(SIden("done")).eq(false))
+			.add(Expr::col(SIden("due_at")).lt(to))
+			.add(
+				Condition::any()
+					.add(Expr::col(SIden("due_at")).gte(from))
+					.add(Expr::col(SIden("recurrence")).is_not_null()),
+			);
+		if let Some(filters) = resolve_filters_ctx(ctx, filters) {
+			let filters: FilterGroups = filters.into();
+			cond = cond.add(Condition::try_from(filters)?);
+		}
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.columns(Task::field_column_refs())
+			.cond_where(cond)
+			.order_by(SIden("due_at"), Order::Asc)
+			.limit(TASK_OCCURRENCES_MAX as u64);
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let tasks = sqlx::query_as_with::<_, Task, _>(&sql, values)
+			.fetch_all(mm.db())
+			.await?;
+
+		// -- Expand the recurring tasks
+		let mut occurrences = Vec::new();
+		for task in tasks {
+			let Some(due_at) = task.due_at else {
+				continue;
+			};
+			let due_ats = match task.recurrence.as_deref() {
+				Some(rule) => rule.parse::<Recurrence>()?.occurrences(
+					due_at,
+					from,
+					to,
+					TASK_OCCURRENCES_MAX,
+				),
+				None => vec![due_at],
+			};
+			occurrences.extend(due_ats.into_iter().map(|occurrence_due_at| {
+				TaskOccurrence {
+					task_id: task.id,
+					title: task.title.clone(),
+					due_at: occurrence_due_at,
+					expanded: occurrence_due_at != due_at,
+				}
+			}));
+		}
+		occurrences
+			.sort_by_key(|occurrence| (occurrence.due_at, occurrence.task_id));
+		occurrences.truncate(TASK_OCCURRENCES_MAX);
+
+		Ok(occurrences)
+	}
+
+	/// Creates the next occurrence of the recurring task, due at the next date
+	/// of its rule from its `due_at` (or from now when none), and with the same
+	/// owner, assignee and tags. Returns `None` when there is no next occurrence.
+	async fn create_next_occurrence(
+		wtx: &mut WriteTx,
+		task: &Task,
+	) -> Result<Option<i64>> {
+		let Some(rule) = task.recurrence.as_deref() else {
+			return Ok(None);
+		};
+		let recurrence: Recurrence = rule.parse()?;
+		let occurrence = task.due_at.unwrap_or_else(now_utc);
+		let Some((due_at, recurrence)) = recurrence.next_occurrence(occurrence)
+		else {
+			return Ok(None);
+		};
+
+		let rank = Self::next_ranks(wtx, task.owner_id, 1).await?.remove(0);
+		let task_i = TaskForInsert {
+			owner_id: task.owner_id,
+			rank,
+			assignee_id: task.assignee_id,
+			title: task.title.clone(),
+			description: task.description.clone(),
+			priority: Some(task.priority),
+			due_at: Some(due_at),
+			status: None,
+			recurrence: Some(recurrence.to_string()),
+			parent_id: task.parent_id,
+			close_with_subtasks: Some(task.close_with_subtasks),
+		};
+		let mut query = Query::select();
+		query
+			.from(TaskTagBmc::table_ref())
+			.column(SIden("tag_id"))
+			.and_where(Expr::col(SIden("task_id")).eq(task.id));
+		let tag_ids: Vec<(i64,)> = wtx.fetch_all(&query).await?;
+
+		let id = wtx.create::<Self>(task_i.not_none_fields()).await?;
+		for (tag_id,) in tag_ids {
+			let task_tag = TaskTagForLink {
+				task_id: id,
+				tag_id,
+			};
+			wtx.link::<TaskTagBmc>(id, task_tag.all_fields()).await?;
+		}
+
+		Ok(Some(id))
+	}
+}
+// endregion: --- TaskBmc Recurrence
+
+// region:    --- TaskBmc History
+
+/// The task state after one of its writes, the revision 1 being the created state.
+#[serde_as]
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct TaskRevision {
+	pub task_id: i64,
+	pub revision: i32,
+	pub actor_id: i64,
+
+	#[sqlx(json)]
+	pub state: Task,
+
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "Rfc3339Schema")]
+	pub ctime: OffsetDateTime,
+}
+
+impl TaskBmc {
+	/// Lists the revisions of the task, oldest first.
+	pub async fn list_revisions(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> Result<Vec<TaskRevision>> {
+		Self::get(ctx, mm, id).await?;
+
+		let revisions = sqlx::query_as(
+			"SELECT task_id, revision, actor_id, state, ctime FROM task_revision
+			 WHERE task_id = $1 ORDER BY revision",
+		)
+		.bind(id)
+		.fetch_all(mm.db())
+		.await?;
+
+		Ok(revisions)
+	}
+
+	/// Reverts the task content (i.e., the `TaskForUpdate` fields) to its state
+	/// at the revision, as a single new update (and so, as a new revision).
+	///
+	/// Note: The status workflow still applies (see `TaskStatus::can_transition_to`),
+	///       and the tree, rank, and assignee are not reverted.
+	pub async fn revert(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		revision: i32,
+	) -> Result<()> {
+		let mut wtx = base::begin(ctx, mm).await?;
+		Self::get_visible_for_update(ctx, &mut wtx, id).await?;
+
+		let mut query = Query::select();
+		query
+			.from(SIden("task_revision"))
+			.column(SIden("state"))
+			.and_where(Expr::col(SIden("task_id")).eq(id))
+			.and_where(Expr::col(SIden("revision")).eq(revision));
+		let state: Option<(sqlx::types::Json<Task>,)> =
+			wtx.fetch_optional(&query).await?;
+		let Some((sqlx::types::Json(state),)) = state else {
+			return Err(Error::TaskRevisionNotFound { id, revision });
+		};
+
+		// Note: `all_fields`, as the fields which were null must be written (as NULL).
+		let task_u = TaskForUpdate {
+			title: Some(state.title),
+			description: state.description,
+			priority: Some(state.priority),
+			due_at: state.due_at,
+			status: Some(state.status),
+			done: None,
+			recurrence: state.recurrence,
+			close_with_subtasks: Some(state.close_with_subtasks),
+		};
+		let (status, fields) = (task_u.status, task_u.all_fields());
+
+		let target = BulkTarget::Ids(vec![id]);
+		Self::update_fields_tx(ctx, &mut wtx, target, status, fields).await?;
+		wtx.commit().await
+	}
+}
+// endregion: --- TaskBmc History
+
+// region:    --- TaskBmc Rank
+
+/// The rank key length above which the ranks of the owner are rebalanced.
+const RANK_LEN_MAX: usize = 32;
+
+/// The `WriteTx` scope of the ranks of an owner (see `TaskBmc::lock_ranks`).
+const RANK_SCOPE: &str = "task_rank";
+
+/// Where to move a task, relative to an anchor task of the same owner.
+#[derive(Debug, Clone, Copy)]
+pub enum TaskMove {
+	Before(i64),
+	After(i64),
+}
+
+/// Note: The ranks are scoped per owner (as tasks do not belong to projects),
+///       and are only read and written with the owner ranks locked.
+impl TaskBmc {
+	/// Moves the task right before or after the anchor task.
+	/// Only the moved task rank is rewritten (unless the ranks get rebalanced).
+	pub async fn move_task(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		to: TaskMove,
+	) -> Result<()> {
+		let (TaskMove::Before(anchor_id) | TaskMove::After(anchor_id)) = to;
+
+		// -- Check the tasks
+		let task = Self::get(ctx, mm, id).await?;
+		let anchor = Self::get(ctx, mm, anchor_id).await?;
+		if anchor.owner_id != task.owner_id {
+			return Err(Error::TaskRankScopeMismatch { id, anchor_id });
+		}
+		if anchor_id == id {
+			return Ok(());
+		}
+
+		// -- Compute the rank (rebalance first if needed)
+		let mut wtx = base::begin(ctx, mm).await?;
+		Self::lock_ranks(&mut wtx, task.owner_id).await?;
+		let (before, after) = Self::move_bounds(&mut wtx, id, anchor_id, to).await?;
+		let rank = match rank::key_between(before.as_deref(), after.as_deref()) {
+			Ok(rank) if rank.len() <= RANK_LEN_MAX => rank,
+			_ => {
+				Self::rebalance_ranks(&mut wtx, task.owner_id).await?;
+				let (before, after) =
+					Self::move_bounds(&mut wtx, id, anchor_id, to).await?;
+				rank::key_between(before.as_deref(), after.as_deref())?
+			}
+		};
+
+		let fields = Fields::new(vec![Field::new(SIden("rank"), rank.into())]);
+		wtx.update::<Self>(id, fields).await?;
+		wtx.commit().await
+	}
+
+	/// Locks the ranks of the owner tasks until the end of the transaction,
+	/// so the ranks read (e.g., the move neighbors) stay the same until written.
+	async fn lock_ranks(wtx: &mut WriteTx, owner_id: i64) -> Result<()> {
+		wtx.lock_scope(RANK_SCOPE, owner_id).await
+	}
+
+	/// Returns the `(before, after)` ranks of the move, which are the anchor rank
+	/// and the rank of its neighbor (excluding the moved task).
+	///
+	/// Note: The owner ranks must be locked (see `TaskBmc::lock_ranks`).
+	async fn move_bounds(
+		wtx: &mut WriteTx,
+		id: i64,
+		anchor_id: i64,
+		to: TaskMove,
+	) -> Result<(Option<String>, Option<String>)> {
+		let anchor: Task = wtx.get_for_update::<Self, _>(anchor_id).await?;
+		let (rank_cond, order) = match to {
+			TaskMove::Before(_) => (
+				Expr::col(SIden("rank")).lt(anchor.rank.as_str()),
+				Order::Desc,
+			),
+			TaskMove::After(_) => (
+				Expr::col(SIden("rank")).gt(anchor.rank.as_str()),
+				Order::Asc,
+			),
+		};
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.column(SIden("rank"))
+			.and_where(Expr::col(SIden("owner_id")).eq(anchor.owner_id))
+			.and_where(rank_cond)
+			.and_where(Expr::col(CommonIden::Id).ne(id))
+			.order_by(SIden("rank"), order)
+			.limit(1);
+		let neighbor_rank: Option<(String,)> = wtx.fetch_optional(&query).await?;
+		let neighbor_rank = neighbor_rank.map(|(rank,)| rank);
+
+		match to {
+			TaskMove::Before(_) => Ok((neighbor_rank, Some(anchor.rank))),
+			TaskMove::After(_) => Ok((Some(anchor.rank), neighbor_rank)),
+		}
+	}
+
+	/// Returns `count` ranks after the last rank of the owner,
+	/// with the owner ranks locked until the end of the transaction.
+	async fn next_ranks(
+		wtx: &mut WriteTx,
+		owner_id: i64,
+		count: usize,
+	) -> Result<Vec<String>> {
+		Self::lock_ranks(wtx, owner_id).await?;
+		let mut last_rank = Self::last_rank(wtx, owner_id).await?;
+		if last_rank.as_ref().is_some_and(|r| r.len() >= RANK_LEN_MAX) {
+			Self::rebalance_ranks(wtx, owner_id).await?;
+			last_rank = Self::last_rank(wtx, owner_id).await?;
+		}
+
+		let mut ranks: Vec<String> = Vec::with_capacity(count);
+		for _ in 0..count {
+			let before = ranks.last().or(last_rank.as_ref());
+			ranks.push(rank::key_between(before.map(String::as_str), None)?);
+		}
+
+		Ok(ranks)
+	}
+
+	async fn last_rank(wtx: &mut WriteTx, owner_id: i64) -> Result<Option<String>> {
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.expr(Expr::col(SIden("rank")).max())
+			.and_where(Expr::col(SIden("owner_id")).eq(owner_id));
+		let (last_rank,): (Option<String>,) = wtx.fetch_one(&query).await?;
+
+		Ok(last_rank)
+	}
+
+	/// Rewrites the ranks of the owner tasks, evenly spaced, in their current order,
+	/// with a single update (and a single `TaskRanksRebalanced` event).
+	///
+	/// Note: The owner ranks must be locked (see `TaskBmc::lock_ranks`).
+	async fn rebalance_ranks(wtx: &mut WriteTx, owner_id: i64) -> Result<()> {
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.column(CommonIden::Id)
+			.and_where(Expr::col(SIden("owner_id")).eq(owner_id))
+			.order_by(SIden("rank"), Order::Asc)
+			.order_by(CommonIden::Id, Order::Asc);
+		let ids: Vec<(i64,)> = wtx.fetch_all(&query).await?;
+		let ranks = rank::evenly_spaced_keys(ids.len());
+		let rows = ids.into_iter().map(|(id,)| id).zip(ranks).collect();
+
+		let event = DomainEvent::TaskRanksRebalanced { owner_id };
+		wtx.update_column_rows::<Self>(RANK_SCOPE, "rank", rows, owner_id, event)
+			.await
+	}
+}
+
+// endregion: --- TaskBmc Rank
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::notify::TaskChangeListener;
+	use crate::model::tag::TagBmc;
+	use crate::model::Error;
+	use anyhow::{Context, Result};
+	use lib_utils::time::format_time;
+	use serde_json::json;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_title = "test_create_ok title";
+
+		// -- Exec
+		let task_c = TaskForCreate {
+			title: fx_title.to_string(),
+			..Default::default()
+		};
+		let id = TaskBmc::create(&ctx, &mm, task_c).await?;
+
+		// -- Check
+		let task = TaskBmc::get(&ctx, &mm, id).await?;
+		assert_eq!(task.title, fx_title);
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_many_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles =
+			&["test_create_many_ok-task 01", "test_create_many_ok-task 02"];
+
+		// -- Exec
+		let tasks_c = fx_titles
+			.iter()
+			.map(|title| TaskForCreate {
+				title: title.to_string(),
+				..Default::default()
+			})
+			.collect();
+		let ids = TaskBmc::create_many(&ctx, &mm, tasks_c).await?;
+
+		// -- Check
+		let tasks = TaskBmc::list_by_ids(&ctx, &mm, &ids).await?;
+		assert_eq!(tasks.len(), 2);
+		assert_eq!(tasks[0].title, fx_titles[0]);
+		assert_eq!(tasks[1].title, fx_titles[1]);
+
+		// -- Clean
+		TaskBmc::delete_many(&ctx, &mm, BulkTarget::Ids(ids)).await?;
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_validate_err_title() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_cases = [
+			("a".repeat(10_000), "length"),
+			("".to_string(), "non_empty"),
+			(" title".to_string(), "trimmed"),
+		];
+
+		for (fx_title, fx_rule) in fx_cases {
+			// -- Exec
+			let task_c = TaskForCreate {
+				title: fx_title,
+				..Default::default()
+			};
+			let err = task_c.validate().expect_err("Should fail");
+
+			// -- Check
+			assert_eq!(err.field, "title");
+			assert_eq!(err.rule, fx_rule);
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_get_err_not_found() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_id = 100;
+
+		// -- Exec
+		let res = TaskBmc::get(&ctx, &mm, fx_id).await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::EntityNotFound {
+					entity: "task",
+					id: 100
+				})
+			),
+			"EntityNotFound not matching"
+		);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_get_projected_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_title = "test_get_projected_ok title";
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, &[fx_title])
+			.await?
+			.remove(0);
+		let fx_fields = ["title".to_string(), "status".to_string()];
+
+		// -- Exec
+		let task = TaskBmc::get_projected(&ctx, &mm, fx_task.id, &fx_fields).await?;
+
+		// -- Check
+		assert_eq!(task, json!({"title": fx_title, "status": "todo"}));
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_get_projected_err_field_unknown() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_fields = ["title".to_string(), "search_tsv".to_string()];
+
+		// -- Exec
+		let res = TaskBmc::get_projected(&ctx, &mm, 100, &fx_fields).await;
+
+		// -- Check
+		assert!(
+			matches!(&res, Err(Error::ProjectionFieldUnknown { field }) if field == "search_tsv"),
+			"ProjectionFieldUnknown not matching"
+		);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_projected_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles = &[
+			"test_list_projected_ok-task 01",
+			"test_list_projected_ok-task 02",
+		];
+		let fx_tasks = _dev_utils::seed_tasks(&ctx, &mm, fx_titles).await?;
+		let fx_fields = ["id".to_string(), "title".to_string()];
+
+		// -- Exec
+		let filters: Vec<TaskFilter> = serde_json::from_value(json!([{
+			"title": {"$startsWith": "test_list_projected_ok-task"}
+		}]))?;
+		let list_options = serde_json::from_value(json!({
+			"order_bys": "!id"
+		}))?;
+		let tasks = TaskBmc::list_projected(
+			&ctx,
+			&mm,
+			Some(filters),
+			Some(list_options),
+			&fx_fields,
+		)
+		.await?;
+
+		// -- Check
+		let expected: Vec<Value> = fx_tasks
+			.iter()
+			.rev()
+			.map(|t| json!({"id": t.id, "title": t.title}))
+			.collect();
+		assert_eq!(tasks, expected);
+
+		// -- Clean
+		for task in fx_tasks.iter() {
+			TaskBmc::delete(&ctx, &mm, task.id).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_all_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles = &["test_list_all_ok-task 01", "test_list_all_ok-task 02"];
+		_dev_utils::seed_tasks(&ctx, &mm, fx_titles).await?;
+
+		// -- Exec
+		let tasks = TaskBmc::list(&ctx, &mm, None, None).await?;
+
+		// -- Check
+		let tasks: Vec<Task> = tasks
+			.into_iter()
+			.filter(|t| t.title.starts_with("test_list_all_ok-task"))
+			.collect();
+		assert_eq!(tasks.len(), 2, "number of seeded tasks.");
+
+		// -- Clean
+		for task in tasks.iter() {
+			TaskBmc::delete(&ctx, &mm, task.id).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_by_filter_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles = &[
+			"test_list_by_filter_ok-task 01.a",
+			"test_list_by_filter_ok-task 01.b",
+			"test_list_by_filter_ok-task 02.a",
+			"test_list_by_filter_ok-task 02.b",
+			"test_list_by_filter_ok-task 03",
+		];
+		_dev_utils::seed_tasks(&ctx, &mm, fx_titles).await?;
+
+		// -- Exec
+		let filters: Vec<TaskFilter> = serde_json::from_value(json!([
+			{
+				"title": {
+					"$endsWith": ".a",
+					"$containsAny": ["01", "02"]
+				}
+			},
+			{
+				"title": {"$contains": "03"}
+			}
+		]))?;
+		let list_options = serde_json::from_value(json!({
+			"order_bys": "!id"
+		}))?;
+		let tasks =
+			TaskBmc::list(&ctx, &mm, Some(filters), Some(list_options)).await?;
+
+		// -- Check
+		assert_eq!(tasks.len(), 3);
+		assert!(tasks[0].title.ends_with("03"));
+		assert!(tasks[1].title.ends_with("02.a"));
+		assert!(tasks[2].title.ends_with("01.a"));
+
+		// -- Clean
+		let tasks = TaskBmc::list(
+			&ctx,
+			&mm,
+			Some(serde_json::from_value(json!([{
+				"title": {"$startsWith": "test_list_by_filter_ok"}
+			}]))?),
+			None,
+		)
+		.await?;
+		assert_eq!(tasks.len(), 5);
+		for task in tasks.iter() {
+			TaskBmc::delete(&ctx, &mm, task.id).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_search_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles = &[
+			"test_search_ok dragon eggs",
+			"test_search_ok dragons hatching from dragon eggs",
+			"test_search_ok knight armor",
+		];
+		let fx_tasks = _dev_utils::seed_tasks(&ctx, &mm, fx_titles).await?;
+
+		// -- Exec
+		let hits = TaskBmc::search(&ctx, &mm, "dragon", None, None).await?;
+
+		// -- Check
+		let hits: Vec<TaskSearchHit> = hits
+			.into_iter()
+			.filter(|h| h.task.title.starts_with("test_search_ok"))
+			.collect();
+		assert_eq!(hits.len(), 2);
+		assert_eq!(
+			hits[0].task.id, fx_tasks[1].id,
+			"more matches, higher score"
+		);
+		assert!(hits[0].score > hits[1].score);
+		assert!(hits[1].snippet.contains("<mark>dragon</mark>"));
+
+		// -- Exec (with filter)
+		let filters: Vec<TaskFilter> = serde_json::from_value(json!([{
+			"id": {"$eq": fx_tasks[0].id}
+		}]))?;
+		let hits =
+			TaskBmc::search(&ctx, &mm, "dragon eggs", Some(filters), None).await?;
+
+		// -- Check
+		assert_eq!(hits.len(), 1);
+		assert_eq!(hits[0].task.id, fx_tasks[0].id);
+
+		// -- Clean
+		for task in fx_tasks.iter() {
+			TaskBmc::delete(&ctx, &mm, task.id).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_search_ok_description() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&[
+				"test_search_ok_description wyvern",
+				"test_search_ok_description feed",
+			],
+		)
+		.await?;
+		let task_u = TaskForUpdate {
+			description: Some("Feed the wyvern before the night".to_string()),
+			..Default::default()
+		};
+		TaskBmc::update(&ctx, &mm, fx_tasks[1].id, task_u).await?;
+
+		// -- Exec
+		let hits = TaskBmc::search(&ctx, &mm, "wyvern", None, None).await?;
+
+		// -- Check
+		let hits: Vec<TaskSearchHit> = hits
+			.into_iter()
+			.filter(|h| h.task.title.starts_with("test_search_ok_description"))
+			.collect();
+		assert_eq!(hits.len(), 2);
+		assert_eq!(hits[0].task.id, fx_tasks[0].id, "title match, higher score");
+		assert_eq!(hits[1].task.id, fx_tasks[1].id);
+		assert!(hits[0].score > hits[1].score);
+		assert!(hits[1].snippet.contains("<mark>wyvern</mark>"));
+
+		// -- Clean
+		for task in fx_tasks.iter() {
+			TaskBmc::delete(&ctx, &mm, task.id).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_by_tags_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&[
+				"test_list_by_tags_ok-task 01",
+				"test_list_by_tags_ok-task 02",
+				"test_list_by_tags_ok-task 03",
+			],
+		)
+		.await?;
+		let fx_tags = _dev_utils::seed_tags(
+			&ctx,
+			&mm,
+			&["test_list_by_tags_ok-tag 01", "test_list_by_tags_ok-tag 02"],
+		)
+		.await?;
+		// task 01: tag 01 & tag 02, task 02: tag 01, task 03: none
+		TagBmc::attach(&ctx, &mm, fx_tasks[0].id, fx_tags[0].id).await?;
+		TagBmc::attach(&ctx, &mm, fx_tasks[0].id, fx_tags[1].id).await?;
+		TagBmc::attach(&ctx, &mm, fx_tasks[1].id, fx_tags[0].id).await?;
+
+		// -- Exec (has any)
+		let filters: Vec<TaskFilter> = serde_json::from_value(json!([{
+			"title": {"$startsWith": "test_list_by_tags_ok"},
+			"tags": {"$hasAny": [fx_tags[0].id, fx_tags[1].id]}
+		}]))?;
+		let tasks = TaskBmc::list(&ctx, &mm, Some(filters), None).await?;
+
+		// -- Check
+		let ids: Vec<i64> = tasks.iter().map(|t| t.id).collect();
+		assert_eq!(ids, &[fx_tasks[0].id, fx_tasks[1].id]);
+
+		// -- Exec (has all)
+		let filters: Vec<TaskFilter> = serde_json::from_value(json!([{
+			"tags": {"$hasAll": [fx_tags[0].id, fx_tags[1].id]}
+		}]))?;
+		let tasks = TaskBmc::list(&ctx, &mm, Some(filters), None).await?;
+
+		// -- Check
+		let ids: Vec<i64> = tasks.iter().map(|t| t.id).collect();
+		assert_eq!(ids, &[fx_tasks[0].id]);
+
+		// -- Exec (has all of none)
+		let filters: Vec<TaskFilter> = serde_json::from_value(json!([{
+			"title": {"$startsWith": "test_list_by_tags_ok"},
+			"tags": {"$hasAll": []}
+		}]))?;
+		let tasks = TaskBmc::list(&ctx, &mm, Some(filters), None).await?;
+
+		// -- Check
+		let ids: Vec<i64> = tasks.iter().map(|t| t.id).collect();
+		let fx_ids: Vec<i64> = fx_tasks.iter().map(|t| t.id).collect();
+		assert_eq!(ids, fx_ids);
+
+		// -- Clean
+		for task in fx_tasks.iter() {
+			TaskBmc::delete(&ctx, &mm, task.id).await?;
+		}
+		for tag in fx_tags.iter() {
+			TagBmc::delete(&ctx, &mm, tag.id).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_title = "test_update_ok - task 01";
+		let fx_title_new = "test_update_ok - task 01 - new";
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, &[fx_title])
+			.await?
+			.remove(0);
+
+		// -- Exec
+		TaskBmc::update(
+			&ctx,
+			&mm,
+			fx_task.id,
+			TaskForUpdate {
+				title: Some(fx_title_new.to_string()),
+				..Default::default()
+			},
+		)
+		.await?;
+
+		// -- Check
+		let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+		assert_eq!(task.title, fx_title_new);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_status_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, &["test_update_status_ok"])
+			.await?
+			.remove(0);
+
+		// -- Exec
+		let task_u: TaskForUpdate = serde_json::from_value(json!({
+			"status": "in_progress",
+			"priority": "urgent",
+			"due_at": "2024-03-01T12:00:00Z",
+			"description": "Some **markdown**"
+		}))?;
+		TaskBmc::update(&ctx, &mm, fx_task.id, task_u).await?;
+
+		// -- Check
+		let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+		assert_eq!(task.status, TaskStatus::InProgress);
+		assert_eq!(task.priority, TaskPriority::Urgent);
+		assert_eq!(
+			task.due_at.map(lib_utils::time::format_time).as_deref(),
+			Some("2024-03-01T12:00:00Z")
+		);
+		assert!(!task.done);
+
+		// -- Exec (done as status)
+		let task_u = TaskForUpdate {
+			done: Some(true),
+			..Default::default()
+		};
+		TaskBmc::update(&ctx, &mm, fx_task.id, task_u).await?;
+
+		// -- Check
+		let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+		assert_eq!(task.status, TaskStatus::Done);
+		assert!(task.done);
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_err_status_transition() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_task = TaskBmc::create(
+			&ctx,
+			&mm,
+			TaskForCreate {
+				title: "test_update_err_status_transition".to_string(),
+				status: Some(TaskStatus::Blocked),
+				..Default::default()
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let task_u = TaskForUpdate {
+			status: Some(TaskStatus::Done),
+			..Default::default()
+		};
+		let res = TaskBmc::update(&ctx, &mm, fx_task, task_u.clone()).await;
+		let res_many =
+			TaskBmc::update_many(&ctx, &mm, BulkTarget::Ids(vec![fx_task]), task_u)
+				.await;
+
+		// -- Check
+		for res in [res.map(|_| ()), res_many.map(|_| ())] {
+			assert!(
+				matches!(
+					res,
+					Err(Error::TaskStatusTransitionInvalid {
+						from: TaskStatus::Blocked,
+						to: TaskStatus::Done,
+						..
+					})
+				),
+				"TaskStatusTransitionInvalid not matching"
+			);
+		}
+		let task = TaskBmc::get(&ctx, &mm, fx_task).await?;
+		assert_eq!(task.status, TaskStatus::Blocked);
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_by_due_at_range_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&[
+				"test_list_by_due_at_range_ok-task 01",
+				"test_list_by_due_at_range_ok-task 02",
+				"test_list_by_due_at_range_ok-task 03",
+			],
+		)
+		.await?;
+		for (task, due_at) in fx_tasks.iter().zip([
+			"2024-01-10T00:00:00Z",
+			"2024-01-20T00:00:00Z",
+			"2024-02-10T00:00:00Z",
+		]) {
+			let task_u: TaskForUpdate =
+				serde_json::from_value(json!({ "due_at": due_at }))?;
+			TaskBmc::update(&ctx, &mm, task.id, task_u).await?;
+		}
+
+		// -- Exec
+		let filters: Vec<TaskFilter> = serde_json::from_value(json!([{
+			"title": {"$startsWith": "test_list_by_due_at_range_ok"},
+			"due_at": {"$gte": "2024-01-15T00:00:00Z", "$lt": "2024-03-01T00:00:00Z"},
+			"status": {"$in": ["todo", "in_progress"]},
+		}]))?;
+		let tasks = TaskBmc::list(&ctx, &mm, Some(filters), None).await?;
+
+		// -- Check
+		let ids: Vec<i64> = tasks.iter().map(|t| t.id).collect();
+		assert_eq!(ids, &[fx_tasks[1].id, fx_tasks[2].id]);
+
+		// -- Clean
+		for task in fx_tasks.iter() {
+			TaskBmc::delete(&ctx, &mm, task.id).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_tree_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&[
+				"test_list_tree_ok-root",
+				"test_list_tree_ok-child 01",
+				"test_list_tree_ok-child 02",
+				"test_list_tree_ok-grandchild 01",
+			],
+		)
+		.await?;
+		let [root, child_01, child_02, grandchild_01] =
+			[0, 1, 2, 3].map(|i| fx_tasks[i].id);
+		TaskBmc::set_parent(&ctx, &mm, child_01, Some(root)).await?;
+		TaskBmc::set_parent(&ctx, &mm, child_02, Some(root)).await?;
+		TaskBmc::set_parent(&ctx, &mm, grandchild_01, Some(child_01)).await?;
+
+		// -- Exec
+		let nodes = TaskBmc::list_tree(&ctx, &mm, Some(root)).await?;
+
+		// -- Check
+		assert_eq!(nodes.len(), 1);
+		let root_node = &nodes[0];
+		assert_eq!(root_node.task.id, root);
+		let child_ids: Vec<i64> =
+			root_node.children.iter().map(|n| n.task.id).collect();
+		assert_eq!(child_ids, &[child_01, child_02]);
+		assert_eq!(root_node.children[0].children[0].task.id, grandchild_01);
+
+		// -- Exec (delete with reparent)
+		TaskBmc::delete_with_policy(&ctx, &mm, child_01, TaskDeletePolicy::Reparent)
+			.await?;
+
+		// -- Check
+		let grandchild = TaskBmc::get(&ctx, &mm, grandchild_01).await?;
+		assert_eq!(grandchild.parent_id, Some(root));
+
+		// -- Exec (delete with cascade)
+		TaskBmc::delete_with_policy(&ctx, &mm, root, TaskDeletePolicy::Cascade)
+			.await?;
+
+		// -- Check
+		let tasks =
+			TaskBmc::list_by_ids(&ctx, &mm, &[root, child_02, grandchild_01])
+				.await?;
+		assert!(tasks.is_empty(), "subtree should be deleted");
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_set_parent_err_cycle() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&[
+				"test_set_parent_err_cycle-parent",
+				"test_set_parent_err_cycle-child",
+			],
+		)
+		.await?;
+		let (parent, child) = (fx_tasks[0].id, fx_tasks[1].id);
+		TaskBmc::set_parent(&ctx, &mm, child, Some(parent)).await?;
+
+		// -- Exec
+		let res = TaskBmc::set_parent(&ctx, &mm, parent, Some(child)).await;
+		let res_self = TaskBmc::set_parent(&ctx, &mm, parent, Some(parent)).await;
+
+		// -- Check
+		for res in [res, res_self] {
+			assert!(
+				matches!(res, Err(Error::TaskParentCycle { id, .. }) if id == parent),
+				"TaskParentCycle not matching"
+			);
+		}
+
+		// -- Clean
+		TaskBmc::delete_with_policy(&ctx, &mm, parent, TaskDeletePolicy::Cascade)
+			.await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_set_parent_err_parent_not_visible() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::new(9039)?;
+		let other_ctx = Ctx::new(9040)?;
+		let fx_parent = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&["test_create_set_parent_err_parent_not_visible-parent"],
+		)
+		.await?
+		.remove(0);
+		let fx_task = _dev_utils::seed_tasks(
+			&other_ctx,
+			&mm,
+			&["test_create_set_parent_err_parent_not_visible-task"],
+		)
+		.await?
+		.remove(0);
+		let fx_task_c = || TaskForCreate {
+			title: "test_create_set_parent_err_parent_not_visible-child".to_string(),
+			parent_id: Some(fx_parent.id),
+			..Default::default()
+		};
+
+		// -- Exec
+		let res_create = TaskBmc::create(&other_ctx, &mm, fx_task_c()).await;
+		let res_create_many =
+			TaskBmc::create_many(&other_ctx, &mm, vec![fx_task_c()]).await;
+		let res_set_parent =
+			TaskBmc::set_parent(&other_ctx, &mm, fx_task.id, Some(fx_parent.id))
+				.await;
+
+		// -- Check
+		for res in [
+			res_create.map(|_| ()),
+			res_create_many.map(|_| ()),
+			res_set_parent,
+		] {
+			assert!(
+				matches!(res, Err(Error::TaskNotVisible { id, user_id: 9040 }) if id == fx_parent.id),
+				"TaskNotVisible not matching"
+			);
+		}
+		let task = TaskBmc::get(&other_ctx, &mm, fx_task.id).await?;
+		assert_eq!(task.parent_id, None);
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_parent.id).await?;
+		TaskBmc::delete(&other_ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_err_depth_over_max() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let mut fx_ids = vec![];
+		let mut parent_id = None;
+		for _ in 0..=TASK_TREE_DEPTH_MAX {
+			let id = TaskBmc::create(
+				&ctx,
+				&mm,
+				TaskForCreate {
+					title: "test_create_err_depth_over_max".to_string(),
+					parent_id,
+					..Default::default()
+				},
+			)
+			.await?;
+			fx_ids.push(id);
+			parent_id = Some(id);
+		}
+
+		// -- Exec
+		let res = TaskBmc::create(
+			&ctx,
+			&mm,
+			TaskForCreate {
+				title: "test_create_err_depth_over_max - too deep".to_string(),
+				parent_id,
+				..Default::default()
+			},
+		)
+		.await;
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::TaskTreeDepthOverMax { .. })),
+			"TaskTreeDepthOverMax not matching"
+		);
+
+		// -- Clean
+		TaskBmc::delete_with_policy(&ctx, &mm, fx_ids[0], TaskDeletePolicy::Cascade)
+			.await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_done_rolls_up_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_parent = TaskBmc::create(
+			&ctx,
+			&mm,
+			TaskForCreate {
+				title: "test_update_done_rolls_up_ok-parent".to_string(),
+				close_with_subtasks: Some(true),
+				..Default::default()
+			},
+		)
+		.await?;
+		let fx_children = TaskBmc::create_many(
+			&ctx,
+			&mm,
+			["child 01", "child 02"]
+				.map(|title| TaskForCreate {
+					title: format!("test_update_done_rolls_up_ok-{title}"),
+					parent_id: Some(fx_parent),
+					..Default::default()
+				})
+				.into(),
+		)
+		.await?;
+		let task_u = TaskForUpdate {
+			status: Some(TaskStatus::Done),
+			..Default::default()
+		};
+
+		// -- Exec & Check
+		TaskBmc::update(&ctx, &mm, fx_children[0], task_u.clone()).await?;
+		let parent = TaskBmc::get(&ctx, &mm, fx_parent).await?;
+		assert!(!parent.done, "one subtask still open");
+
+		TaskBmc::update(&ctx, &mm, fx_children[1], task_u).await?;
+		let parent = TaskBmc::get(&ctx, &mm, fx_parent).await?;
+		assert!(parent.done, "all subtasks done");
+
+		// -- Clean
+		TaskBmc::delete_with_policy(&ctx, &mm, fx_parent, TaskDeletePolicy::Cascade)
+			.await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_move_task_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::new(9031)?;
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&[
+				"test_move_task_ok-task 01",
+				"test_move_task_ok-task 02",
+				"test_move_task_ok-task 03",
+			],
+		)
+		.await?;
+		let [id_01, id_02, id_03] = [0, 1, 2].map(|i| fx_tasks[i].id);
+
+		// -- Exec
+		TaskBmc::move_task(&ctx, &mm, id_03, TaskMove::Before(id_01)).await?;
+		TaskBmc::move_task(&ctx, &mm, id_01, TaskMove::After(id_02)).await?;
+
+		// -- Check
+		let ids = list_ids_by_rank(&ctx, &mm).await?;
+		assert_eq!(ids, &[id_03, id_02, id_01]);
+
+		// -- Exec (always moving right after the same task, to force rebalancing)
+		for i in 0..300 {
+			let id = if i % 2 == 0 { id_01 } else { id_02 };
+			TaskBmc::move_task(&ctx, &mm, id, TaskMove::After(id_03)).await?;
+		}
+
+		// -- Check
+		let ids = list_ids_by_rank(&ctx, &mm).await?;
+		assert_eq!(ids, &[id_03, id_02, id_01]);
+		let tasks = TaskBmc::list_by_ids(&ctx, &mm, &ids).await?;
+		assert!(tasks.iter().all(|t| t.rank.len() <= RANK_LEN_MAX));
+
+		// -- Clean
+		for id in ids {
+			TaskBmc::delete(&ctx, &mm, id).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_rebalance_ranks_ok_single_event() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::new(9032)?;
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&[
+				"test_rebalance_ranks_ok_single_event-task 01",
+				"test_rebalance_ranks_ok_single_event-task 02",
+				"test_rebalance_ranks_ok_single_event-task 03",
+			],
+		)
+		.await?;
+		let fx_ids: Vec<i64> = fx_tasks.iter().map(|t| t.id).collect();
+		let count_sql = "SELECT
+			 (SELECT count(*) FROM task_revision WHERE task_id = ANY($1)),
+			 (SELECT count(*) FROM audit_event WHERE entity_id = ANY($1)),
+			 (SELECT count(*) FROM audit_event WHERE entity = 'task_rank' AND entity_id = $2)";
+		let counts_before: (i64, i64, i64) = sqlx::query_as(count_sql)
+			.bind(&fx_ids)
+			.bind(ctx.user_id())
+			.fetch_one(mm.db())
+			.await?;
+
+		// -- Exec
+		let mut wtx = base::begin(&ctx, &mm).await?;
+		TaskBmc::lock_ranks(&mut wtx, ctx.user_id()).await?;
+		TaskBmc::rebalance_ranks(&mut wtx, ctx.user_id()).await?;
+		wtx.commit().await?;
+
+		// -- Check
+		let ids = list_ids_by_rank(&ctx, &mm).await?;
+		assert_eq!(ids, fx_ids, "Order should be kept");
+		let (revisions, task_audits, rank_audits): (i64, i64, i64) =
+			sqlx::query_as(count_sql)
+				.bind(&fx_ids)
+				.bind(ctx.user_id())
+				.fetch_one(mm.db())
+				.await?;
+		assert_eq!(revisions, counts_before.0, "No revisions");
+		assert_eq!(task_audits, counts_before.1, "No per task audit events");
+		assert_eq!(rank_audits, counts_before.2 + 1, "A single audit event");
+		let events: Vec<(Value,)> =
+			sqlx::query_as("SELECT event FROM outbox ORDER BY id DESC LIMIT 2")
+				.fetch_all(mm.db())
+				.await?;
+		let events = events
+			.into_iter()
+			.map(|(event,)| serde_json::from_value(event))
+			.collect::<serde_json::Result<Vec<DomainEvent>>>()?;
+		assert_eq!(
+			events[0],
+			DomainEvent::TaskRanksRebalanced {
+				owner_id: ctx.user_id()
+			}
+		);
+		assert!(
+			!matches!(events[1], DomainEvent::TaskRanksRebalanced { .. }),
+			"A single domain event"
+		);
+
+		// -- Clean
+		for id in fx_ids {
+			TaskBmc::delete(&ctx, &mm, id).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_ok_concurrent_ranks() -> Result<()> {
+		// -- Setup & Fixtures
+		// Note: The test pool has a single connection, so the concurrent create
+		//       gets its own pool.
+		let mm = _dev_utils::init_test().await;
+		let mm_other = ModelManager::new().await?;
+		let ctx = Ctx::new(9033)?;
+		let fx_task_c = |title: &str| TaskForCreate {
+			title: format!("test_create_ok_concurrent_ranks-{title}"),
+			..Default::default()
+		};
+
+		// -- Exec
+		// The first create holds the owner ranks from its `next_ranks`...
+		let mut wtx = base::begin(&ctx, &mm).await?;
+		let rank = TaskBmc::next_ranks(&mut wtx, ctx.user_id(), 1)
+			.await?
+			.remove(0);
+		// ... so the concurrent create waits for it (and does not take the same rank).
+		let other = tokio::spawn({
+			let ctx = ctx.clone();
+			let task_c = fx_task_c("task 02");
+			async move { TaskBmc::create(&ctx, &mm_other, task_c).await }
+		});
+		tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+		let task_i = TaskForInsert::new(fx_task_c("task 01"), ctx.user_id(), rank);
+		let id_01 = wtx.create::<TaskBmc>(task_i.not_none_fields()).await?;
+		wtx.commit().await?;
+		let id_02 = other.await??;
+
+		// -- Check
+		let ids = list_ids_by_rank(&ctx, &mm).await?;
+		assert_eq!(ids, &[id_01, id_02]);
+		let tasks = TaskBmc::list_by_ids(&ctx, &mm, &ids).await?;
+		assert_ne!(tasks[0].rank, tasks[1].rank, "Ranks should be distinct");
+
+		// -- Clean
+		for id in ids {
+			TaskBmc::delete(&ctx, &mm, id).await?;
+		}
+
+		Ok(())
+	}
+
+	async fn list_ids_by_rank(ctx: &Ctx, mm: &ModelManager) -> Result<Vec<i64>> {
+		let filters: Vec<TaskFilter> = serde_json::from_value(json!([{
+			"owner_id": ctx.user_id()
+		}]))?;
+		let list_options: ListOptions =
+			serde_json::from_value(json!({"order_bys": "rank"}))?;
+		let tasks =
+			TaskBmc::list(ctx, mm, Some(filters), Some(list_options)).await?;
+
+		Ok(tasks.into_iter().map(|t| t.id).collect())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_assign_ok_assigned_to_me() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&["test_assign_ok-task 01", "test_assign_ok-task 02"],
+		)
+		.await?;
+		let fx_user: User = UserBmc::first_by_username(&ctx, &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+		let fx_user_ctx = Ctx::new(fx_user.id)?;
+
+		// -- Exec
+		TaskBmc::assign(&ctx, &mm, fx_tasks[0].id, fx_user.id).await?;
+		TaskBmc::assign(&ctx, &mm, fx_tasks[1].id, fx_user.id).await?;
+		TaskBmc::unassign(&ctx, &mm, fx_tasks[1].id).await?;
+
+		// -- Check
+		let filters: Vec<TaskFilter> = serde_json::from_value(json!([{
+			"title": {"$startsWith": "test_assign_ok-task"},
+			"assigned_to_me": true
+		}]))?;
+		let tasks = TaskBmc::list(&fx_user_ctx, &mm, Some(filters), None).await?;
+		assert_eq!(tasks.len(), 1);
+		assert_eq!(tasks[0].id, fx_tasks[0].id);
+		assert_eq!(tasks[0].assignee_id, Some(fx_user.id));
+		let task = TaskBmc::get(&ctx, &mm, fx_tasks[1].id).await?;
+		assert_eq!(task.assignee_id, None);
+
+		// -- Clean
+		for task in fx_tasks {
+			TaskBmc::delete(&ctx, &mm, task.id).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_visibility_ok_owner_assignee() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx_a = Ctx::new(9039)?;
+		let fx_user_b: User = UserBmc::first_by_username(&ctx_a, &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+		let ctx_b = Ctx::new(fx_user_b.id)?;
+		let fx_title = "test_visibility_ok_owner_assignee";
+		let fx_task = _dev_utils::seed_tasks(&ctx_a, &mm, &[fx_title])
+			.await?
+			.remove(0);
+		let fx_filters = || -> Result<Vec<TaskFilter>> {
+			Ok(serde_json::from_value(json!([{"title": fx_title}]))?)
+		};
+		let fx_fields = ["title".to_string()];
+
+		// -- Exec & Check (not visible to B)
+		let res = TaskBmc::get(&ctx_b, &mm, fx_task.id).await;
+		assert!(
+			matches!(res, Err(Error::TaskNotVisible { id, user_id })
+				if id == fx_task.id && user_id == fx_user_b.id),
+			"TaskNotVisible not matching"
+		);
+		let res = TaskBmc::get_projected(&ctx_b, &mm, fx_task.id, &fx_fields).await;
+		assert!(matches!(res, Err(Error::TaskNotVisible { .. })));
+		let res = TaskBmc::list_tree(&ctx_b, &mm, Some(fx_task.id)).await;
+		assert!(matches!(res, Err(Error::TaskNotVisible { .. })));
+		let res = TaskBmc::watch(&ctx_b, &mm, fx_task.id).await;
+		assert!(matches!(res, Err(Error::TaskNotVisible { .. })));
+		let tasks = TaskBmc::list(&ctx_b, &mm, Some(fx_filters()?), None).await?;
+		assert!(tasks.is_empty());
+		let tasks = TaskBmc::list(&ctx_b, &mm, None, None).await?;
+		assert!(tasks.iter().all(|t| t.id != fx_task.id));
+		let tasks = TaskBmc::list_projected(
+			&ctx_b,
+			&mm,
+			Some(fx_filters()?),
+			None,
+			&fx_fields,
+		)
+		.await?;
+		assert!(tasks.is_empty());
+		let hits = TaskBmc::search(&ctx_b, &mm, fx_title, None, None).await?;
+		assert!(hits.is_empty());
+		let nodes = TaskBmc::list_tree(&ctx_b, &mm, None).await?;
+		assert!(nodes.iter().all(|n| n.task.id != fx_task.id));
+
+		// -- Exec & Check (visible to A, the owner)
+		TaskBmc::get(&ctx_a, &mm, fx_task.id).await?;
+		let tasks = TaskBmc::list(&ctx_a, &mm, Some(fx_filters()?), None).await?;
+		assert_eq!(tasks.len(), 1);
+
+		// -- Exec & Check (visible to B, once assigned)
+		TaskBmc::assign(&ctx_a, &mm, fx_task.id, fx_user_b.id).await?;
+		TaskBmc::get(&ctx_b, &mm, fx_task.id).await?;
+		let tasks = TaskBmc::list(&ctx_b, &mm, Some(fx_filters()?), None).await?;
+		assert_eq!(tasks.len(), 1);
+		let hits = TaskBmc::search(&ctx_b, &mm, fx_title, None, None).await?;
+		assert_eq!(hits.len(), 1);
+		let nodes = TaskBmc::list_tree(&ctx_b, &mm, Some(fx_task.id)).await?;
+		assert_eq!(nodes.len(), 1);
+
+		// -- Clean
+		TaskBmc::delete(&ctx_a, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_is_event_visible_ok_owner_only() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx_a = Ctx::new(9039)?;
+		let ctx_b = Ctx::new(9040)?;
+		let mut listener = TaskChangeListener::connect().await?;
+		let fx_task =
+			_dev_utils::seed_tasks(&ctx_a, &mm, &["test_is_event_visible_ok"])
+				.await?
+				.remove(0);
+		let task_u = TaskForUpdate {
+			title: Some("test_is_event_visible_ok - new".to_string()),
+			..Default::default()
+		};
+		TaskBmc::update(&ctx_a, &mm, fx_task.id, task_u).await?;
+
+		// -- Exec & Check (created and updated)
+		for _ in 0..2 {
+			let event = listener.recv().await?;
+			assert_eq!(event.task_id(), Some(fx_task.id));
+			assert!(TaskBmc::is_event_visible(&ctx_a, &mm, &event).await?);
+			assert!(!TaskBmc::is_event_visible(&ctx_b, &mm, &event).await?);
+		}
+
+		// -- Exec & Check (deleted)
+		TaskBmc::delete(&ctx_a, &mm, fx_task.id).await?;
+		let event = listener.recv().await?;
+		assert!(matches!(event, DomainEvent::TaskDeleted { .. }));
+		assert!(TaskBmc::is_event_visible(&ctx_a, &mm, &event).await?);
+		assert!(!TaskBmc::is_event_visible(&ctx_b, &mm, &event).await?);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_assign_err_user_not_found() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_task =
+			_dev_utils::seed_tasks(&ctx, &mm, &["test_assign_err_user_not_found"])
+				.await?
+				.remove(0);
+
+		// -- Exec
+		let res = TaskBmc::assign(&ctx, &mm, fx_task.id, 100).await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::EntityNotFound {
+					entity: "user",
+					id: 100
+				})
+			),
+			"EntityNotFound not matching"
+		);
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_assign_err_not_owner_nor_assignee() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::new(9039)?;
+		let fx_user: User = UserBmc::first_by_username(&ctx, &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+		let fx_user_ctx = Ctx::new(fx_user.id)?;
+		let fx_task = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&["test_assign_err_not_owner_nor_assignee"],
+		)
+		.await?
+		.remove(0);
+		// Note: The user stays a watcher (so sees the task) once unassigned.
+		TaskBmc::assign(&ctx, &mm, fx_task.id, fx_user.id).await?;
+		TaskBmc::watch(&fx_user_ctx, &mm, fx_task.id).await?;
+		TaskBmc::unassign(&ctx, &mm, fx_task.id).await?;
+
+		// -- Exec
+		let res_assign =
+			TaskBmc::assign(&fx_user_ctx, &mm, fx_task.id, fx_user.id).await;
+		let res_unassign = TaskBmc::unassign(&fx_user_ctx, &mm, fx_task.id).await;
+
+		// -- Check
+		for res in [res_assign, res_unassign] {
+			assert!(
+				matches!(res, Err(Error::AccessDenied { entity: "task", id }) if id == fx_task.id),
+				"AccessDenied not matching"
+			);
+		}
+		let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+		assert_eq!(task.assignee_id, None);
+
+		// -- Exec & Check (the current assignee can unassign)
+		TaskBmc::assign(&ctx, &mm, fx_task.id, fx_user.id).await?;
+		TaskBmc::unassign(&fx_user_ctx, &mm, fx_task.id).await?;
+		let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+		assert_eq!(task.assignee_id, None);
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_watch_unwatch_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_user: User = UserBmc::first_by_username(&ctx, &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+		let fx_user_ctx = Ctx::new(fx_user.id)?;
+		// Note: Owned by the user, as only the visible tasks can be watched.
+		let fx_task =
+			_dev_utils::seed_tasks(&fx_user_ctx, &mm, &["test_watch_unwatch_ok"])
+				.await?
+				.remove(0);
+
+		// -- Exec & Check
+		TaskBmc::watch(&fx_user_ctx, &mm, fx_task.id).await?;
+		TaskBmc::watch(&fx_user_ctx, &mm, fx_task.id).await?;
+		let watcher_ids = TaskBmc::list_watcher_ids(&ctx, &mm, fx_task.id).await?;
+		assert_eq!(watcher_ids, &[fx_user.id]);
+
+		TaskBmc::unwatch(&fx_user_ctx, &mm, fx_task.id).await?;
+		let watcher_ids = TaskBmc::list_watcher_ids(&ctx, &mm, fx_task.id).await?;
+		assert!(watcher_ids.is_empty());
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_watch_err_user_fk() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, &["test_watch_err_user_fk"])
+			.await?
+			.remove(0);
+
+		// -- Exec
+		// Note: The root ctx user (id 0) has no user row.
+		let res = TaskBmc::watch(&ctx, &mm, fx_task.id).await;
+
+		// -- Check
+		assert!(
+			matches!(
+				&res,
+				Err(Error::ForeignKeyViolation { entity, field })
+					if entity == "task_watcher" && field == "user_id"
+			),
+			"ForeignKeyViolation not matching"
+		);
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_done_creates_next_occurrence() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_title = "test_update_done_creates_next_occurrence";
+		let fx_id = TaskBmc::create(
+			&ctx,
+			&mm,
+			TaskForCreate {
+				title: fx_title.to_string(),
+				due_at: Some(parse_utc("2024-01-01T09:00:00Z")?),
+				recurrence: Some("freq=weekly;byday=fr,mo;count=2".to_string()),
+				..Default::default()
+			},
+		)
+		.await?;
+		let task_u = TaskForUpdate {
+			status: Some(TaskStatus::Done),
+			..Default::default()
+		};
+
+		// -- Exec
+		TaskBmc::update(&ctx, &mm, fx_id, task_u.clone()).await?;
+
+		// -- Check
+		let tasks = list_by_title(&ctx, &mm, fx_title).await?;
+		assert_eq!(tasks.len(), 2);
+		let next = &tasks[1];
+		assert_eq!(next.status, TaskStatus::Todo);
+		assert_eq!(next.due_at, Some(parse_utc("2024-01-05T09:00:00Z")?));
+		assert_eq!(
+			next.recurrence.as_deref(),
+			Some("FREQ=WEEKLY;BYDAY=MO,FR;COUNT=1")
+		);
+
+		// -- Exec & Check (the last occurrence, and done twice is a no-op)
+		TaskBmc::update(&ctx, &mm, next.id, task_u.clone()).await?;
+		TaskBmc::update(&ctx, &mm, next.id, task_u).await?;
+		let tasks = list_by_title(&ctx, &mm, fx_title).await?;
+		assert_eq!(tasks.len(), 2);
+
+		// -- Clean
+		for task in tasks {
+			TaskBmc::delete(&ctx, &mm, task.id).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_occurrence_this_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_title = "test_update_occurrence_this_ok";
+		let fx_id = TaskBmc::create(
+			&ctx,
+			&mm,
+			TaskForCreate {
+				title: fx_title.to_string(),
+				due_at: Some(parse_utc("2024-01-31T09:00:00Z")?),
+				recurrence: Some("FREQ=MONTHLY".to_string()),
+				..Default::default()
+			},
+		)
+		.await?;
 
-// region:    --- Task Types
-#[derive(Debug, Clone, Fields, FromRow, Serialize)]
-pub struct Task {
-	pub id: i64,
+		// -- Exec
+		let task_u = TaskForUpdate {
+			description: Some("only this one".to_string()),
+			..Default::default()
+		};
+		TaskBmc::update_occurrence(
+			&ctx,
+			&mm,
+			fx_id,
+			task_u,
+			TaskOccurrenceScope::This,
+		)
+		.await?;
 
-	pub title: String,
-	pub done: bool,
-}
+		// -- Check
+		let tasks = list_by_title(&ctx, &mm, fx_title).await?;
+		assert_eq!(tasks.len(), 2);
+		let (this, next) = (&tasks[0], &tasks[1]);
+		assert_eq!(this.description.as_deref(), Some("only this one"));
+		assert_eq!(this.recurrence, None);
+		assert_eq!(next.description, None);
+		assert_eq!(next.due_at, Some(parse_utc("2024-03-31T09:00:00Z")?));
+		assert_eq!(next.recurrence.as_deref(), Some("FREQ=MONTHLY"));
 
-#[derive(Fields, Deserialize)]
-pub struct TaskForCreate {
-	pub title: String,
-}
+		// -- Clean
+		for task in tasks {
+			TaskBmc::delete(&ctx, &mm, task.id).await?;
+		}
 
-#[derive(Fields, Default, Deserialize)]
-pub struct TaskForUpdate {
-	pub title: Option<String>,
-	pub done: Option<bool>,
-}
+		Ok(())
+	}
 
-#[derive(FilterNodes, Deserialize, Default, Debug)]
-pub struct TaskFilter {
-	id: Option<OpValsInt64>,
+	#[serial]
+	#[tokio::test]
+	async fn test_update_occurrence_this_err_rolled_back() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_title = "test_update_occurrence_this_err_rolled_back";
+		let fx_id = TaskBmc::create(
+			&ctx,
+			&mm,
+			TaskForCreate {
+				title: fx_title.to_string(),
+				due_at: Some(parse_utc("2024-01-31T09:00:00Z")?),
+				recurrence: Some("FREQ=MONTHLY".to_string()),
+				..Default::default()
+			},
+		)
+		.await?;
 
-	title: Option<OpValsString>,
-	done: Option<OpValsBool>,
-}
-// endregion: --- Task Types
+		// -- Exec
+		// Note: Fails on the update, after the occurrence is detached.
+		let task_u = TaskForUpdate {
+			title: Some("x".repeat(300)),
+			..Default::default()
+		};
+		let res = TaskBmc::update_occurrence(
+			&ctx,
+			&mm,
+			fx_id,
+			task_u,
+			TaskOccurrenceScope::This,
+		)
+		.await;
 
-// region:    --- TaskBmc
-pub struct TaskBmc;
+		// -- Check
+		assert!(res.is_err(), "title over the column size should fail");
+		let tasks = list_by_title(&ctx, &mm, fx_title).await?;
+		assert_eq!(tasks.len(), 1, "no next occurrence");
+		assert_eq!(tasks[0].recurrence.as_deref(), Some("FREQ=MONTHLY"));
 
-impl DbBmc for TaskBmc {
-	const TABLE: &'static str = "task";
-}
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_id).await?;
 
-impl TaskBmc {
-	pub async fn create(
-		ctx: &Ctx,
-		mm: &ModelManager,
-		task_c: TaskForCreate,
-	) -> Result<i64> {
-		base::create::<Self, _>(ctx, mm, task_c).await
+		Ok(())
 	}
 
-	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Task> {
-		base::get::<Self, _>(ctx, mm, id).await
+	#[serial]
+	#[tokio::test]
+	async fn test_list_occurrences_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_title = "test_list_occurrences_ok";
+		let fx_tasks_c = [
+			(
+				parse_utc("2024-01-01T09:00:00Z")?,
+				Some("FREQ=DAILY;INTERVAL=3"),
+			),
+			(parse_utc("2024-01-05T12:00:00Z")?, None),
+			(parse_utc("2024-03-01T12:00:00Z")?, None),
+		];
+		let mut fx_ids = Vec::new();
+		for (due_at, recurrence) in fx_tasks_c {
+			let task_c = TaskForCreate {
+				title: fx_title.to_string(),
+				due_at: Some(due_at),
+				recurrence: recurrence.map(str::to_string),
+				..Default::default()
+			};
+			fx_ids.push(TaskBmc::create(&ctx, &mm, task_c).await?);
+		}
+
+		// -- Exec
+		let filters: Vec<TaskFilter> =
+			serde_json::from_value(json!([{ "title": fx_title }]))?;
+		let occurrences = TaskBmc::list_occurrences(
+			&ctx,
+			&mm,
+			parse_utc("2024-01-03T00:00:00Z")?,
+			parse_utc("2024-01-11T00:00:00Z")?,
+			Some(filters),
+		)
+		.await?;
+
+		// -- Check
+		let occurrences: Vec<(i64, String, bool)> = occurrences
+			.into_iter()
+			.map(|o| (o.task_id, format_time(o.due_at), o.expanded))
+			.collect();
+		assert_eq!(
+			occurrences,
+			&[
+				(fx_ids[0], "2024-01-04T09:00:00Z".to_string(), true),
+				(fx_ids[1], "2024-01-05T12:00:00Z".to_string(), false),
+				(fx_ids[0], "2024-01-07T09:00:00Z".to_string(), true),
+				(fx_ids[0], "2024-01-10T09:00:00Z".to_string(), true),
+			]
+		);
+
+		// -- Clean
+		for id in fx_ids {
+			TaskBmc::delete(&ctx, &mm, id).await?;
+		}
+
+		Ok(())
 	}
 
-	pub async fn list(
+	async fn list_by_title(
 		ctx: &Ctx,
 		mm: &ModelManager,
-		filters: Option<Vec<TaskFilter>>,
-		list_options: Option<ListOptions>,
+		title: &str,
 	) -> Result<Vec<Task>> {
-		base::list::<Self, _, _>(ctx, mm, filters, list_options).await
-	}
+		let filters: Vec<TaskFilter> =
+			serde_json::from_value(json!([{ "title": title }]))?;
+		let list_options: ListOptions =
+			serde_json::from_value(json!({"order_bys": "id"}))?;
 
-	pub async fn update(
-		ctx: &Ctx,
-		mm: &ModelManager,
-		id: i64,
-		task_u: TaskForUpdate,
-	) -> Result<()> {
-		base::update::<Self, _>(ctx, mm, id, task_u).await
+		Ok(TaskBmc::list(ctx, mm, Some(filters), Some(list_options)).await?)
 	}
 
-	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
-		base::delete::<Self>(ctx, mm, id).await
-	}
-}
-// endregion: --- TaskBmc
+	#[serial]
+	#[tokio::test]
+	async fn test_revert_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, &["test_revert_ok"])
+			.await?
+			.remove(0);
+		for title in ["test_revert_ok - edit 01", "test_revert_ok - edit 02"] {
+			let task_u = TaskForUpdate {
+				title: Some(title.to_string()),
+				description: Some("bulk edited".to_string()),
+				..Default::default()
+			};
+			TaskBmc::update(&ctx, &mm, fx_task.id, task_u).await?;
+		}
 
-// region:    --- Tests
-#[cfg(test)]
-mod tests {
-	use super::*;
-	use crate::_dev_utils;
-	use crate::model::Error;
-	use anyhow::Result;
-	use serde_json::json;
-	use serial_test::serial;
+		// -- Exec
+		TaskBmc::revert(&ctx, &mm, fx_task.id, 1).await?;
+
+		// -- Check
+		let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+		assert_eq!(task.title, "test_revert_ok");
+		assert_eq!(task.description, None);
+		let revisions = TaskBmc::list_revisions(&ctx, &mm, fx_task.id).await?;
+		let titles: Vec<&str> =
+			revisions.iter().map(|r| r.state.title.as_str()).collect();
+		assert_eq!(
+			titles,
+			&[
+				"test_revert_ok",
+				"test_revert_ok - edit 01",
+				"test_revert_ok - edit 02",
+				"test_revert_ok",
+			],
+			"exactly one new revision"
+		);
+		let res = TaskBmc::revert(&ctx, &mm, fx_task.id, 99).await;
+		assert!(
+			matches!(res, Err(Error::TaskRevisionNotFound { revision: 99, .. })),
+			"TaskRevisionNotFound not matching"
+		);
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+
+		Ok(())
+	}
 
 	#[serial]
 	#[tokio::test]
-	async fn test_create_ok() -> Result<()> {
+	async fn test_update_many_by_filter_ok() -> Result<()> {
 		// -- Setup & Fixtures
 		let mm = _dev_utils::init_test().await;
 		let ctx = Ctx::root_ctx();
-		let fx_title = "test_create_ok title";
+		let fx_titles = &[
+			"test_update_many_by_filter_ok-task 01",
+			"test_update_many_by_filter_ok-task 02",
+			"test_update_many_by_filter_ok-other 03",
+		];
+		let fx_tasks = _dev_utils::seed_tasks(&ctx, &mm, fx_titles).await?;
 
 		// -- Exec
-		let task_c = TaskForCreate {
-			title: fx_title.to_string(),
-		};
-		let id = TaskBmc::create(&ctx, &mm, task_c).await?;
+		let filters: Vec<TaskFilter> = serde_json::from_value(json!([{
+			"title": {"$startsWith": "test_update_many_by_filter_ok-task"}
+		}]))?;
+		let ids = TaskBmc::update_many(
+			&ctx,
+			&mm,
+			BulkTarget::Filters(filters),
+			TaskForUpdate {
+				done: Some(true),
+				..Default::default()
+			},
+		)
+		.await?;
 
 		// -- Check
-		let task = TaskBmc::get(&ctx, &mm, id).await?;
-		assert_eq!(task.title, fx_title);
+		assert_eq!(ids.len(), 2);
+		let fx_ids: Vec<i64> = fx_tasks.iter().map(|t| t.id).collect();
+		let tasks = TaskBmc::list_by_ids(&ctx, &mm, &fx_ids).await?;
+		let dones: Vec<bool> = tasks.iter().map(|t| t.done).collect();
+		assert_eq!(dones, &[true, true, false]);
 
 		// -- Clean
-		TaskBmc::delete(&ctx, &mm, id).await?;
+		TaskBmc::delete_many(&ctx, &mm, BulkTarget::Ids(fx_ids)).await?;
 
 		Ok(())
 	}
 
 	#[serial]
 	#[tokio::test]
-	async fn test_get_err_not_found() -> Result<()> {
+	async fn test_update_many_by_ids_err_not_found() -> Result<()> {
 		// -- Setup & Fixtures
 		let mm = _dev_utils::init_test().await;
 		let ctx = Ctx::root_ctx();
-		let fx_id = 100;
+		let fx_title = "test_update_many_by_ids_err_not_found - task 01";
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, &[fx_title])
+			.await?
+			.remove(0);
 
 		// -- Exec
-		let res = TaskBmc::get(&ctx, &mm, fx_id).await;
+		let res = TaskBmc::update_many(
+			&ctx,
+			&mm,
+			BulkTarget::Ids(vec![fx_task.id, 100]),
+			TaskForUpdate {
+				title: Some("should not be updated".to_string()),
+				..Default::default()
+			},
+		)
+		.await;
 
 		// -- Check
 		assert!(
@@ -138,121 +3520,115 @@ mod tests {
 			),
 			"EntityNotFound not matching"
 		);
+		let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+		assert_eq!(task.title, fx_title, "update should have been rolled back");
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
 
 		Ok(())
 	}
 
 	#[serial]
 	#[tokio::test]
-	async fn test_list_all_ok() -> Result<()> {
+	async fn test_delete_many_err_filters_empty() -> Result<()> {
 		// -- Setup & Fixtures
 		let mm = _dev_utils::init_test().await;
 		let ctx = Ctx::root_ctx();
-		let fx_titles = &["test_list_all_ok-task 01", "test_list_all_ok-task 02"];
-		_dev_utils::seed_tasks(&ctx, &mm, fx_titles).await?;
 
 		// -- Exec
-		let tasks = TaskBmc::list(&ctx, &mm, None, None).await?;
+		let filters: Vec<TaskFilter> = serde_json::from_value(json!([{}]))?;
+		let res =
+			TaskBmc::delete_many(&ctx, &mm, BulkTarget::Filters(filters)).await;
 
 		// -- Check
-		let tasks: Vec<Task> = tasks
-			.into_iter()
-			.filter(|t| t.title.starts_with("test_list_all_ok-task"))
-			.collect();
-		assert_eq!(tasks.len(), 2, "number of seeded tasks.");
-
-		// -- Clean
-		for task in tasks.iter() {
-			TaskBmc::delete(&ctx, &mm, task.id).await?;
-		}
+		assert!(
+			matches!(res, Err(Error::BulkFiltersEmpty)),
+			"BulkFiltersEmpty not matching"
+		);
 
 		Ok(())
 	}
 
 	#[serial]
 	#[tokio::test]
-	async fn test_list_by_filter_ok() -> Result<()> {
+	async fn test_delete_many_ok_reparent() -> Result<()> {
 		// -- Setup & Fixtures
+		// root > a > b > c, with a and b deleted
 		let mm = _dev_utils::init_test().await;
 		let ctx = Ctx::root_ctx();
-		let fx_titles = &[
-			"test_list_by_filter_ok-task 01.a",
-			"test_list_by_filter_ok-task 01.b",
-			"test_list_by_filter_ok-task 02.a",
-			"test_list_by_filter_ok-task 02.b",
-			"test_list_by_filter_ok-task 03",
-		];
-		_dev_utils::seed_tasks(&ctx, &mm, fx_titles).await?;
+		let mut fx_parent_id = None;
+		let mut fx_ids = Vec::new();
+		for name in ["root", "a", "b", "c"] {
+			let task_c = TaskForCreate {
+				title: format!("test_delete_many_ok_reparent-{name}"),
+				parent_id: fx_parent_id,
+				..Default::default()
+			};
+			let id = TaskBmc::create(&ctx, &mm, task_c).await?;
+			fx_parent_id = Some(id);
+			fx_ids.push(id);
+		}
+		let (fx_root, fx_c) = (fx_ids[0], fx_ids[3]);
 
 		// -- Exec
-		let filters: Vec<TaskFilter> = serde_json::from_value(json!([
-			{
-				"title": {
-					"$endsWith": ".a",
-					"$containsAny": ["01", "02"]
-				}
-			},
-			{
-				"title": {"$contains": "03"}
-			}
-		]))?;
-		let list_options = serde_json::from_value(json!({
-			"order_bys": "!id"
-		}))?;
-		let tasks =
-			TaskBmc::list(&ctx, &mm, Some(filters), Some(list_options)).await?;
+		let target = BulkTarget::Ids(vec![fx_ids[2], fx_ids[1]]);
+		let ids = TaskBmc::delete_many(&ctx, &mm, target).await?;
 
 		// -- Check
-		assert_eq!(tasks.len(), 3);
-		assert!(tasks[0].title.ends_with("03"));
-		assert!(tasks[1].title.ends_with("02.a"));
-		assert!(tasks[2].title.ends_with("01.a"));
+		assert_eq!(ids, &[fx_ids[1], fx_ids[2]]);
+		let c = TaskBmc::get(&ctx, &mm, fx_c).await?;
+		assert_eq!(c.parent_id, Some(fx_root), "closest ancestor not deleted");
 
 		// -- Clean
-		let tasks = TaskBmc::list(
-			&ctx,
-			&mm,
-			Some(serde_json::from_value(json!([{
-				"title": {"$startsWith": "test_list_by_filter_ok"}
-			}]))?),
-			None,
-		)
-		.await?;
-		assert_eq!(tasks.len(), 5);
-		for task in tasks.iter() {
-			TaskBmc::delete(&ctx, &mm, task.id).await?;
-		}
+		TaskBmc::delete_with_policy(&ctx, &mm, fx_root, TaskDeletePolicy::Cascade)
+			.await?;
 
 		Ok(())
 	}
 
 	#[serial]
 	#[tokio::test]
-	async fn test_update_ok() -> Result<()> {
+	async fn test_delete_many_ok_cascade() -> Result<()> {
 		// -- Setup & Fixtures
 		let mm = _dev_utils::init_test().await;
 		let ctx = Ctx::root_ctx();
-		let fx_title = "test_update_ok - task 01";
-		let fx_title_new = "test_update_ok - task 01 - new";
-		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, &[fx_title])
-			.await?
-			.remove(0);
-
-		// -- Exec
-		TaskBmc::update(
+		let fx_parent = TaskBmc::create(
 			&ctx,
 			&mm,
-			fx_task.id,
-			TaskForUpdate {
-				title: Some(fx_title_new.to_string()),
+			TaskForCreate {
+				title: "test_delete_many_ok_cascade-parent".to_string(),
 				..Default::default()
 			},
 		)
 		.await?;
+		let fx_child = TaskBmc::create(
+			&ctx,
+			&mm,
+			TaskForCreate {
+				title: "test_delete_many_ok_cascade-child".to_string(),
+				parent_id: Some(fx_parent),
+				..Default::default()
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let target = BulkTarget::Ids(vec![fx_parent]);
+		TaskBmc::delete_many_with_policy(
+			&ctx,
+			&mm,
+			target,
+			TaskDeletePolicy::Cascade,
+		)
+		.await?;
 
 		// -- Check
-		let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
-		assert_eq!(task.title, fx_title_new);
+		let res = TaskBmc::get(&ctx, &mm, fx_child).await;
+		assert!(
+			matches!(res, Err(Error::EntityNotFound { id, .. }) if id == fx_child),
+			"subtask should be deleted with its parent"
+		);
 
 		Ok(())
 	}
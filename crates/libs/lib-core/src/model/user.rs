@@ -1,10 +1,12 @@
 use crate::ctx::Ctx;
 use crate::model::base::{self, DbBmc};
 use crate::model::ModelManager;
-use crate::model::Result;
+use crate::model::{Error, Result};
 use lib_auth::pwd::{self, ContentToHash};
-use modql::field::{Fields, HasFields};
-use sea_query::{Expr, Iden, PostgresQueryBuilder, Query, SimpleExpr};
+use lib_utils::validate::{LazyRegex, Validate};
+use modql::field::{Field, Fields, HasFields};
+use schemars::JsonSchema;
+use sea_query::{Expr, Iden, PostgresQueryBuilder, Query};
 use sea_query_binder::SqlxBinder;
 use serde::{Deserialize, Serialize};
 use sqlx::postgres::PgRow;
@@ -12,7 +14,9 @@ use sqlx::FromRow;
 use uuid::Uuid;
 
 // region:    --- User Types
-#[derive(Clone, Fields, FromRow, Debug, Serialize)]
+static USERNAME_REGEX: LazyRegex = LazyRegex::new("^[A-Za-z0-9_.-]+$");
+
+#[derive(Clone, Fields, FromRow, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct User {
 	pub id: i64,
 	pub username: String,
@@ -24,6 +28,14 @@ pub struct UserForCreate {
 	pub pwd_clear: String,
 }
 
+/// Note: The web token identifies the user by username, so a username change
+///       requires a new login.
+#[derive(Fields, Default, Serialize, Deserialize, JsonSchema, Validate)]
+pub struct UserForUpdate {
+	#[validate(length(max = 128), regex(path = USERNAME_REGEX))]
+	pub username: Option<String>,
+}
+
 #[derive(Fields)]
 pub struct UserForInsert {
 	pub username: String,
@@ -49,21 +61,31 @@ pub struct UserForAuth {
 	pub token_salt: Uuid,
 }
 
+#[derive(Clone, FromRow, Fields, Debug)]
+pub struct UserForAdmin {
+	pub id: i64,
+	pub username: String,
+
+	/// Can list the audit events (see `AuditBmc`).
+	pub admin: bool,
+}
+
 /// Marker trait
 pub trait UserBy: HasFields + for<'r> FromRow<'r, PgRow> + Unpin + Send {}
 
 impl UserBy for User {}
 impl UserBy for UserForLogin {}
 impl UserBy for UserForAuth {}
+impl UserBy for UserForAdmin {}
 
 // Note: Since the entity properties Iden will be given by modql::field::Fields
 //       UserIden does not have to be exhaustive, but just have the columns
 //       we use in our specific code.
 #[derive(Iden)]
 enum UserIden {
-	Id,
 	Username,
 	Pwd,
+	TokenSalt,
 }
 
 // endregion: --- User Types
@@ -72,6 +94,8 @@ pub struct UserBmc;
 
 impl DbBmc for UserBmc {
 	const TABLE: &'static str = "user";
+	const AUDIT_REDACTED: &'static [&'static str] =
+		&["pwd", "pwd_salt", "token_salt"];
 }
 
 impl UserBmc {
@@ -108,14 +132,48 @@ impl UserBmc {
 		Ok(user)
 	}
 
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		user_u: UserForUpdate,
+	) -> Result<()> {
+		base::update::<Self, _>(ctx, mm, id, user_u).await
+	}
+
+	/// Changes the password, when `pwd_clear_old` matches the current password.
+	pub async fn change_pwd(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		pwd_clear_old: &str,
+		pwd_clear_new: &str,
+	) -> Result<()> {
+		// -- Validate the old password
+		let user: UserForLogin = Self::get(ctx, mm, id).await?;
+		let Some(pwd) = &user.pwd else {
+			return Err(Error::UserPwdNotMatching { user_id: id });
+		};
+		pwd::validate_pwd(
+			&ContentToHash {
+				content: pwd_clear_old.to_string(),
+				salt: user.pwd_salt,
+			},
+			pwd,
+		)
+		.map_err(|_| Error::UserPwdNotMatching { user_id: id })?;
+
+		Self::update_pwd(ctx, mm, id, pwd_clear_new).await
+	}
+
+	/// Sets the password, and rotates the token salt in the same update,
+	/// so the web tokens issued before are no longer valid.
 	pub async fn update_pwd(
 		ctx: &Ctx,
 		mm: &ModelManager,
 		id: i64,
 		pwd_clear: &str,
 	) -> Result<()> {
-		let db = mm.db();
-
 		// -- Prep password
 		let user: UserForLogin = Self::get(ctx, mm, id).await?;
 		let pwd = pwd::hash_pwd(&ContentToHash {
@@ -123,21 +181,32 @@ impl UserBmc {
 			salt: user.pwd_salt,
 		})?;
 
-		// -- Build query
-		let mut query = Query::update();
-		query
-			.table(Self::table_ref())
-			.value(UserIden::Pwd, SimpleExpr::from(pwd))
-			.and_where(Expr::col(UserIden::Id).eq(id));
+		let fields = Fields::new(vec![
+			Field::new(UserIden::Pwd, pwd.into()),
+			Field::new(UserIden::TokenSalt, Uuid::new_v4().into()),
+		]);
 
-		// -- Exec query
-		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-		let _count = sqlx::query_with(&sql, values)
-			.execute(db)
-			.await?
-			.rows_affected();
+		base::update_fields::<Self>(ctx, mm, id, fields).await
+	}
 
-		Ok(())
+	/// Checks that the ctx user is an admin (the root ctx always is).
+	pub async fn check_admin(ctx: &Ctx, mm: &ModelManager) -> Result<()> {
+		let user_id = ctx.user_id();
+		if user_id == Ctx::root_ctx().user_id() {
+			return Ok(());
+		}
+
+		let admin = match Self::get::<UserForAdmin>(ctx, mm, user_id).await {
+			Ok(user) => user.admin,
+			Err(Error::EntityNotFound { .. }) => false,
+			Err(ex) => return Err(ex),
+		};
+
+		if admin {
+			Ok(())
+		} else {
+			Err(Error::AdminRequired { user_id })
+		}
 	}
 }
 
@@ -167,5 +236,69 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_change_pwd_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_pwd = "welcome";
+		let fx_pwd_new = "test_change_pwd_ok new pwd";
+		let user: UserForAuth = UserBmc::first_by_username(&ctx, &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+
+		// -- Exec
+		UserBmc::change_pwd(&ctx, &mm, user.id, fx_pwd, fx_pwd_new).await?;
+
+		// -- Check
+		let user_changed: UserForAuth = UserBmc::get(&ctx, &mm, user.id).await?;
+		assert_ne!(
+			user_changed.token_salt, user.token_salt,
+			"Token salt should be rotated"
+		);
+		let res = UserBmc::change_pwd(&ctx, &mm, user.id, fx_pwd, fx_pwd_new).await;
+		assert!(
+			matches!(res, Err(Error::UserPwdNotMatching { user_id }) if user_id == user.id),
+			"Old password should not match anymore"
+		);
+
+		// -- Clean
+		UserBmc::change_pwd(&ctx, &mm, user.id, fx_pwd_new, fx_pwd).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_username = "demo1";
+		let fx_username_new = "test_update_ok demo1";
+		let user: User = UserBmc::first_by_username(&ctx, &mm, fx_username)
+			.await?
+			.context("Should have user 'demo1'")?;
+
+		// -- Exec
+		let user_u = UserForUpdate {
+			username: Some(fx_username_new.to_string()),
+		};
+		UserBmc::update(&ctx, &mm, user.id, user_u).await?;
+
+		// -- Check
+		let user_updated: User = UserBmc::get(&ctx, &mm, user.id).await?;
+		assert_eq!(user_updated.username, fx_username_new);
+
+		// -- Clean
+		let user_u = UserForUpdate {
+			username: Some(fx_username.to_string()),
+		};
+		UserBmc::update(&ctx, &mm, user.id, user_u).await?;
+
+		Ok(())
+	}
 }
 // endregion: --- Tests
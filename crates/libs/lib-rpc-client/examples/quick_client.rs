@@ -0,0 +1,44 @@
+#![allow(unused)] // For beginning only.
+
+use anyhow::Result;
+use lib_core::model::task::{TaskDeletePolicy, TaskForCreate};
+use lib_rpc_client::{ClientError, RpcClient};
+use serde_json::json;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+	let client = RpcClient::new("http://localhost:8080")?;
+
+	client.login("demo1", "welcome").await?;
+
+	// -- Create Task
+	let task = client
+		.create_task(TaskForCreate {
+			title: "task from quick_client".to_string(),
+			..Default::default()
+		})
+		.await?;
+	println!("->> created: {task:?}");
+
+	// -- List Tasks
+	let tasks = client
+		.list_tasks(Some(json!({"title": {"$contains": "quick_client"}})), None)
+		.await?;
+	println!("->> listed: {}", tasks.len());
+
+	// -- Delete Task
+	client
+		.delete_task(task.id, TaskDeletePolicy::default())
+		.await?;
+	let res = client
+		.delete_task(task.id, TaskDeletePolicy::default())
+		.await;
+	let Err(err) = res else {
+		panic!("delete_task should fail for a deleted task");
+	};
+	println!("->> error: {:?}", err.client_error());
+
+	client.logoff().await?;
+
+	Ok(())
+}
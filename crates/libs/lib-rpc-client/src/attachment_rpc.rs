@@ -0,0 +1,17 @@
+use crate::{Result, RpcClient};
+use lib_core::model::attachment::Attachment;
+use serde_json::json;
+
+/// Note: The attachments content is uploaded and downloaded with the
+///       `/api/tasks/:task_id/attachments` and `/api/attachments/:id` web routes
+///       (not part of this client).
+impl RpcClient {
+	pub async fn list_attachments(&self, task_id: i64) -> Result<Vec<Attachment>> {
+		self.call("list_attachments", json!({ "task_id": task_id }))
+			.await
+	}
+
+	pub async fn delete_attachment(&self, id: i64) -> Result<Attachment> {
+		self.call("delete_attachment", json!({ "id": id })).await
+	}
+}
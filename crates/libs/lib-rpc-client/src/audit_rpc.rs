@@ -0,0 +1,19 @@
+use crate::{Result, RpcClient};
+use lib_core::model::audit::AuditEvent;
+use serde_json::{json, Value};
+
+impl RpcClient {
+	/// Note: For the admins only.
+	pub async fn list_audit_events(
+		&self,
+		filters: Option<Value>,
+		list_options: Option<Value>,
+	) -> Result<Vec<AuditEvent>> {
+		let params = json!({
+			"filters": filters,
+			"list_options": list_options,
+		});
+
+		self.call("list_audit_events", params).await
+	}
+}
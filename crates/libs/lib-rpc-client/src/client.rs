@@ -0,0 +1,194 @@
+use crate::{Error, Result};
+use lib_rpc::{ClientError, JSONRPC_VERSION};
+use reqwest::RequestBuilder;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{from_value, json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The client of the web-server JSON-RPC API (`/api/rpc`), with the typed
+/// methods of the rpc methods (e.g., `RpcClient::create_task`).
+///
+/// Authenticated with the login session cookie (see `RpcClient::login`),
+/// or with a bearer token (see `RpcClient::with_bearer_token`).
+pub struct RpcClient {
+	http: reqwest::Client,
+	base_url: String,
+	bearer_token: Option<String>,
+	next_id: AtomicU64,
+}
+
+// region:    --- Constructors & Auth
+impl RpcClient {
+	/// e.g., `RpcClient::new("http://localhost:8080")`
+	pub fn new(base_url: impl Into<String>) -> Result<Self> {
+		let http = reqwest::Client::builder().cookie_store(true).build()?;
+
+		Ok(RpcClient {
+			http,
+			base_url: base_url.into().trim_end_matches('/').to_string(),
+			bearer_token: None,
+			next_id: AtomicU64::new(1),
+		})
+	}
+
+	/// Authenticates the calls with the web token
+	/// (as `Authorization: Bearer <token>`), instead of the login session.
+	///
+	/// Note: The token is not renewed, so it must be replaced before it expires
+	///       (or after a password change), e.g., with a new `login`.
+	pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+		self.bearer_token = Some(token.into());
+		self
+	}
+
+	/// Logs in, and keeps the session cookie for the next calls
+	/// (renewed by the server on each call).
+	///
+	/// Returns the web token, e.g., for a client with `with_bearer_token`.
+	pub async fn login(&self, username: &str, pwd: &str) -> Result<String> {
+		let body = json!({
+			"username": username,
+			"pwd": pwd,
+		});
+		let req = self.http.post(self.url("/api/login")).json(&body);
+
+		let result = self.send(req).await?;
+		let token = result.get("token").and_then(Value::as_str).ok_or(
+			Error::RpcResponseInvalid {
+				cause: "no login token",
+			},
+		)?;
+
+		Ok(token.to_string())
+	}
+
+	pub async fn logoff(&self) -> Result<()> {
+		let body = json!({ "logoff": true });
+		let req = self.http.post(self.url("/api/logoff")).json(&body);
+
+		self.send(req).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Constructors & Auth
+
+// region:    --- Call
+impl RpcClient {
+	/// Calls the rpc method, and returns its result
+	/// (for the methods without typed method).
+	pub async fn call<P, R>(&self, rpc_method: &str, params: P) -> Result<R>
+	where
+		P: Serialize,
+		R: DeserializeOwned,
+	{
+		self.call_with_key(rpc_method, params, None).await
+	}
+
+	/// Same as `call`, with the idempotency key of a mutating method call
+	/// (e.g., a uuid per logical call), which returns the first call result
+	/// when retried with the same key and params.
+	pub async fn call_idempotent<P, R>(
+		&self,
+		rpc_method: &str,
+		params: P,
+		idempotency_key: &str,
+	) -> Result<R>
+	where
+		P: Serialize,
+		R: DeserializeOwned,
+	{
+		self.call_with_key(rpc_method, params, Some(idempotency_key))
+			.await
+	}
+
+	async fn call_with_key<P, R>(
+		&self,
+		rpc_method: &str,
+		params: P,
+		idempotency_key: Option<&str>,
+	) -> Result<R>
+	where
+		P: Serialize,
+		R: DeserializeOwned,
+	{
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+		let mut body = json!({
+			"jsonrpc": JSONRPC_VERSION,
+			"id": id,
+			"method": rpc_method,
+			"params": params,
+		});
+		if let Some(key) = idempotency_key {
+			body["idempotency_key"] = key.into();
+		}
+		let req = self.http.post(self.url("/api/rpc")).json(&body);
+
+		let result = self.send(req).await?;
+
+		Ok(from_value(result)?)
+	}
+
+	/// Sends the request, and returns the response `result`,
+	/// or the response `error` as `Error::Rpc`.
+	async fn send(&self, req: RequestBuilder) -> Result<Value> {
+		let req = match &self.bearer_token {
+			Some(token) => req.bearer_auth(token),
+			None => req,
+		};
+
+		// Note: The error responses have an error http status, and their
+		//       `error` in the body (as the rpc error responses).
+		let mut body: Value = req.send().await?.json().await?;
+
+		if let Some(error) = body.get_mut("error").map(Value::take) {
+			return Err(rpc_error(error));
+		}
+
+		body.get_mut("result")
+			.map(Value::take)
+			.ok_or(Error::RpcResponseInvalid {
+				cause: "no result or error",
+			})
+	}
+
+	fn url(&self, path: &str) -> String {
+		format!("{}{path}", self.base_url)
+	}
+}
+
+/// Returns the `ClientError` of the JSON-RPC error object
+/// `{code, message, data: {req_uuid, detail}}`.
+fn rpc_error(mut error: Value) -> Error {
+	let code = error
+		.get("code")
+		.and_then(Value::as_i64)
+		.unwrap_or_default();
+	let message = error.get_mut("message").map(Value::take);
+	let data = error.get_mut("data").map(Value::take).unwrap_or_default();
+	let req_uuid = data
+		.get("req_uuid")
+		.and_then(Value::as_str)
+		.map(String::from);
+	let detail = data.get("detail").cloned().unwrap_or_default();
+
+	let client_error =
+		from_value::<ClientError>(json!({"message": message, "detail": detail}));
+
+	match client_error {
+		Ok(client_error) => Error::Rpc {
+			client_error,
+			req_uuid,
+		},
+		Err(_) => Error::RpcUnknownError {
+			code,
+			message: message
+				.as_ref()
+				.and_then(Value::as_str)
+				.unwrap_or_default()
+				.to_string(),
+		},
+	}
+}
+// endregion: --- Call
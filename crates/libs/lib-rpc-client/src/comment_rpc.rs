@@ -0,0 +1,32 @@
+use crate::{Result, RpcClient};
+use lib_core::model::comment::{Comment, CommentForCreate, CommentForUpdate};
+use serde_json::{json, Value};
+
+impl RpcClient {
+	pub async fn add_comment(&self, comment_c: CommentForCreate) -> Result<Comment> {
+		self.call("add_comment", json!({ "data": comment_c })).await
+	}
+
+	pub async fn list_comments(
+		&self,
+		task_id: i64,
+		list_options: Option<Value>,
+	) -> Result<Vec<Comment>> {
+		let params = json!({ "task_id": task_id, "list_options": list_options });
+
+		self.call("list_comments", params).await
+	}
+
+	pub async fn edit_comment(
+		&self,
+		id: i64,
+		comment_u: CommentForUpdate,
+	) -> Result<Comment> {
+		self.call("edit_comment", json!({ "id": id, "data": comment_u }))
+			.await
+	}
+
+	pub async fn delete_comment(&self, id: i64) -> Result<Comment> {
+		self.call("delete_comment", json!({ "id": id })).await
+	}
+}
@@ -0,0 +1,55 @@
+use derive_more::From;
+use lib_rpc::ClientError;
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[serde_as]
+#[derive(Debug, Serialize, From)]
+pub enum Error {
+	// -- Rpc
+	/// The error response of the server (e.g., `ENTITY_NOT_FOUND`),
+	/// with its request uuid (for the server logs).
+	Rpc {
+		client_error: ClientError,
+		req_uuid: Option<String>,
+	},
+	/// An error response not matching a `ClientError` (e.g., a newer server).
+	RpcUnknownError {
+		code: i64,
+		message: String,
+	},
+	RpcResponseInvalid {
+		cause: &'static str,
+	},
+
+	// -- External Modules
+	#[from]
+	Reqwest(#[serde_as(as = "DisplayFromStr")] reqwest::Error),
+	#[from]
+	SerdeJson(#[serde_as(as = "DisplayFromStr")] serde_json::Error),
+}
+
+impl Error {
+	/// Returns the server `ClientError` (`None` for the other errors).
+	pub fn client_error(&self) -> Option<&ClientError> {
+		match self {
+			Error::Rpc { client_error, .. } => Some(client_error),
+			_ => None,
+		}
+	}
+}
+
+// region:    --- Error Boilerplate
+impl core::fmt::Display for Error {
+	fn fmt(
+		&self,
+		fmt: &mut core::fmt::Formatter,
+	) -> core::result::Result<(), core::fmt::Error> {
+		write!(fmt, "{self:?}")
+	}
+}
+
+impl std::error::Error for Error {}
+// endregion: --- Error Boilerplate
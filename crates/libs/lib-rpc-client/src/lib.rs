@@ -0,0 +1,36 @@
+//! The typed client of the web-server JSON-RPC API.
+//!
+//! The params and results are the `lib_core` model types, and the errors the
+//! `lib_rpc::ClientError` of the server, so they can't drift from the server.
+//!
+//! Note: The `filters` and `list_options` params are json values
+//!       (e.g., `json!({"title": {"$contains": "report"}})`), as the `modql`
+//!       filter types are not serializable.
+
+// region:    --- Modules
+
+mod attachment_rpc;
+mod audit_rpc;
+mod client;
+mod comment_rpc;
+mod error;
+mod tag_rpc;
+mod task_rpc;
+mod user_rpc;
+
+pub use self::client::RpcClient;
+pub use self::error::{Error, Result};
+pub use lib_rpc::ClientError;
+
+use serde::Deserialize;
+
+// endregion: --- Modules
+
+/// The result of one item of a bulk rpc call (e.g., `RpcClient::create_tasks`),
+/// where the error is the `ClientError` of the item.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemResult<T> {
+	Ok(T),
+	Error(ClientError),
+}
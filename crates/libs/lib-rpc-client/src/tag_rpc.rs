@@ -0,0 +1,43 @@
+use crate::{Result, RpcClient};
+use lib_core::model::tag::{Tag, TagForCreate, TagForUpdate, TagWithCount};
+use serde_json::{json, Value};
+
+impl RpcClient {
+	pub async fn create_tag(&self, tag_c: TagForCreate) -> Result<Tag> {
+		self.call("create_tag", json!({ "data": tag_c })).await
+	}
+
+	pub async fn list_tags(
+		&self,
+		filters: Option<Value>,
+		list_options: Option<Value>,
+	) -> Result<Vec<TagWithCount>> {
+		let params = json!({
+			"filters": filters,
+			"list_options": list_options,
+		});
+
+		self.call("list_tags", params).await
+	}
+
+	pub async fn update_tag(&self, id: i64, tag_u: TagForUpdate) -> Result<Tag> {
+		self.call("update_tag", json!({ "id": id, "data": tag_u }))
+			.await
+	}
+
+	pub async fn delete_tag(&self, id: i64) -> Result<Tag> {
+		self.call("delete_tag", json!({ "id": id })).await
+	}
+
+	pub async fn attach_tag(&self, task_id: i64, tag_id: i64) -> Result<()> {
+		let params = json!({ "task_id": task_id, "tag_id": tag_id });
+
+		self.call("attach_tag", params).await
+	}
+
+	pub async fn detach_tag(&self, task_id: i64, tag_id: i64) -> Result<()> {
+		let params = json!({ "task_id": task_id, "tag_id": tag_id });
+
+		self.call("detach_tag", params).await
+	}
+}
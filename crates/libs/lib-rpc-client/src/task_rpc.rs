@@ -0,0 +1,230 @@
+use crate::{ItemResult, Result, RpcClient};
+use lib_core::model::task::{
+	Task, TaskDeletePolicy, TaskForCreate, TaskForUpdate, TaskNode, TaskOccurrence,
+	TaskOccurrenceScope, TaskRevision, TaskSearchHit,
+};
+use lib_utils::time::format_time;
+use serde_json::{json, Value};
+use time::OffsetDateTime;
+
+impl RpcClient {
+	pub async fn create_task(&self, task_c: TaskForCreate) -> Result<Task> {
+		self.call("create_task", json!({ "data": task_c })).await
+	}
+
+	pub async fn get_task(&self, id: i64) -> Result<Task> {
+		self.call("get_task", json!({ "id": id })).await
+	}
+
+	/// Returns the task with only the `fields` (e.g., `&["id", "title"]`).
+	pub async fn get_task_fields(&self, id: i64, fields: &[&str]) -> Result<Value> {
+		self.call("get_task", json!({ "id": id, "fields": fields }))
+			.await
+	}
+
+	/// The `filters` and `list_options` are as the `list_tasks` params
+	/// (e.g., `json!({"status": {"$in": ["todo", "in_progress"]}})`).
+	pub async fn list_tasks(
+		&self,
+		filters: Option<Value>,
+		list_options: Option<Value>,
+	) -> Result<Vec<Task>> {
+		let params = json!({
+			"filters": filters,
+			"list_options": list_options,
+		});
+
+		self.call("list_tasks", params).await
+	}
+
+	/// Same as `list_tasks`, with only the `fields` of each task.
+	pub async fn list_tasks_fields(
+		&self,
+		filters: Option<Value>,
+		list_options: Option<Value>,
+		fields: &[&str],
+	) -> Result<Vec<Value>> {
+		let params = json!({
+			"filters": filters,
+			"list_options": list_options,
+			"fields": fields,
+		});
+
+		self.call("list_tasks", params).await
+	}
+
+	pub async fn search_tasks(
+		&self,
+		query: &str,
+		filters: Option<Value>,
+		list_options: Option<Value>,
+	) -> Result<Vec<TaskSearchHit>> {
+		let params = json!({
+			"query": query,
+			"filters": filters,
+			"list_options": list_options,
+		});
+
+		self.call("search_tasks", params).await
+	}
+
+	pub async fn update_task(&self, id: i64, task_u: TaskForUpdate) -> Result<Task> {
+		self.call("update_task", json!({ "id": id, "data": task_u }))
+			.await
+	}
+
+	pub async fn delete_task(
+		&self,
+		id: i64,
+		policy: TaskDeletePolicy,
+	) -> Result<Task> {
+		self.call("delete_task", json!({ "id": id, "policy": policy }))
+			.await
+	}
+
+	// -- Tree
+
+	pub async fn set_task_parent(
+		&self,
+		id: i64,
+		parent_id: Option<i64>,
+	) -> Result<Task> {
+		let params = json!({ "id": id, "parent_id": parent_id });
+
+		self.call("set_task_parent", params).await
+	}
+
+	pub async fn list_task_tree(
+		&self,
+		root_id: Option<i64>,
+	) -> Result<Vec<TaskNode>> {
+		self.call("list_task_tree", json!({ "root_id": root_id }))
+			.await
+	}
+
+	// -- Rank
+
+	/// Exactly one of `before_id` or `after_id` must be given.
+	pub async fn move_task(
+		&self,
+		id: i64,
+		before_id: Option<i64>,
+		after_id: Option<i64>,
+	) -> Result<Task> {
+		let params = json!({
+			"id": id,
+			"before_id": before_id,
+			"after_id": after_id,
+		});
+
+		self.call("move_task", params).await
+	}
+
+	// -- Assignment
+
+	pub async fn assign_task(&self, id: i64, assignee_id: i64) -> Result<Task> {
+		let params = json!({ "id": id, "assignee_id": assignee_id });
+
+		self.call("assign_task", params).await
+	}
+
+	pub async fn unassign_task(&self, id: i64) -> Result<Task> {
+		self.call("unassign_task", json!({ "id": id })).await
+	}
+
+	/// Returns the watcher ids.
+	pub async fn watch_task(&self, id: i64) -> Result<Vec<i64>> {
+		self.call("watch_task", json!({ "id": id })).await
+	}
+
+	/// Returns the watcher ids.
+	pub async fn unwatch_task(&self, id: i64) -> Result<Vec<i64>> {
+		self.call("unwatch_task", json!({ "id": id })).await
+	}
+
+	// -- Recurrence
+
+	pub async fn update_task_occurrence(
+		&self,
+		id: i64,
+		task_u: TaskForUpdate,
+		scope: TaskOccurrenceScope,
+	) -> Result<Task> {
+		let params = json!({ "id": id, "data": task_u, "scope": scope });
+
+		self.call("update_task_occurrence", params).await
+	}
+
+	pub async fn list_task_occurrences(
+		&self,
+		from: OffsetDateTime,
+		to: OffsetDateTime,
+		filters: Option<Value>,
+	) -> Result<Vec<TaskOccurrence>> {
+		let params = json!({
+			"from": format_time(from),
+			"to": format_time(to),
+			"filters": filters,
+		});
+
+		self.call("list_task_occurrences", params).await
+	}
+
+	// -- History
+
+	pub async fn get_task_history(&self, id: i64) -> Result<Vec<TaskRevision>> {
+		self.call("get_task_history", json!({ "id": id })).await
+	}
+
+	pub async fn revert_task(&self, id: i64, revision: i32) -> Result<Task> {
+		let params = json!({ "id": id, "revision": revision });
+
+		self.call("revert_task", params).await
+	}
+
+	// -- Bulk
+
+	pub async fn create_tasks(
+		&self,
+		task_cs: Vec<TaskForCreate>,
+		per_item: bool,
+	) -> Result<Vec<ItemResult<Task>>> {
+		let params = json!({ "data": task_cs, "per_item": per_item });
+
+		self.call("create_tasks", params).await
+	}
+
+	/// Exactly one of `ids` or `filters` must be given.
+	pub async fn update_tasks(
+		&self,
+		ids: Option<Vec<i64>>,
+		filters: Option<Value>,
+		task_u: TaskForUpdate,
+		per_item: bool,
+	) -> Result<Vec<ItemResult<Task>>> {
+		let params = json!({
+			"ids": ids,
+			"filters": filters,
+			"data": task_u,
+			"per_item": per_item,
+		});
+
+		self.call("update_tasks", params).await
+	}
+
+	/// Exactly one of `ids` or `filters` must be given.
+	pub async fn delete_tasks(
+		&self,
+		ids: Option<Vec<i64>>,
+		filters: Option<Value>,
+		per_item: bool,
+	) -> Result<Vec<ItemResult<i64>>> {
+		let params = json!({
+			"ids": ids,
+			"filters": filters,
+			"per_item": per_item,
+		});
+
+		self.call("delete_tasks", params).await
+	}
+}
@@ -0,0 +1,20 @@
+use crate::{Result, RpcClient};
+use lib_core::model::user::{User, UserForUpdate};
+use serde_json::json;
+
+impl RpcClient {
+	pub async fn get_me(&self) -> Result<User> {
+		self.call("get_me", json!({})).await
+	}
+
+	/// Note: A `username` change requires a new login.
+	pub async fn update_me(&self, user_u: UserForUpdate) -> Result<User> {
+		self.call("update_me", json!({ "data": user_u })).await
+	}
+
+	pub async fn change_password(&self, pwd_old: &str, pwd_new: &str) -> Result<()> {
+		let params = json!({ "pwd_old": pwd_old, "pwd_new": pwd_new });
+
+		self.call("change_password", params).await
+	}
+}
@@ -0,0 +1,47 @@
+use crate::ParamsIded;
+use crate::{Result, RpcMethodConfig, RpcRouter};
+use lib_core::ctx::Ctx;
+use lib_core::model::attachment::{Attachment, AttachmentBmc};
+use lib_core::model::ModelManager;
+use lib_utils::validate::Validate;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+pub fn rpc_router() -> RpcRouter {
+	crate::rpc_router!(
+		list_attachments,
+		delete_attachment => RpcMethodConfig::new().idempotent(),
+	)
+}
+
+/// Note: The attachments content is uploaded and downloaded with the
+///       `/api/tasks/:task_id/attachments` and `/api/attachments/:id` web routes.
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForListAttachments {
+	pub task_id: i64,
+}
+
+pub async fn list_attachments(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForListAttachments,
+) -> Result<Vec<Attachment>> {
+	let ParamsForListAttachments { task_id } = params;
+
+	let attachments = AttachmentBmc::list_for_task(&ctx, &mm, task_id).await?;
+
+	Ok(attachments)
+}
+
+pub async fn delete_attachment(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Attachment> {
+	let ParamsIded { id } = params;
+
+	let attachment = AttachmentBmc::get(&ctx, &mm, id).await?;
+	AttachmentBmc::delete(&ctx, &mm, id).await?;
+
+	Ok(attachment)
+}
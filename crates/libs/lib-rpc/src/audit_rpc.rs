@@ -0,0 +1,23 @@
+use crate::params::ParamsList;
+use crate::{Result, RpcMethodConfig, RpcPermission, RpcRouter};
+use lib_core::ctx::Ctx;
+use lib_core::model::audit::{AuditBmc, AuditEvent, AuditEventFilter};
+use lib_core::model::ModelManager;
+
+pub fn rpc_router() -> RpcRouter {
+	crate::rpc_router!(
+		list_audit_events => RpcMethodConfig::new().permission(RpcPermission::Admin),
+	)
+}
+
+/// Note: For the admins only (see `AuditBmc::list`, and its method permission).
+pub async fn list_audit_events(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<AuditEventFilter>,
+) -> Result<Vec<AuditEvent>> {
+	let events =
+		AuditBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+
+	Ok(events)
+}
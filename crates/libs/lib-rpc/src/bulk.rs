@@ -0,0 +1,52 @@
+//! Base constructs for the bulk rpc handler functions (e.g., `task_rpc::create_tasks`).
+//!
+//! The bulk functions run in one of two modes, selected by the `per_item` param:
+//!
+//! - Atomic (default): a single model layer bulk call, all items succeed or the rpc call fails.
+//! - Per item: each item runs on its own, and its error is reported in its `ItemResult`.
+//!   (Only meaningful for `ids` targets, filters always match rows that can be processed)
+//!
+
+use crate::{ClientError, Error, Result};
+use lib_core::model::{self, BulkTarget};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// The result of one item of a bulk rpc call,
+/// serialized as `{"ok": T}` or `{"error": ClientError}`.
+///
+/// Note: The item error is the `ClientError` of the model error,
+///       as for the rpc call error responses (never the internal error).
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(rename = "ItemResult_{T}")]
+pub enum ItemResult<T> {
+	Ok(T),
+	Error(ClientError),
+}
+
+impl<T> From<model::Result<T>> for ItemResult<T> {
+	fn from(res: model::Result<T>) -> Self {
+		match res {
+			Ok(val) => ItemResult::Ok(val),
+			Err(err) => ItemResult::Error(ClientError::from(&err)),
+		}
+	}
+}
+
+/// Builds the `BulkTarget` from the `ids` and `filters` params,
+/// making sure that exactly one of them is given.
+pub fn bulk_target<F>(
+	rpc_method: &str,
+	ids: Option<Vec<i64>>,
+	filters: Option<F>,
+) -> Result<BulkTarget<F>> {
+	match (ids, filters) {
+		(Some(ids), None) => Ok(BulkTarget::Ids(ids)),
+		(None, Some(filters)) => Ok(BulkTarget::Filters(filters)),
+		_ => Err(Error::RpcInvalidParams {
+			rpc_method: rpc_method.to_string(),
+			cause: "exactly one of 'ids' or 'filters' must be given",
+		}),
+	}
+}
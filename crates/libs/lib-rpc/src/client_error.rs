@@ -0,0 +1,336 @@
+//! The errors returned to the clients, in the JSON-RPC error responses,
+//! as `{"code": ..., "message": "<ClientError variant>", "data": {"detail": ...}}`.
+//!
+//! Note: Shared by the web-server (from its errors) and the rpc clients
+//!       (e.g., `lib-rpc-client`), so that they cannot drift.
+
+use crate::OpenRpcError;
+use lib_core::model;
+use lib_core::model::task::TaskStatus;
+use lib_utils::time;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+	Debug,
+	Clone,
+	PartialEq,
+	Serialize,
+	Deserialize,
+	JsonSchema,
+	strum_macros::AsRefStr,
+)]
+#[serde(tag = "message", content = "detail")]
+#[allow(non_camel_case_types)]
+pub enum ClientError {
+	// -- JSON-RPC
+	PARSE_ERROR,
+	INVALID_REQUEST,
+	METHOD_NOT_FOUND,
+	INVALID_PARAMS,
+
+	LOGIN_FAIL,
+	NO_AUTH,
+	ADMIN_REQUIRED,
+	PWD_NOT_MATCHING,
+	ACCESS_DENIED {
+		entity: String,
+		id: i64,
+	},
+	ENTITY_NOT_FOUND {
+		entity: String,
+		id: i64,
+	},
+	UNIQUE_VIOLATION {
+		entity: String,
+		field: String,
+	},
+	FOREIGN_KEY_VIOLATION {
+		entity: String,
+		field: String,
+	},
+	CHECK_VIOLATION {
+		entity: String,
+		constraint: String,
+	},
+	ATTACHMENT_INVALID,
+	ATTACHMENT_CONTENT_TYPE_NOT_ALLOWED,
+	ATTACHMENT_SIZE_OVER_MAX {
+		max: usize,
+	},
+	VALIDATION_FAILED {
+		field: String,
+		rule: String,
+		message: String,
+	},
+	LIST_LIMIT_OVER_MAX {
+		max: i64,
+	},
+	BULK_SIZE_OVER_MAX {
+		max: usize,
+	},
+	BULK_FILTERS_EMPTY,
+	RECURRENCE_INVALID {
+		rule: String,
+		cause: String,
+	},
+	IDEMPOTENCY_KEY_REUSED,
+	IDEMPOTENCY_KEY_IN_PROGRESS,
+	RATE_LIMITED {
+		retry_after_sec: u64,
+	},
+	TASK_STATUS_TRANSITION_INVALID {
+		id: i64,
+		from: TaskStatus,
+		to: TaskStatus,
+	},
+	TASK_PARENT_CYCLE {
+		id: i64,
+		parent_id: i64,
+	},
+	TASK_TREE_DEPTH_OVER_MAX {
+		max: i32,
+	},
+	TASK_RANK_SCOPE_MISMATCH {
+		id: i64,
+		anchor_id: i64,
+	},
+	TASK_RANK_INVALID,
+	TASK_REVISION_NOT_FOUND {
+		id: i64,
+		revision: i32,
+	},
+	TASK_NOT_VISIBLE {
+		id: i64,
+		user_id: i64,
+	},
+
+	SERVICE_ERROR,
+}
+
+impl ClientError {
+	/// The JSON-RPC error code, standard for the protocol errors (and the
+	/// `SERVICE_ERROR` internal error), and application defined otherwise
+	/// (outside of the reserved -32768 to -32000 range).
+	pub fn code(&self) -> i64 {
+		use ClientError::*;
+
+		match self {
+			// -- JSON-RPC standard
+			PARSE_ERROR => -32700,
+			INVALID_REQUEST => -32600,
+			METHOD_NOT_FOUND => -32601,
+			INVALID_PARAMS => -32602,
+			SERVICE_ERROR => -32603,
+
+			// -- Application
+			LOGIN_FAIL => 1000,
+			NO_AUTH => 1001,
+			ADMIN_REQUIRED => 1002,
+			PWD_NOT_MATCHING => 1003,
+			ACCESS_DENIED { .. } => 1004,
+			ENTITY_NOT_FOUND { .. } => 2000,
+			UNIQUE_VIOLATION { .. } => 2001,
+			FOREIGN_KEY_VIOLATION { .. } => 2002,
+			CHECK_VIOLATION { .. } => 2003,
+			ATTACHMENT_INVALID => 3000,
+			ATTACHMENT_CONTENT_TYPE_NOT_ALLOWED => 3001,
+			ATTACHMENT_SIZE_OVER_MAX { .. } => 3002,
+			VALIDATION_FAILED { .. } => 4000,
+			LIST_LIMIT_OVER_MAX { .. } => 4001,
+			BULK_SIZE_OVER_MAX { .. } => 4002,
+			BULK_FILTERS_EMPTY => 4003,
+			RECURRENCE_INVALID { .. } => 4004,
+			IDEMPOTENCY_KEY_REUSED => 5000,
+			IDEMPOTENCY_KEY_IN_PROGRESS => 5001,
+			RATE_LIMITED { .. } => 6000,
+			TASK_STATUS_TRANSITION_INVALID { .. } => 7000,
+			TASK_PARENT_CYCLE { .. } => 7001,
+			TASK_TREE_DEPTH_OVER_MAX { .. } => 7002,
+			TASK_RANK_SCOPE_MISMATCH { .. } => 7003,
+			TASK_RANK_INVALID => 7004,
+			TASK_REVISION_NOT_FOUND { .. } => 7005,
+			TASK_NOT_VISIBLE { .. } => 7006,
+		}
+	}
+
+	/// The errors of the rpc methods, for the OpenRPC document.
+	///
+	/// Note: Without the request errors (e.g., `METHOD_NOT_FOUND`),
+	///       and the login and attachment routes errors.
+	pub fn openrpc_errors() -> Vec<OpenRpcError> {
+		use ClientError::*;
+
+		let errors = [
+			INVALID_PARAMS,
+			NO_AUTH,
+			ADMIN_REQUIRED,
+			PWD_NOT_MATCHING,
+			ACCESS_DENIED {
+				entity: String::new(),
+				id: 0,
+			},
+			ENTITY_NOT_FOUND {
+				entity: String::new(),
+				id: 0,
+			},
+			UNIQUE_VIOLATION {
+				entity: String::new(),
+				field: String::new(),
+			},
+			FOREIGN_KEY_VIOLATION {
+				entity: String::new(),
+				field: String::new(),
+			},
+			CHECK_VIOLATION {
+				entity: String::new(),
+				constraint: String::new(),
+			},
+			VALIDATION_FAILED {
+				field: String::new(),
+				rule: String::new(),
+				message: String::new(),
+			},
+			LIST_LIMIT_OVER_MAX { max: 0 },
+			BULK_SIZE_OVER_MAX { max: 0 },
+			BULK_FILTERS_EMPTY,
+			RECURRENCE_INVALID {
+				rule: String::new(),
+				cause: String::new(),
+			},
+			IDEMPOTENCY_KEY_REUSED,
+			IDEMPOTENCY_KEY_IN_PROGRESS,
+			RATE_LIMITED { retry_after_sec: 0 },
+			TASK_STATUS_TRANSITION_INVALID {
+				id: 0,
+				from: TaskStatus::Todo,
+				to: TaskStatus::Todo,
+			},
+			TASK_PARENT_CYCLE {
+				id: 0,
+				parent_id: 0,
+			},
+			TASK_TREE_DEPTH_OVER_MAX { max: 0 },
+			TASK_RANK_SCOPE_MISMATCH {
+				id: 0,
+				anchor_id: 0,
+			},
+			TASK_RANK_INVALID,
+			TASK_REVISION_NOT_FOUND { id: 0, revision: 0 },
+			TASK_NOT_VISIBLE { id: 0, user_id: 0 },
+			SERVICE_ERROR,
+		];
+
+		errors
+			.iter()
+			.map(|error| OpenRpcError {
+				code: error.code(),
+				message: error.as_ref().to_string(),
+			})
+			.collect()
+	}
+}
+
+// region:    --- From Model Error
+
+/// From the model error, to its `ClientError`
+/// (`SERVICE_ERROR` for the internal errors, e.g., the `Sqlx` errors).
+///
+/// Note: Shared by the web-server error responses, and the bulk methods
+///       item errors (see `ItemResult`), so that they cannot drift.
+impl From<&model::Error> for ClientError {
+	fn from(err: &model::Error) -> Self {
+		use model::Error::*;
+
+		match err {
+			EntityNotFound { entity, id } => ClientError::ENTITY_NOT_FOUND {
+				entity: entity.to_string(),
+				id: *id,
+			},
+			AccessDenied { entity, id } => ClientError::ACCESS_DENIED {
+				entity: entity.to_string(),
+				id: *id,
+			},
+			AdminRequired { .. } => ClientError::ADMIN_REQUIRED,
+			UserPwdNotMatching { .. } => ClientError::PWD_NOT_MATCHING,
+			ListLimitOverMax { max, .. } => {
+				ClientError::LIST_LIMIT_OVER_MAX { max: *max }
+			}
+			BulkSizeOverMax { max, .. } => {
+				ClientError::BULK_SIZE_OVER_MAX { max: *max }
+			}
+			BulkFiltersEmpty => ClientError::BULK_FILTERS_EMPTY,
+			ProjectionFieldUnknown { .. } | ProjectionFieldsEmpty => {
+				ClientError::INVALID_PARAMS
+			}
+			IdempotencyKeyReused { .. } => ClientError::IDEMPOTENCY_KEY_REUSED,
+			IdempotencyKeyInProgress { .. } | IdempotencyKeyLeaseLost { .. } => {
+				ClientError::IDEMPOTENCY_KEY_IN_PROGRESS
+			}
+
+			// -- Constraint Violations
+			UniqueViolation { entity, field } => ClientError::UNIQUE_VIOLATION {
+				entity: entity.to_string(),
+				field: field.to_string(),
+			},
+			ForeignKeyViolation { entity, field } => {
+				ClientError::FOREIGN_KEY_VIOLATION {
+					entity: entity.to_string(),
+					field: field.to_string(),
+				}
+			}
+			CheckViolation { entity, constraint } => ClientError::CHECK_VIOLATION {
+				entity: entity.to_string(),
+				constraint: constraint.to_string(),
+			},
+
+			// -- Task
+			TaskStatusTransitionInvalid { id, from, to } => {
+				ClientError::TASK_STATUS_TRANSITION_INVALID {
+					id: *id,
+					from: *from,
+					to: *to,
+				}
+			}
+			TaskParentCycle { id, parent_id } => ClientError::TASK_PARENT_CYCLE {
+				id: *id,
+				parent_id: *parent_id,
+			},
+			TaskTreeDepthOverMax { max, .. } => {
+				ClientError::TASK_TREE_DEPTH_OVER_MAX { max: *max }
+			}
+			TaskRankScopeMismatch { id, anchor_id } => {
+				ClientError::TASK_RANK_SCOPE_MISMATCH {
+					id: *id,
+					anchor_id: *anchor_id,
+				}
+			}
+			TaskRevisionNotFound { id, revision } => {
+				ClientError::TASK_REVISION_NOT_FOUND {
+					id: *id,
+					revision: *revision,
+				}
+			}
+			TaskNotVisible { id, user_id } => ClientError::TASK_NOT_VISIBLE {
+				id: *id,
+				user_id: *user_id,
+			},
+
+			// -- Modules
+			Rank(_) => ClientError::TASK_RANK_INVALID,
+			Time(time::Error::RecurrenceInvalid { rule, cause }) => {
+				ClientError::RECURRENCE_INVALID {
+					rule: rule.to_string(),
+					cause: cause.to_string(),
+				}
+			}
+			Time(time::Error::FailToDateParse(_)) => ClientError::INVALID_PARAMS,
+
+			// -- Internals
+			Pwd(_) | Store(_) | Blob(_) | Sqlx(_) | SeaQuery(_)
+			| ModqlIntoSea(_) | SerdeJson(_) => ClientError::SERVICE_ERROR,
+		}
+	}
+}
+
+// endregion: --- From Model Error
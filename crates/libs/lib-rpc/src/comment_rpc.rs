@@ -0,0 +1,85 @@
+use crate::{ParamsForCreate, ParamsForUpdate, ParamsIded};
+use crate::{Result, RpcMethodConfig, RpcRouter};
+use lib_core::ctx::Ctx;
+use lib_core::model::comment::{
+	Comment, CommentBmc, CommentForCreate, CommentForUpdate,
+};
+use lib_core::model::schema::ListOptionsSchema;
+use lib_core::model::ModelManager;
+use lib_utils::validate::Validate;
+use modql::filter::ListOptions;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+pub fn rpc_router() -> RpcRouter {
+	crate::rpc_router!(
+		add_comment => RpcMethodConfig::new().idempotent(),
+		list_comments,
+		edit_comment => RpcMethodConfig::new().idempotent(),
+		delete_comment => RpcMethodConfig::new().idempotent(),
+	)
+}
+
+/// Params for `list_comments`, paginated with the `list_options` limit and offset.
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForListComments {
+	pub task_id: i64,
+	#[schemars(with = "Option<ListOptionsSchema>")]
+	pub list_options: Option<ListOptions>,
+}
+
+pub async fn add_comment(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreate<CommentForCreate>,
+) -> Result<Comment> {
+	let ParamsForCreate { data } = params;
+
+	let id = CommentBmc::create(&ctx, &mm, data).await?;
+	let comment = CommentBmc::get(&ctx, &mm, id).await?;
+
+	Ok(comment)
+}
+
+pub async fn list_comments(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForListComments,
+) -> Result<Vec<Comment>> {
+	let ParamsForListComments {
+		task_id,
+		list_options,
+	} = params;
+
+	let comments =
+		CommentBmc::list_for_task(&ctx, &mm, task_id, list_options).await?;
+
+	Ok(comments)
+}
+
+pub async fn edit_comment(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdate<CommentForUpdate>,
+) -> Result<Comment> {
+	let ParamsForUpdate { id, data } = params;
+
+	CommentBmc::update(&ctx, &mm, id, data).await?;
+
+	let comment = CommentBmc::get(&ctx, &mm, id).await?;
+
+	Ok(comment)
+}
+
+pub async fn delete_comment(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Comment> {
+	let ParamsIded { id } = params;
+
+	let comment = CommentBmc::get(&ctx, &mm, id).await?;
+	CommentBmc::delete(&ctx, &mm, id).await?;
+
+	Ok(comment)
+}
@@ -1,5 +1,6 @@
 use derive_more::From;
 use lib_core::model;
+use lib_utils::validate;
 use serde::Serialize;
 use serde_with::{serde_as, DisplayFromStr};
 
@@ -8,6 +9,11 @@ pub type Result<T> = core::result::Result<T, Error>;
 #[serde_as]
 #[derive(Debug, Serialize, From)]
 pub enum Error {
+	// -- JSON-RPC
+	RpcParseError,
+	RpcInvalidRequest {
+		cause: &'static str,
+	},
 	RpcMethodUnknown(String),
 	RpcMissingParams {
 		rpc_method: String,
@@ -15,6 +21,19 @@ pub enum Error {
 	RpcFailJsonParams {
 		rpc_method: String,
 	},
+	RpcInvalidParams {
+		rpc_method: String,
+		cause: &'static str,
+	},
+	RpcRateLimited {
+		rpc_method: String,
+		retry_after_sec: u64,
+	},
+	ValidationFailed {
+		field: String,
+		rule: &'static str,
+		message: String,
+	},
 
 	// -- Modules
 	#[from]
@@ -25,6 +44,21 @@ pub enum Error {
 	SerdeJson(#[serde_as(as = "DisplayFromStr")] serde_json::Error),
 }
 
+impl From<validate::Error> for Error {
+	fn from(err: validate::Error) -> Self {
+		let validate::Error {
+			field,
+			rule,
+			message,
+		} = err;
+		Self::ValidationFailed {
+			field,
+			rule,
+			message,
+		}
+	}
+}
+
 // region:    --- Error Boilerplate
 impl core::fmt::Display for Error {
 	fn fmt(
@@ -0,0 +1,83 @@
+//! The JSON-RPC 2.0 request parsing (single call or batch), and validation.
+//!
+//! Note: The responses are built by the transports (e.g., the web-server
+//!       `/api/rpc` and `/api/ws`), as the error codes come from their errors.
+
+use crate::{Error, Result, RpcRequest};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// The required `jsonrpc` value of the requests.
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// The calls of a request body, not yet validated (see `RpcRequest::from_value`).
+pub enum RpcBody {
+	Single(Value),
+	Batch(Vec<Value>),
+}
+
+/// Parses a request body, failing with `RpcParseError` on invalid json,
+/// and with `RpcInvalidRequest` on an empty batch.
+pub fn parse_rpc_body(body: &[u8]) -> Result<RpcBody> {
+	let body: Value =
+		serde_json::from_slice(body).map_err(|_| Error::RpcParseError)?;
+
+	match body {
+		Value::Array(calls) if calls.is_empty() => Err(Error::RpcInvalidRequest {
+			cause: "empty batch",
+		}),
+		Value::Array(calls) => Ok(RpcBody::Batch(calls)),
+		call => Ok(RpcBody::Single(call)),
+	}
+}
+
+impl RpcRequest {
+	/// Returns the validated request, or the `RpcInvalidRequest` error with
+	/// the request id (when it could be read, otherwise, `Value::Null`).
+	pub fn from_value(
+		value: Value,
+	) -> core::result::Result<RpcRequest, (Value, Error)> {
+		let id = match value.get("id") {
+			Some(id @ (Value::Null | Value::Number(_) | Value::String(_))) => {
+				id.clone()
+			}
+			Some(_) => {
+				return Err((
+					Value::Null,
+					invalid_request("id not string or number"),
+				))
+			}
+			None => Value::Null,
+		};
+
+		let rpc_req: RpcRequest = serde_json::from_value(value)
+			.map_err(|_| (id.clone(), invalid_request("not a request object")))?;
+		if rpc_req.jsonrpc != JSONRPC_VERSION {
+			return Err((id, invalid_request("jsonrpc not \"2.0\"")));
+		}
+		if matches!(rpc_req.params, Some(ref p) if !p.is_object() && !p.is_array()) {
+			return Err((id, invalid_request("params not object or array")));
+		}
+
+		Ok(rpc_req)
+	}
+
+	/// A request without id gets no response.
+	pub fn is_notification(&self) -> bool {
+		self.id.is_none()
+	}
+}
+
+fn invalid_request(cause: &'static str) -> Error {
+	Error::RpcInvalidRequest { cause }
+}
+
+/// Deserializes a present `id` as `Some` (even when `null`).
+pub(crate) fn deserialize_id<'de, D>(
+	deserializer: D,
+) -> core::result::Result<Option<Value>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	Value::deserialize(deserializer).map(Some)
+}
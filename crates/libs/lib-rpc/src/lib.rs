@@ -1,70 +1,69 @@
 // region:    --- Modules
 
+mod attachment_rpc;
+mod audit_rpc;
+mod bulk;
+mod client_error;
+mod comment_rpc;
 mod error;
+mod jsonrpc;
 mod params;
+mod projection;
+mod router;
+mod tag_rpc;
 mod task_rpc;
+mod user_rpc;
 
+pub use self::client_error::ClientError;
 pub use self::error::{Error, Result};
+pub use self::jsonrpc::{parse_rpc_body, RpcBody, JSONRPC_VERSION};
+pub use self::router::{
+	FromResources, IntoParams, MetricsMiddleware, Next, OpenRpcError,
+	PermissionMiddleware, PinFutureValue, RateLimit, RateLimitMiddleware, RpcCall,
+	RpcCallMetric, RpcHandler, RpcMethodConfig, RpcMiddleware, RpcPermission,
+	RpcResources, RpcRouter, SlowCallMiddleware, IDEMPOTENCY_KEY_MAX_LEN,
+	IDEMPOTENCY_LEASE_DEFAULT, RPC_DISCOVER_METHOD,
+};
 use params::*;
 
-use lib_core::ctx::Ctx;
-use lib_core::model::ModelManager;
 use serde::Deserialize;
-use serde_json::{from_value, to_value, Value};
-use task_rpc::{create_task, delete_task, list_tasks, update_task};
+use serde_json::Value;
 
 // endregion: --- Modules
 
 // region:    --- RPC Types
 
-/// The raw JSON-RPC request object, serving as the foundation for RPC routing.
+/// The raw JSON-RPC 2.0 request object, serving as the foundation for RPC routing.
+/// (See `RpcRequest::from_value` for the validation)
 #[derive(Deserialize)]
 pub struct RpcRequest {
+	pub jsonrpc: String,
+	/// `None` when the request has no `id` member (i.e., a notification),
+	/// and `Some(Value::Null)` for an explicit `"id": null`.
+	#[serde(default, deserialize_with = "jsonrpc::deserialize_id")]
 	pub id: Option<Value>,
 	pub method: String,
 	pub params: Option<Value>,
+	/// The optional key of a mutating method call, to replay its first result
+	/// on a retry (see `RpcRouter::call`).
+	///
+	/// Note: An extension member of the JSON-RPC request object.
+	pub idempotency_key: Option<String>,
 }
 
-macro_rules! exec_rpc_fn {
-	// With Params
-	($rpc_fn:expr, $ctx:expr, $mm:expr, $rpc_params:expr) => {{
-		let rpc_fn_name = stringify!($rpc_fn);
-		let params = $rpc_params.ok_or(Error::RpcMissingParams {
-			rpc_method: rpc_fn_name.to_string(),
-		})?;
-		let params = from_value(params).map_err(|_| Error::RpcFailJsonParams {
-			rpc_method: rpc_fn_name.to_string(),
-		})?;
-		$rpc_fn($ctx, $mm, params).await.map(to_value)?? // FIXME
-	}};
-
-	// Without Params
-	($rpc_fn:expr, $ctx:expr, $mm:expr) => {
-		$rpc_fn($ctx, $mm).await.map(to_value)??
-	};
-}
-
-pub async fn exec_rpc(
-	ctx: Ctx,
-	mm: ModelManager,
-	rpc_req: RpcRequest,
-) -> Result<Value> {
-	let rpc_method = rpc_req.method;
-	let rpc_params = rpc_req.params;
-
-	// -- Exec & Store RpcInfo in response.
-	let result_json: Value = match rpc_method.as_str() {
-		// -- Task RPC methods.
-		"create_task" => exec_rpc_fn!(create_task, ctx, mm, rpc_params),
-		"list_tasks" => exec_rpc_fn!(list_tasks, ctx, mm, rpc_params),
-		"update_task" => exec_rpc_fn!(update_task, ctx, mm, rpc_params),
-		"delete_task" => exec_rpc_fn!(delete_task, ctx, mm, rpc_params),
+// endregion: --- RPC Types
 
-		// -- Fallback as Err.
-		_ => return Err(Error::RpcMethodUnknown(rpc_method)),
-	};
+// region:    --- RPC Router
 
-	Ok(result_json)
+/// Returns the router of all the RPC methods (the merged domain routers).
+pub fn all_rpc_router() -> RpcRouter {
+	RpcRouter::new()
+		.merge(task_rpc::rpc_router())
+		.merge(comment_rpc::rpc_router())
+		.merge(attachment_rpc::rpc_router())
+		.merge(tag_rpc::rpc_router())
+		.merge(audit_rpc::rpc_router())
+		.merge(user_rpc::rpc_router())
 }
 
-// endregion: --- RPC Types
+// endregion: --- RPC Router
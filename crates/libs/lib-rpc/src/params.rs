@@ -4,35 +4,128 @@
 //! Most of these base constructs use generics for their respective data elements, allowing
 //! each rpc handler function to receive the exact desired type.
 //!
+//! All params are `Validate`, and validated before their rpc handler function call
+//! (see `IntoParams`), with the `data` elements validated as `nested`.
+//!
 
+use lib_core::model::schema::{ListOptionsSchema, OneOrManySchema};
+use lib_utils::validate::Validate;
 use modql::filter::ListOptions;
+use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde_with::{serde_as, OneOrMany};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema, Validate)]
 pub struct ParamsForCreate<D> {
+	#[validate(nested)]
 	pub data: D,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema, Validate)]
 pub struct ParamsForUpdate<D> {
 	pub id: i64,
+	#[validate(nested)]
 	pub data: D,
 }
 
-#[derive(Deserialize)]
+/// Params for the bulk create rpc handler functions (e.g., `task_rpc::create_tasks`).
+/// When `per_item` is false (default), all items are created or none.
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForCreateMany<D> {
+	#[validate(nested)]
+	pub data: Vec<D>,
+	#[serde(default)]
+	pub per_item: bool,
+}
+
+/// Params for the bulk update rpc handler functions (e.g., `task_rpc::update_tasks`).
+/// Exactly one of `ids` or `filters` must be given.
+#[serde_as]
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForUpdateMany<D, F>
+where
+	F: DeserializeOwned,
+{
+	pub ids: Option<Vec<i64>>,
+	#[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
+	#[schemars(with = "Option<OneOrManySchema<F>>")]
+	pub filters: Option<Vec<F>>,
+	#[validate(nested)]
+	pub data: D,
+	#[serde(default)]
+	pub per_item: bool,
+}
+
+#[derive(Deserialize, JsonSchema, Validate)]
 pub struct ParamsIded {
 	pub id: i64,
 }
 
+/// Params for the get rpc handler functions with the `fields` projection
+/// (e.g., `task_rpc::get_task`), where `fields` are the entity field names.
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsGet {
+	pub id: i64,
+	pub fields: Option<Vec<String>>,
+}
+
 #[serde_as]
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema, Validate)]
 pub struct ParamsList<F>
 where
 	F: DeserializeOwned,
 {
 	#[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
+	#[schemars(with = "Option<OneOrManySchema<F>>")]
 	pub filters: Option<Vec<F>>,
+	#[schemars(with = "Option<ListOptionsSchema>")]
 	pub list_options: Option<ListOptions>,
 }
+
+/// Same as `ParamsList`, with the `fields` projection (see `ParamsGet`).
+#[serde_as]
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsListProjected<F>
+where
+	F: DeserializeOwned,
+{
+	#[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
+	#[schemars(with = "Option<OneOrManySchema<F>>")]
+	pub filters: Option<Vec<F>>,
+	#[schemars(with = "Option<ListOptionsSchema>")]
+	pub list_options: Option<ListOptions>,
+	pub fields: Option<Vec<String>>,
+}
+
+/// Params for the full-text search rpc handler functions (e.g., `task_rpc::search_tasks`),
+/// which can be combined with the same `filters` and `list_options` as `ParamsList`.
+#[serde_as]
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsSearch<F>
+where
+	F: DeserializeOwned,
+{
+	pub query: String,
+	#[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
+	#[schemars(with = "Option<OneOrManySchema<F>>")]
+	pub filters: Option<Vec<F>>,
+	#[schemars(with = "Option<ListOptionsSchema>")]
+	pub list_options: Option<ListOptions>,
+}
+
+/// Params for the bulk delete rpc handler functions (e.g., `task_rpc::delete_tasks`).
+/// Exactly one of `ids` or `filters` must be given.
+#[serde_as]
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForDeleteMany<F>
+where
+	F: DeserializeOwned,
+{
+	pub ids: Option<Vec<i64>>,
+	#[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
+	#[schemars(with = "Option<OneOrManySchema<F>>")]
+	pub filters: Option<Vec<F>>,
+	#[serde(default)]
+	pub per_item: bool,
+}
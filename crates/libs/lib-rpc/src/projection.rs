@@ -0,0 +1,34 @@
+//! The result of the rpc handler functions with the `fields` projection param
+//! (e.g., `task_rpc::get_task`).
+
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::Serialize;
+use serde_json::Value;
+use std::borrow::Cow;
+
+/// The full entity without `fields`, otherwise, only the requested fields
+/// (as selected by the model layer, e.g., `TaskBmc::get_projected`).
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum Projected<T> {
+	Full(T),
+	Fields(Value),
+}
+
+/// The schema of `T` without required properties, as any of them can be
+/// left out by the projection.
+impl<T: JsonSchema> JsonSchema for Projected<T> {
+	fn schema_name() -> Cow<'static, str> {
+		format!("{}Projection", T::schema_name()).into()
+	}
+
+	fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+		let mut schema = T::json_schema(generator);
+		schema.remove("required");
+		schema.insert(
+			"description".into(),
+			format!("The `{}` fields of the projection.", T::schema_name()).into(),
+		);
+		schema
+	}
+}
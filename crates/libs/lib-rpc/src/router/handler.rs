@@ -0,0 +1,158 @@
+use crate::router::{FromResources, IntoParams, RpcResources};
+use crate::Result;
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::Serialize;
+use serde_json::{to_value, Value};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+pub type PinFutureValue = Pin<Box<dyn Future<Output = Result<Value>> + Send>>;
+
+/// The async functions usable as rpc handlers, with their `FromResources`
+/// arguments first (e.g., `Ctx` and `ModelManager`), and optionally a last
+/// `IntoParams` argument, returning a `Result` of a serializable value.
+///
+/// `T` is the tuple of the resource arguments, and `P` is `(P,)` with
+/// a params argument, otherwise, `()` (for the impls not to overlap).
+///
+/// The params and result types are `JsonSchema`, for the OpenRPC document
+/// (see `RpcRouter::openrpc_document`).
+pub trait RpcHandler<T, P>: Clone + Send + Sync + Sized + 'static {
+	fn call(
+		self,
+		resources: RpcResources,
+		rpc_method: String,
+		params: Option<Value>,
+	) -> PinFutureValue;
+
+	/// The params object schema (`None` without params argument).
+	fn params_schema(generator: &mut SchemaGenerator) -> Option<Schema>;
+
+	fn result_schema(generator: &mut SchemaGenerator) -> Schema;
+}
+
+macro_rules! impl_rpc_handler {
+	($($T:ident),*) => {
+		// -- Without params
+		impl<F, Fut, $($T,)* R> RpcHandler<($($T,)*), ()> for F
+		where
+			F: FnOnce($($T),*) -> Fut + Clone + Send + Sync + 'static,
+			Fut: Future<Output = Result<R>> + Send,
+			$($T: FromResources + Send,)*
+			R: Serialize + JsonSchema,
+		{
+			#[allow(non_snake_case, unused_variables)]
+			fn call(
+				self,
+				resources: RpcResources,
+				_rpc_method: String,
+				_params: Option<Value>,
+			) -> PinFutureValue {
+				Box::pin(async move {
+					$(let $T = $T::from_resources(&resources)?;)*
+					let result = self($($T),*).await?;
+					Ok(to_value(result)?)
+				})
+			}
+
+			fn params_schema(_generator: &mut SchemaGenerator) -> Option<Schema> {
+				None
+			}
+
+			fn result_schema(generator: &mut SchemaGenerator) -> Schema {
+				generator.subschema_for::<R>()
+			}
+		}
+
+		// -- With params
+		impl<F, Fut, $($T,)* P, R> RpcHandler<($($T,)*), (P,)> for F
+		where
+			F: FnOnce($($T,)* P) -> Fut + Clone + Send + Sync + 'static,
+			Fut: Future<Output = Result<R>> + Send,
+			$($T: FromResources + Send,)*
+			P: IntoParams + JsonSchema,
+			R: Serialize + JsonSchema,
+		{
+			#[allow(non_snake_case, unused_variables)]
+			fn call(
+				self,
+				resources: RpcResources,
+				rpc_method: String,
+				params: Option<Value>,
+			) -> PinFutureValue {
+				Box::pin(async move {
+					$(let $T = $T::from_resources(&resources)?;)*
+					let params = P::into_params(&rpc_method, params)?;
+					let result = self($($T,)* params).await?;
+					Ok(to_value(result)?)
+				})
+			}
+
+			fn params_schema(generator: &mut SchemaGenerator) -> Option<Schema> {
+				Some(P::json_schema(generator))
+			}
+
+			fn result_schema(generator: &mut SchemaGenerator) -> Schema {
+				generator.subschema_for::<R>()
+			}
+		}
+	};
+}
+
+impl_rpc_handler!();
+impl_rpc_handler!(T1);
+impl_rpc_handler!(T1, T2);
+impl_rpc_handler!(T1, T2, T3);
+
+// region:    --- Dyn Handler
+/// The type erased `RpcHandler`, as stored by the `RpcRouter`.
+pub(super) trait RpcHandlerDyn: Send + Sync {
+	fn call(
+		&self,
+		resources: RpcResources,
+		rpc_method: String,
+		params: Option<Value>,
+	) -> PinFutureValue;
+
+	fn params_schema(&self, generator: &mut SchemaGenerator) -> Option<Schema>;
+
+	fn result_schema(&self, generator: &mut SchemaGenerator) -> Schema;
+}
+
+pub(super) struct RpcHandlerWrapper<H, T, P> {
+	handler: H,
+	_marker: PhantomData<fn() -> (T, P)>,
+}
+
+impl<H, T, P> RpcHandlerWrapper<H, T, P> {
+	pub(super) fn new(handler: H) -> Self {
+		Self {
+			handler,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<H, T, P> RpcHandlerDyn for RpcHandlerWrapper<H, T, P>
+where
+	H: RpcHandler<T, P>,
+{
+	fn call(
+		&self,
+		resources: RpcResources,
+		rpc_method: String,
+		params: Option<Value>,
+	) -> PinFutureValue {
+		self.handler.clone().call(resources, rpc_method, params)
+	}
+
+	fn params_schema(&self, generator: &mut SchemaGenerator) -> Option<Schema> {
+		H::params_schema(generator)
+	}
+
+	fn result_schema(&self, generator: &mut SchemaGenerator) -> Schema {
+		H::result_schema(generator)
+	}
+}
+// endregion: --- Dyn Handler
@@ -0,0 +1,233 @@
+//! The idempotent calls of the mutating methods (configured with
+//! `RpcMethodConfig::idempotent`), with an idempotency key
+//! (see `RpcRequest::idempotency_key`), which replay the result of the first
+//! call with the key (e.g., on a client retry after a network failure).
+//!
+//! The key is per user, and the first call params must be the same (by their hash).
+//! The failed calls are not stored, so that they can be retried with the same key,
+//! and the cancelled ones (e.g., on a client disconnect) hold the key until
+//! their lease expires (see `IdempotencyKeyBmc::reserve`).
+
+use crate::router::handler::RpcHandlerDyn;
+use crate::router::RpcResources;
+use crate::{Error, Result};
+use lib_core::model::idempotency::{IdempotencyKeyBmc, IdempotencyReservation};
+use lib_utils::b64::b64u_encode;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tracing::error;
+
+/// The max length of the idempotency keys.
+pub const IDEMPOTENCY_KEY_MAX_LEN: usize = 128;
+
+/// The default lease of the idempotency keys, which must be longer than
+/// the calls (see `RpcMethodConfig::idempotent_lease` for the longer ones).
+pub const IDEMPOTENCY_LEASE_DEFAULT: Duration = Duration::from_secs(60);
+
+/// Calls the handler once for the key, and returns the result of the first call
+/// on the next calls with the key.
+pub(super) async fn call_idempotent(
+	handler: &dyn RpcHandlerDyn,
+	resources: RpcResources,
+	rpc_method: String,
+	params: Option<Value>,
+	key: String,
+	lease: Duration,
+) -> Result<Value> {
+	if key.is_empty() || key.len() > IDEMPOTENCY_KEY_MAX_LEN {
+		return Err(Error::RpcInvalidRequest {
+			cause: "idempotency_key not 1 to 128 chars",
+		});
+	}
+
+	let RpcResources { ctx, mm } = resources.clone();
+	let request_hash = request_hash(&rpc_method, params.as_ref());
+
+	let lease =
+		match IdempotencyKeyBmc::reserve(&ctx, &mm, &key, &request_hash, lease)
+			.await?
+		{
+			IdempotencyReservation::Reserved(lease) => lease,
+			IdempotencyReservation::Replay(result) => return Ok(result),
+		};
+
+	match handler.call(resources, rpc_method.clone(), params).await {
+		Ok(result) => {
+			// Note: The call is done, so its result is returned even when not stored
+			//       (the retries then fail with `IdempotencyKeyInProgress`, until
+			//       the lease expires).
+			if let Err(ex) =
+				IdempotencyKeyBmc::complete(&ctx, &mm, &lease, &result).await
+			{
+				error!(
+					"{:<12} - idempotency key not completed - {rpc_method} - key: {key} - {ex:?}",
+					"RPC"
+				);
+			}
+			Ok(result)
+		}
+		Err(err) => {
+			// Note: The call error is returned even when the key is not released.
+			if let Err(ex) = IdempotencyKeyBmc::release(&ctx, &mm, &lease).await {
+				error!(
+					"{:<12} - idempotency key not released - {rpc_method} - key: {key} - {ex:?}",
+					"RPC"
+				);
+			}
+			Err(err)
+		}
+	}
+}
+
+/// Returns the hash of the method and params (as base64url).
+///
+/// Note: The params object members are serialized in key order,
+///       so the same params in any order have the same hash.
+fn request_hash(rpc_method: &str, params: Option<&Value>) -> String {
+	let mut hasher = Sha256::new();
+	hasher.update(rpc_method.as_bytes());
+	if let Some(params) = params {
+		hasher.update(b"\n");
+		hasher.update(params.to_string().as_bytes());
+	}
+
+	b64u_encode(hasher.finalize())
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{RpcMethodConfig, RpcRequest, RpcRouter, JSONRPC_VERSION};
+	use anyhow::Result;
+	use lib_core::_dev_utils;
+	use lib_core::ctx::Ctx;
+	use lib_core::model::{self, ModelManager};
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_call_idempotent_ok_cancelled() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = fx_mm().await?;
+		let fx_key = "test_call_idempotent_ok_cancelled";
+		let router_pending = RpcRouter::new().add_with(
+			"test_call",
+			call_pending,
+			RpcMethodConfig::new().idempotent(),
+		);
+		// The same method, after the lease of the cancelled call (zero here).
+		let router_retry = RpcRouter::new().add_with(
+			"test_call",
+			call_ok,
+			RpcMethodConfig::new().idempotent_lease(Duration::ZERO),
+		);
+
+		// -- Exec
+		let res_cancelled = tokio::time::timeout(
+			Duration::from_millis(100),
+			router_pending.call(fx_resources(&mm), fx_request(fx_key)),
+		)
+		.await;
+		let res_in_progress = router_pending
+			.call(fx_resources(&mm), fx_request(fx_key))
+			.await;
+		let retry = router_retry
+			.call(fx_resources(&mm), fx_request(fx_key))
+			.await?;
+		let replay = router_pending
+			.call(fx_resources(&mm), fx_request(fx_key))
+			.await?;
+
+		// -- Check
+		assert!(res_cancelled.is_err(), "Call should be cancelled");
+		assert!(
+			matches!(
+				res_in_progress,
+				Err(Error::Model(model::Error::IdempotencyKeyInProgress { .. }))
+			),
+			"IdempotencyKeyInProgress not matching"
+		);
+		assert_eq!(retry, Value::from(1));
+		assert_eq!(replay, Value::from(1));
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_call_idempotent_ok_complete_failed() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = fx_mm().await?;
+		let fx_key = "test_call_idempotent_ok_complete_failed";
+		let router = RpcRouter::new().add_with(
+			"test_call",
+			call_lease_lost,
+			RpcMethodConfig::new().idempotent(),
+		);
+
+		// -- Exec
+		let res = router.call(fx_resources(&mm), fx_request(fx_key)).await?;
+		let res_retry = router.call(fx_resources(&mm), fx_request(fx_key)).await;
+
+		// -- Check
+		// The call result is returned, but not stored (the key is held by the
+		// call which took it over).
+		assert_eq!(res, Value::from(1));
+		assert!(
+			matches!(
+				res_retry,
+				Err(Error::Model(model::Error::IdempotencyKeyReused { .. }))
+			),
+			"IdempotencyKeyReused not matching"
+		);
+
+		Ok(())
+	}
+
+	async fn call_pending(_ctx: Ctx) -> crate::Result<i64> {
+		std::future::pending().await
+	}
+
+	async fn call_ok(_ctx: Ctx) -> crate::Result<i64> {
+		Ok(1)
+	}
+
+	/// Returns once its key lease is taken over (by another call, with another
+	/// hash, i.e., as if the call outlived its lease).
+	async fn call_lease_lost(ctx: Ctx, mm: ModelManager) -> crate::Result<i64> {
+		let key = "test_call_idempotent_ok_complete_failed";
+		IdempotencyKeyBmc::reserve(&ctx, &mm, key, "other hash", Duration::ZERO)
+			.await?;
+
+		Ok(1)
+	}
+
+	/// Returns a `ModelManager` with its own pool (i.e., on the test runtime).
+	///
+	/// Note: The pool connections are bound to the tokio runtime, and each test
+	///       has its own runtime, so the tests do not share the `init_test` one.
+	async fn fx_mm() -> Result<ModelManager> {
+		_dev_utils::init_test().await;
+		Ok(ModelManager::new().await?)
+	}
+
+	fn fx_resources(mm: &ModelManager) -> RpcResources {
+		RpcResources {
+			ctx: Ctx::root_ctx(),
+			mm: mm.clone(),
+		}
+	}
+
+	fn fx_request(key: &str) -> RpcRequest {
+		RpcRequest {
+			jsonrpc: JSONRPC_VERSION.to_string(),
+			id: Some(Value::from(1)),
+			method: "test_call".to_string(),
+			params: None,
+			idempotency_key: Some(key.to_string()),
+		}
+	}
+}
+// endregion: --- Tests
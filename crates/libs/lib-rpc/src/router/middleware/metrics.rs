@@ -0,0 +1,48 @@
+use crate::router::middleware::{Next, RpcCall, RpcMiddleware};
+use crate::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+/// The metric of a call, with the method `labels`.
+#[derive(Debug)]
+pub struct RpcCallMetric {
+	pub rpc_method: String,
+	pub labels: Vec<(&'static str, &'static str)>,
+	pub duration: Duration,
+	pub ok: bool,
+}
+
+/// Records the metric of every call with the recorder function
+/// (e.g., logging it, or updating the app metrics).
+pub struct MetricsMiddleware {
+	recorder: Box<dyn Fn(RpcCallMetric) + Send + Sync>,
+}
+
+impl MetricsMiddleware {
+	pub fn new(recorder: impl Fn(RpcCallMetric) + Send + Sync + 'static) -> Self {
+		Self {
+			recorder: Box::new(recorder),
+		}
+	}
+}
+
+#[async_trait]
+impl RpcMiddleware for MetricsMiddleware {
+	async fn call(&self, call: RpcCall, next: Next<'_>) -> Result<Value> {
+		let rpc_method = call.rpc_method.clone();
+		let labels = call.config.labels.clone();
+
+		let start = Instant::now();
+		let res = next.run(call).await;
+
+		(self.recorder)(RpcCallMetric {
+			rpc_method,
+			labels,
+			duration: start.elapsed(),
+			ok: res.is_ok(),
+		});
+
+		res
+	}
+}
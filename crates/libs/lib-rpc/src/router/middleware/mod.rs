@@ -0,0 +1,296 @@
+//! The rpc middlewares, which run around the method handler of every call
+//! (see `RpcRouter::layer`), with the call `Ctx`, method name, params,
+//! and the method `RpcMethodConfig` (set at its registration).
+//!
+//! e.g., the `PermissionMiddleware`, `RateLimitMiddleware`, `SlowCallMiddleware`,
+//! and `MetricsMiddleware`, configured by the `RpcMethodConfig` of each method.
+
+// region:    --- Modules
+
+mod metrics;
+mod permission;
+mod rate_limit;
+mod slow_call;
+
+pub use self::metrics::{MetricsMiddleware, RpcCallMetric};
+pub use self::permission::PermissionMiddleware;
+pub use self::rate_limit::RateLimitMiddleware;
+pub use self::slow_call::SlowCallMiddleware;
+
+use crate::router::handler::RpcHandlerDyn;
+use crate::router::{idempotency, RpcResources, IDEMPOTENCY_LEASE_DEFAULT};
+use crate::Result;
+use async_trait::async_trait;
+use lib_core::ctx::Ctx;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+// endregion: --- Modules
+
+// region:    --- RpcMiddleware
+
+#[async_trait]
+pub trait RpcMiddleware: Send + Sync + 'static {
+	/// Handles the call, and usually passes it to the `next` middleware
+	/// (or returns early, e.g., with an error).
+	async fn call(&self, call: RpcCall, next: Next<'_>) -> Result<Value>;
+}
+
+/// A call through the middlewares, to its method handler.
+pub struct RpcCall {
+	pub resources: RpcResources,
+	pub rpc_method: String,
+	pub params: Option<Value>,
+	pub idempotency_key: Option<String>,
+	pub config: Arc<RpcMethodConfig>,
+}
+
+impl RpcCall {
+	pub fn ctx(&self) -> &Ctx {
+		&self.resources.ctx
+	}
+}
+
+/// The rest of the middlewares of a call, then its method handler.
+pub struct Next<'a> {
+	middlewares: &'a [Arc<dyn RpcMiddleware>],
+	handler: &'a dyn RpcHandlerDyn,
+}
+
+impl<'a> Next<'a> {
+	pub(super) fn new(
+		middlewares: &'a [Arc<dyn RpcMiddleware>],
+		handler: &'a dyn RpcHandlerDyn,
+	) -> Self {
+		Self {
+			middlewares,
+			handler,
+		}
+	}
+
+	/// Runs the next middleware, or the method handler after the last one.
+	pub async fn run(self, call: RpcCall) -> Result<Value> {
+		match self.middlewares.split_first() {
+			Some((middleware, middlewares)) => {
+				let next = Next::new(middlewares, self.handler);
+				middleware.call(call, next).await
+			}
+			None => call_handler(self.handler, call).await,
+		}
+	}
+}
+
+/// Calls the method handler, idempotently for a call of an idempotent method
+/// with an idempotency key (which is otherwise ignored).
+async fn call_handler(handler: &dyn RpcHandlerDyn, call: RpcCall) -> Result<Value> {
+	let RpcCall {
+		resources,
+		rpc_method,
+		params,
+		idempotency_key,
+		config,
+	} = call;
+
+	match (idempotency_key, config.idempotency_lease) {
+		(Some(key), Some(lease)) => {
+			idempotency::call_idempotent(
+				handler, resources, rpc_method, params, key, lease,
+			)
+			.await
+		}
+		_ => handler.call(resources, rpc_method, params).await,
+	}
+}
+
+// endregion: --- RpcMiddleware
+
+// region:    --- RpcMethodConfig
+
+/// The config of a method, for the middlewares, set at its registration
+/// (see `RpcRouter::add_with` and `rpc_router!`).
+///
+/// e.g., `RpcMethodConfig::new().rate_limit(5, Duration::from_secs(60))`
+#[derive(Debug, Clone, Default)]
+pub struct RpcMethodConfig {
+	/// The permission required to call the method (see `PermissionMiddleware`).
+	pub permission: RpcPermission,
+	/// The max calls per user (see `RateLimitMiddleware`).
+	pub rate_limit: Option<RateLimit>,
+	/// The threshold of the slow calls, instead of the default one
+	/// (see `SlowCallMiddleware`).
+	pub slow_call: Option<Duration>,
+	/// The labels of the method metrics (see `MetricsMiddleware`).
+	pub labels: Vec<(&'static str, &'static str)>,
+	/// The lease of the idempotency keys, for a mutating method
+	/// (i.e., its calls with an idempotency key are idempotent, see `idempotency`).
+	pub idempotency_lease: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RpcPermission {
+	/// Any authenticated user.
+	#[default]
+	User,
+	/// The admin users only.
+	Admin,
+}
+
+/// At most `max_calls` per `period`, per user.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+	pub max_calls: u32,
+	pub period: Duration,
+}
+
+impl RpcMethodConfig {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn permission(mut self, permission: RpcPermission) -> Self {
+		self.permission = permission;
+		self
+	}
+
+	pub fn rate_limit(mut self, max_calls: u32, period: Duration) -> Self {
+		self.rate_limit = Some(RateLimit { max_calls, period });
+		self
+	}
+
+	pub fn slow_call(mut self, threshold: Duration) -> Self {
+		self.slow_call = Some(threshold);
+		self
+	}
+
+	pub fn label(mut self, name: &'static str, value: &'static str) -> Self {
+		self.labels.push((name, value));
+		self
+	}
+
+	/// Marks the method as mutating, so its calls with an idempotency key are
+	/// idempotent, with the `IDEMPOTENCY_LEASE_DEFAULT` lease.
+	pub fn idempotent(self) -> Self {
+		self.idempotent_lease(IDEMPOTENCY_LEASE_DEFAULT)
+	}
+
+	/// Same as `idempotent`, with a longer lease for the long calls.
+	pub fn idempotent_lease(mut self, lease: Duration) -> Self {
+		self.idempotency_lease = Some(lease);
+		self
+	}
+}
+
+// endregion: --- RpcMethodConfig
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::router::tests::{fx_request, fx_resources};
+	use crate::{Error, RpcRouter};
+	use anyhow::Result;
+	use serial_test::serial;
+	use std::sync::Mutex;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_layer_ok_order() -> Result<()> {
+		// -- Setup & Fixtures
+		let resources = fx_resources(Ctx::root_ctx()).await?;
+		let trace = Arc::new(Mutex::new(Vec::new()));
+		let router = RpcRouter::new()
+			.add("test_call", call_ok)
+			.layer(TraceMiddleware::new("a", &trace))
+			.merge(RpcRouter::new().layer(TraceMiddleware::new("b", &trace)));
+
+		// -- Exec
+		let res = router.call(resources, fx_request("test_call")).await?;
+
+		// -- Check
+		assert_eq!(res, Value::from("ok"));
+		assert_eq!(
+			*trace.lock().unwrap(),
+			&["a before", "b before", "b after", "a after"]
+		);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_layer_ok_short_circuit() -> Result<()> {
+		// -- Setup & Fixtures
+		let resources = fx_resources(Ctx::root_ctx()).await?;
+		let trace = Arc::new(Mutex::new(Vec::new()));
+		let router = RpcRouter::new()
+			.add("test_call", call_ok)
+			.layer(TraceMiddleware::new("a", &trace))
+			.layer(RejectMiddleware)
+			.layer(TraceMiddleware::new("b", &trace));
+
+		// -- Exec
+		let res = router.call(resources, fx_request("test_call")).await;
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::RpcInvalidRequest { cause: "rejected" })),
+			"RpcInvalidRequest not matching"
+		);
+		// The next middlewares (and the handler) do not run.
+		assert_eq!(*trace.lock().unwrap(), &["a before", "a after"]);
+
+		Ok(())
+	}
+
+	async fn call_ok() -> crate::Result<&'static str> {
+		Ok("ok")
+	}
+
+	/// Records its name before and after the next middlewares.
+	struct TraceMiddleware {
+		name: &'static str,
+		trace: Arc<Mutex<Vec<String>>>,
+	}
+
+	impl TraceMiddleware {
+		fn new(name: &'static str, trace: &Arc<Mutex<Vec<String>>>) -> Self {
+			Self {
+				name,
+				trace: trace.clone(),
+			}
+		}
+	}
+
+	#[async_trait]
+	impl RpcMiddleware for TraceMiddleware {
+		async fn call(&self, call: RpcCall, next: Next<'_>) -> crate::Result<Value> {
+			self.trace
+				.lock()
+				.unwrap()
+				.push(format!("{} before", self.name));
+			let res = next.run(call).await;
+			self.trace
+				.lock()
+				.unwrap()
+				.push(format!("{} after", self.name));
+
+			res
+		}
+	}
+
+	/// Fails every call, without running the next middlewares.
+	struct RejectMiddleware;
+
+	#[async_trait]
+	impl RpcMiddleware for RejectMiddleware {
+		async fn call(
+			&self,
+			_call: RpcCall,
+			_next: Next<'_>,
+		) -> crate::Result<Value> {
+			Err(Error::RpcInvalidRequest { cause: "rejected" })
+		}
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,100 @@
+use crate::router::middleware::{Next, RpcCall, RpcMiddleware, RpcPermission};
+use crate::router::RpcResources;
+use crate::Result;
+use async_trait::async_trait;
+use lib_core::model::user::UserBmc;
+use serde_json::Value;
+
+/// Checks the method `permission` of the ctx user, and fails with
+/// the model `AdminRequired` for the admin methods.
+pub struct PermissionMiddleware;
+
+#[async_trait]
+impl RpcMiddleware for PermissionMiddleware {
+	async fn call(&self, call: RpcCall, next: Next<'_>) -> Result<Value> {
+		match call.config.permission {
+			RpcPermission::User => (),
+			RpcPermission::Admin => {
+				let RpcResources { ctx, mm } = &call.resources;
+				UserBmc::check_admin(ctx, mm).await?;
+			}
+		}
+
+		next.run(call).await
+	}
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::router::tests::{fx_request, fx_resources};
+	use crate::{Error, RpcMethodConfig, RpcRouter};
+	use anyhow::Result;
+	use lib_core::ctx::Ctx;
+	use lib_core::model;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_permission_admin_err_not_admin() -> Result<()> {
+		// -- Setup & Fixtures
+		// Note: An unknown user is not an admin.
+		let fx_user_id = 9_000_001;
+		let resources = fx_resources(Ctx::new(fx_user_id)?).await?;
+		let router = fx_router();
+
+		// -- Exec
+		let res_user = router
+			.call(resources.clone(), fx_request("test_user_call"))
+			.await?;
+		let res_admin = router.call(resources, fx_request("test_admin_call")).await;
+
+		// -- Check
+		assert_eq!(res_user, Value::from("ok"));
+		assert!(
+			matches!(
+				res_admin,
+				Err(Error::Model(model::Error::AdminRequired { user_id }))
+					if user_id == fx_user_id
+			),
+			"AdminRequired not matching"
+		);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_permission_admin_ok_root() -> Result<()> {
+		// -- Setup & Fixtures
+		let resources = fx_resources(Ctx::root_ctx()).await?;
+		let router = fx_router();
+
+		// -- Exec
+		let res = router
+			.call(resources, fx_request("test_admin_call"))
+			.await?;
+
+		// -- Check
+		assert_eq!(res, Value::from("ok"));
+
+		Ok(())
+	}
+
+	fn fx_router() -> RpcRouter {
+		RpcRouter::new()
+			.add("test_user_call", call_ok)
+			.add_with(
+				"test_admin_call",
+				call_ok,
+				RpcMethodConfig::new().permission(RpcPermission::Admin),
+			)
+			.layer(PermissionMiddleware)
+	}
+
+	async fn call_ok() -> crate::Result<&'static str> {
+		Ok("ok")
+	}
+}
+// endregion: --- Tests
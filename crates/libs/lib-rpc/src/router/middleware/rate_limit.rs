@@ -0,0 +1,134 @@
+use crate::router::middleware::{Next, RpcCall, RpcMiddleware};
+use crate::{Error, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The window count above which the expired windows are removed.
+const WINDOWS_CLEAN_THRESHOLD: usize = 10_000;
+
+/// Limits the calls of the methods with a `rate_limit`, per user and method,
+/// with fixed windows, and fails with `RpcRateLimited` over the limit.
+///
+/// Note: The windows are in memory, so per server instance.
+#[derive(Default)]
+pub struct RateLimitMiddleware {
+	windows: Mutex<HashMap<(i64, String), Window>>,
+}
+
+struct Window {
+	start: Instant,
+	period: Duration,
+	count: u32,
+}
+
+impl Window {
+	fn is_expired(&self, now: Instant) -> bool {
+		now.duration_since(self.start) >= self.period
+	}
+}
+
+#[async_trait]
+impl RpcMiddleware for RateLimitMiddleware {
+	async fn call(&self, call: RpcCall, next: Next<'_>) -> Result<Value> {
+		if let Some(rate_limit) = call.config.rate_limit {
+			let key = (call.ctx().user_id(), call.rpc_method.clone());
+			let now = Instant::now();
+
+			let mut windows = self.windows.lock().unwrap();
+			if windows.len() > WINDOWS_CLEAN_THRESHOLD {
+				windows.retain(|_, window| !window.is_expired(now));
+			}
+
+			let window = windows.entry(key).or_insert(Window {
+				start: now,
+				period: rate_limit.period,
+				count: 0,
+			});
+			if window.is_expired(now) {
+				window.start = now;
+				window.count = 0;
+			}
+
+			if window.count >= rate_limit.max_calls {
+				let retry_after =
+					rate_limit.period - now.duration_since(window.start);
+				return Err(Error::RpcRateLimited {
+					rpc_method: call.rpc_method,
+					// Note: Rounded up, so that the retry is after the window.
+					retry_after_sec: retry_after.as_secs() + 1,
+				});
+			}
+			window.count += 1;
+		}
+
+		next.run(call).await
+	}
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::router::tests::{fx_request, fx_resources};
+	use crate::{RpcMethodConfig, RpcRouter};
+	use anyhow::Result;
+	use lib_core::ctx::Ctx;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_rate_limit_ok_window() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_period = Duration::from_millis(300);
+		let resources = fx_resources(Ctx::root_ctx()).await?;
+		let resources_other = fx_resources(Ctx::new(9_000_001)?).await?;
+		let router = RpcRouter::new()
+			.add_with(
+				"test_call",
+				call_ok,
+				RpcMethodConfig::new().rate_limit(2, fx_period),
+			)
+			.add("test_call_unlimited", call_ok)
+			.layer(RateLimitMiddleware::default());
+
+		// -- Exec & Check
+		for _ in 0..2 {
+			router
+				.call(resources.clone(), fx_request("test_call"))
+				.await?;
+		}
+		let res = router
+			.call(resources.clone(), fx_request("test_call"))
+			.await;
+		assert!(
+			matches!(
+				&res,
+				Err(Error::RpcRateLimited { rpc_method, retry_after_sec: 1 })
+					if rpc_method == "test_call"
+			),
+			"RpcRateLimited not matching: {res:?}"
+		);
+
+		// The windows are per user and method.
+		router
+			.call(resources_other, fx_request("test_call"))
+			.await?;
+		router
+			.call(resources.clone(), fx_request("test_call_unlimited"))
+			.await?;
+
+		// A new window after the period.
+		tokio::time::sleep(fx_period).await;
+		router.call(resources, fx_request("test_call")).await?;
+
+		Ok(())
+	}
+
+	async fn call_ok() -> crate::Result<&'static str> {
+		Ok("ok")
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,42 @@
+use crate::router::middleware::{Next, RpcCall, RpcMiddleware};
+use crate::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Logs the calls over the method `slow_call` threshold,
+/// or over the default one.
+pub struct SlowCallMiddleware {
+	default_threshold: Duration,
+}
+
+impl SlowCallMiddleware {
+	pub fn new(default_threshold: Duration) -> Self {
+		Self { default_threshold }
+	}
+}
+
+#[async_trait]
+impl RpcMiddleware for SlowCallMiddleware {
+	async fn call(&self, call: RpcCall, next: Next<'_>) -> Result<Value> {
+		let threshold = call.config.slow_call.unwrap_or(self.default_threshold);
+		let rpc_method = call.rpc_method.clone();
+		let user_id = call.ctx().user_id();
+
+		let start = Instant::now();
+		let res = next.run(call).await;
+		let duration = start.elapsed();
+
+		if duration > threshold {
+			warn!(
+				"{:<12} - slow call - {rpc_method} - user_id: {user_id} - {}ms (threshold: {}ms)",
+				"RPC",
+				duration.as_millis(),
+				threshold.as_millis()
+			);
+		}
+
+		res
+	}
+}
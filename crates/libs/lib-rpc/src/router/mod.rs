@@ -0,0 +1,281 @@
+//! The RPC router, where the rpc handler functions are registered by method name.
+//!
+//! Each domain module has its own router (e.g., `task_rpc::rpc_router`),
+//! and the routers are merged into the app router (see `crate::all_rpc_router`),
+//! which publishes its OpenRPC document with the `rpc.discover` method
+//! (see `RpcRouter::add_discover`).
+//!
+//! The rpc handler functions are async functions with their arguments extracted
+//! from the call (see `RpcHandler`), e.g., `task_rpc::create_task(ctx, mm, params)`.
+//!
+//! The mutating method calls with an idempotency key are idempotent, for the
+//! methods configured with `RpcMethodConfig::idempotent` (see `idempotency`).
+//!
+//! The calls run through the router middlewares (see `RpcRouter::layer`),
+//! configured per method at its registration (see `RpcRouter::add_with`).
+
+// region:    --- Modules
+
+mod handler;
+mod idempotency;
+mod middleware;
+mod openrpc;
+mod resources;
+
+pub use self::handler::{PinFutureValue, RpcHandler};
+pub use self::idempotency::{IDEMPOTENCY_KEY_MAX_LEN, IDEMPOTENCY_LEASE_DEFAULT};
+pub use self::middleware::{
+	MetricsMiddleware, Next, PermissionMiddleware, RateLimit, RateLimitMiddleware,
+	RpcCall, RpcCallMetric, RpcMethodConfig, RpcMiddleware, RpcPermission,
+	SlowCallMiddleware,
+};
+pub use self::openrpc::{OpenRpcError, RPC_DISCOVER_METHOD};
+pub use self::resources::{FromResources, IntoParams, RpcResources};
+
+use crate::{Error, Result, RpcRequest};
+use handler::{RpcHandlerDyn, RpcHandlerWrapper};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// endregion: --- Modules
+
+/// A registered method, with its handler and config.
+#[derive(Clone)]
+struct RpcRoute {
+	handler: Arc<dyn RpcHandlerDyn>,
+	config: Arc<RpcMethodConfig>,
+}
+
+#[derive(Clone, Default)]
+pub struct RpcRouter {
+	route_by_method: HashMap<&'static str, RpcRoute>,
+	middlewares: Vec<Arc<dyn RpcMiddleware>>,
+}
+
+impl RpcRouter {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Registers the handler function for the method, with the default config.
+	///
+	/// Note: Panics when the method is already registered (i.e., at startup).
+	pub fn add<H, T, P>(self, rpc_method: &'static str, handler: H) -> Self
+	where
+		H: RpcHandler<T, P>,
+		T: 'static,
+		P: 'static,
+	{
+		self.add_with(rpc_method, handler, RpcMethodConfig::default())
+	}
+
+	/// Registers the handler function for the method, with its config
+	/// for the middlewares (e.g., its permission or rate limit).
+	///
+	/// Note: Panics when the method is already registered (i.e., at startup).
+	pub fn add_with<H, T, P>(
+		mut self,
+		rpc_method: &'static str,
+		handler: H,
+		config: RpcMethodConfig,
+	) -> Self
+	where
+		H: RpcHandler<T, P>,
+		T: 'static,
+		P: 'static,
+	{
+		let route = RpcRoute {
+			handler: Arc::new(RpcHandlerWrapper::new(handler)),
+			config: Arc::new(config),
+		};
+		let previous = self.route_by_method.insert(rpc_method, route);
+		assert!(
+			previous.is_none(),
+			"rpc method '{rpc_method}' already registered"
+		);
+
+		self
+	}
+
+	/// Adds the middleware, around all the method calls of this router.
+	///
+	/// Note: The first middleware added is the outermost one
+	///       (i.e., the first to run, and the last to return).
+	pub fn layer(mut self, middleware: impl RpcMiddleware) -> Self {
+		self.middlewares.push(Arc::new(middleware));
+		self
+	}
+
+	/// Merges the other router methods (and middlewares, after the ones
+	/// of this router) into this one.
+	///
+	/// Note: Panics on a method registered by both routers.
+	pub fn merge(mut self, other: RpcRouter) -> Self {
+		for (rpc_method, route) in other.route_by_method {
+			let previous = self.route_by_method.insert(rpc_method, route);
+			assert!(
+				previous.is_none(),
+				"rpc method '{rpc_method}' already registered"
+			);
+		}
+		self.middlewares.extend(other.middlewares);
+
+		self
+	}
+
+	/// Returns the registered method names (in no particular order).
+	pub fn rpc_methods(&self) -> impl Iterator<Item = &'static str> + '_ {
+		self.route_by_method.keys().copied()
+	}
+
+	/// Returns the config of the method, if registered.
+	pub fn method_config(&self, rpc_method: &str) -> Option<&RpcMethodConfig> {
+		self.route_by_method
+			.get(rpc_method)
+			.map(|route| route.config.as_ref())
+	}
+
+	/// Executes the request through the middlewares, then with its method
+	/// handler, or fails with `RpcMethodUnknown`.
+	///
+	/// Note: The idempotency key of a call is ignored, unless its method is
+	///       configured as idempotent.
+	pub async fn call(
+		&self,
+		resources: RpcResources,
+		rpc_req: RpcRequest,
+	) -> Result<Value> {
+		let RpcRequest {
+			method,
+			params,
+			idempotency_key,
+			..
+		} = rpc_req;
+
+		let route = self
+			.route_by_method
+			.get(method.as_str())
+			.ok_or_else(|| Error::RpcMethodUnknown(method.clone()))?;
+
+		let call = RpcCall {
+			resources,
+			rpc_method: method,
+			params,
+			idempotency_key,
+			config: route.config.clone(),
+		};
+
+		Next::new(&self.middlewares, route.handler.as_ref())
+			.run(call)
+			.await
+	}
+}
+
+/// Returns a `RpcRouter` with the handler functions,
+/// registered with their function names as method names,
+/// and their optional config (after `=>`).
+///
+/// e.g., `rpc_router!(create_task, list_tasks, delete_task => RpcMethodConfig::new())`
+#[macro_export]
+macro_rules! rpc_router {
+	($($rpc_fn:ident $(=> $config:expr)?),* $(,)?) => {{
+		let router = $crate::RpcRouter::new();
+		$(let router = router.add_with(
+			stringify!($rpc_fn),
+			$rpc_fn,
+			$crate::rpc_router!(@config $($config)?),
+		);)*
+		router
+	}};
+	(@config) => {
+		$crate::RpcMethodConfig::default()
+	};
+	(@config $config:expr) => {
+		$config
+	};
+}
+
+// region:    --- Tests
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use crate::JSONRPC_VERSION;
+	use anyhow::Result;
+	use lib_core::_dev_utils;
+	use lib_core::ctx::Ctx;
+	use lib_core::model::ModelManager;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_call_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let resources = fx_resources(Ctx::root_ctx()).await?;
+		let router = RpcRouter::new()
+			.add("test_call_a", call_ok)
+			.merge(RpcRouter::new().add("test_call_b", call_user_id));
+
+		// -- Exec
+		let res_a = router
+			.call(resources.clone(), fx_request("test_call_a"))
+			.await?;
+		let res_b = router.call(resources, fx_request("test_call_b")).await?;
+
+		// -- Check
+		assert_eq!(res_a, Value::from("ok"));
+		assert_eq!(res_b, Value::from(Ctx::root_ctx().user_id()));
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_call_err_method_unknown() -> Result<()> {
+		// -- Setup & Fixtures
+		let resources = fx_resources(Ctx::root_ctx()).await?;
+		let router = RpcRouter::new().add("test_call", call_ok);
+
+		// -- Exec
+		let res = router.call(resources, fx_request("test_unknown")).await;
+
+		// -- Check
+		assert!(
+			matches!(&res, Err(Error::RpcMethodUnknown(method)) if method == "test_unknown"),
+			"RpcMethodUnknown not matching"
+		);
+
+		Ok(())
+	}
+
+	async fn call_ok() -> crate::Result<&'static str> {
+		Ok("ok")
+	}
+
+	async fn call_user_id(ctx: Ctx) -> crate::Result<i64> {
+		Ok(ctx.user_id())
+	}
+
+	/// Returns the resources of a call by the ctx user, with their own pool.
+	///
+	/// Note: The pool connections are bound to the tokio runtime, and each test
+	///       has its own runtime, so the tests do not share the `init_test` one.
+	pub(crate) async fn fx_resources(ctx: Ctx) -> Result<RpcResources> {
+		_dev_utils::init_test().await;
+		let mm = ModelManager::new().await?;
+
+		Ok(RpcResources { ctx, mm })
+	}
+
+	/// Returns a request of the method, without params.
+	pub(crate) fn fx_request(rpc_method: &str) -> RpcRequest {
+		RpcRequest {
+			jsonrpc: JSONRPC_VERSION.to_string(),
+			id: Some(Value::from(1)),
+			method: rpc_method.to_string(),
+			params: None,
+			idempotency_key: None,
+		}
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,116 @@
+//! The OpenRPC document of the router methods (see https://spec.open-rpc.org),
+//! with their params and result JSON schemas (draft 7), and errors.
+
+use crate::router::RpcRouter;
+use crate::Error;
+use schemars::generate::SchemaSettings;
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+
+const OPENRPC_VERSION: &str = "1.2.6";
+
+/// The method returning the OpenRPC document (see `RpcRouter::add_discover`).
+pub const RPC_DISCOVER_METHOD: &str = "rpc.discover";
+
+/// An error of the rpc methods, as defined by the transport
+/// (e.g., the web-server `ClientError`).
+pub struct OpenRpcError {
+	pub code: i64,
+	pub message: String,
+}
+
+impl RpcRouter {
+	/// Returns the OpenRPC document, where the methods are sorted by name,
+	/// and all have the `errors`.
+	///
+	/// Note: The params are by-name (i.e., the params object properties).
+	pub fn openrpc_document(&self, errors: &[OpenRpcError]) -> Value {
+		let mut generator = SchemaSettings::draft07()
+			.with(|s| s.definitions_path = "/components/schemas".into())
+			.into_generator();
+
+		let error_refs: Vec<Value> = errors
+			.iter()
+			.map(|e| json!({"$ref": format!("#/components/errors/{}", e.message)}))
+			.collect();
+
+		let mut rpc_methods: Vec<&'static str> = self.rpc_methods().collect();
+		rpc_methods.sort_unstable();
+
+		let mut methods = Vec::new();
+		for rpc_method in rpc_methods {
+			let handler = &self.route_by_method[rpc_method].handler;
+			let params = handler
+				.params_schema(&mut generator)
+				.map(|schema| params_descriptors(schema.to_value()))
+				.unwrap_or_default();
+			let result = handler.result_schema(&mut generator);
+
+			methods.push(json!({
+				"name": rpc_method,
+				"paramStructure": "by-name",
+				"params": params,
+				"result": {"name": "result", "schema": result},
+				"errors": error_refs,
+			}));
+		}
+
+		let errors: Map<String, Value> = errors
+			.iter()
+			.map(|e| {
+				let error = json!({"code": e.code, "message": e.message});
+				(e.message.clone(), error)
+			})
+			.collect();
+
+		json!({
+			"openrpc": OPENRPC_VERSION,
+			"info": {
+				"title": "dragonlord",
+				"version": env!("CARGO_PKG_VERSION"),
+			},
+			"methods": methods,
+			"components": {
+				"schemas": generator.take_definitions(true),
+				"errors": errors,
+			},
+		})
+	}
+
+	/// Registers the `rpc.discover` method, returning the document
+	/// (built with `openrpc_document`, before this registration).
+	pub fn add_discover(self, openrpc_doc: Arc<Value>) -> Self {
+		let rpc_discover = move || {
+			let openrpc_doc = openrpc_doc.clone();
+			async move { Ok::<_, Error>(openrpc_doc.as_ref().clone()) }
+		};
+
+		self.add(RPC_DISCOVER_METHOD, rpc_discover)
+	}
+}
+
+/// Returns the content descriptors of the params object properties.
+fn params_descriptors(params_schema: Value) -> Vec<Value> {
+	let required: Vec<&str> = params_schema
+		.get("required")
+		.and_then(Value::as_array)
+		.map(|names| names.iter().filter_map(Value::as_str).collect())
+		.unwrap_or_default();
+
+	let Some(properties) =
+		params_schema.get("properties").and_then(Value::as_object)
+	else {
+		return Vec::new();
+	};
+
+	properties
+		.iter()
+		.map(|(name, schema)| {
+			json!({
+				"name": name,
+				"required": required.contains(&name.as_str()),
+				"schema": schema,
+			})
+		})
+		.collect()
+}
@@ -0,0 +1,56 @@
+use crate::{Error, Result};
+use lib_core::ctx::Ctx;
+use lib_core::model::ModelManager;
+use lib_utils::validate::Validate;
+use serde::de::DeserializeOwned;
+use serde_json::{from_value, Value};
+
+/// The resources of a call, from which the rpc handler function
+/// arguments are extracted (see `FromResources`).
+#[derive(Clone)]
+pub struct RpcResources {
+	pub ctx: Ctx,
+	pub mm: ModelManager,
+}
+
+// region:    --- FromResources
+/// The rpc handler function arguments taken from the call resources
+/// (e.g., `Ctx` and `ModelManager`).
+pub trait FromResources: Sized {
+	fn from_resources(resources: &RpcResources) -> Result<Self>;
+}
+
+impl FromResources for Ctx {
+	fn from_resources(resources: &RpcResources) -> Result<Self> {
+		Ok(resources.ctx.clone())
+	}
+}
+
+impl FromResources for ModelManager {
+	fn from_resources(resources: &RpcResources) -> Result<Self> {
+		Ok(resources.mm.clone())
+	}
+}
+// endregion: --- FromResources
+
+// region:    --- IntoParams
+/// The rpc handler function params argument (the last one),
+/// deserialized from the request `params`, and validated.
+pub trait IntoParams: DeserializeOwned + Validate + Send {
+	fn into_params(rpc_method: &str, params: Option<Value>) -> Result<Self> {
+		let params = params.ok_or_else(|| Error::RpcMissingParams {
+			rpc_method: rpc_method.to_string(),
+		})?;
+
+		let params: Self =
+			from_value(params).map_err(|_| Error::RpcFailJsonParams {
+				rpc_method: rpc_method.to_string(),
+			})?;
+		params.validate()?;
+
+		Ok(params)
+	}
+}
+
+impl<P> IntoParams for P where P: DeserializeOwned + Validate + Send {}
+// endregion: --- IntoParams
@@ -0,0 +1,103 @@
+use crate::params::ParamsList;
+use crate::{ParamsForCreate, ParamsForUpdate, ParamsIded};
+use crate::{Result, RpcMethodConfig, RpcRouter};
+use lib_core::ctx::Ctx;
+use lib_core::model::tag::{
+	Tag, TagBmc, TagFilter, TagForCreate, TagForUpdate, TagWithCount,
+};
+use lib_core::model::ModelManager;
+use lib_utils::validate::Validate;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+pub fn rpc_router() -> RpcRouter {
+	crate::rpc_router!(
+		create_tag => RpcMethodConfig::new().idempotent(),
+		list_tags,
+		update_tag => RpcMethodConfig::new().idempotent(),
+		delete_tag => RpcMethodConfig::new().idempotent(),
+		attach_tag => RpcMethodConfig::new().idempotent(),
+		detach_tag => RpcMethodConfig::new().idempotent(),
+	)
+}
+
+/// Params for the `attach_tag` and `detach_tag` rpc handler functions.
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsTaskTag {
+	pub task_id: i64,
+	pub tag_id: i64,
+}
+
+pub async fn create_tag(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreate<TagForCreate>,
+) -> Result<Tag> {
+	let ParamsForCreate { data } = params;
+
+	let id = TagBmc::create(&ctx, &mm, data).await?;
+	let tag = TagBmc::get(&ctx, &mm, id).await?;
+
+	Ok(tag)
+}
+
+pub async fn list_tags(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<TagFilter>,
+) -> Result<Vec<TagWithCount>> {
+	let tags = TagBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+
+	Ok(tags)
+}
+
+pub async fn update_tag(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdate<TagForUpdate>,
+) -> Result<Tag> {
+	let ParamsForUpdate { id, data } = params;
+
+	TagBmc::update(&ctx, &mm, id, data).await?;
+
+	let tag = TagBmc::get(&ctx, &mm, id).await?;
+
+	Ok(tag)
+}
+
+pub async fn delete_tag(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Tag> {
+	let ParamsIded { id } = params;
+
+	let tag = TagBmc::get(&ctx, &mm, id).await?;
+	TagBmc::delete(&ctx, &mm, id).await?;
+
+	Ok(tag)
+}
+
+pub async fn attach_tag(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsTaskTag,
+) -> Result<()> {
+	let ParamsTaskTag { task_id, tag_id } = params;
+
+	TagBmc::attach(&ctx, &mm, task_id, tag_id).await?;
+
+	Ok(())
+}
+
+pub async fn detach_tag(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsTaskTag,
+) -> Result<()> {
+	let ParamsTaskTag { task_id, tag_id } = params;
+
+	TagBmc::detach(&ctx, &mm, task_id, tag_id).await?;
+
+	Ok(())
+}
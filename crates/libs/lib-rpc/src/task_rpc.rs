@@ -1,11 +1,60 @@
-use crate::params::ParamsList;
-use crate::Result;
-use crate::{ParamsForCreate, ParamsForUpdate, ParamsIded};
+use crate::bulk::{bulk_target, ItemResult};
+use crate::params::{ParamsGet, ParamsListProjected, ParamsSearch};
+use crate::projection::Projected;
+use crate::{Error, Result, RpcMethodConfig, RpcRouter};
+use crate::{
+	ParamsForCreate, ParamsForCreateMany, ParamsForDeleteMany, ParamsForUpdate,
+	ParamsForUpdateMany, ParamsIded,
+};
 use lib_core::ctx::Ctx;
+use lib_core::model::schema::{OneOrManySchema, Rfc3339Schema};
 use lib_core::model::task::{
-	Task, TaskBmc, TaskFilter, TaskForCreate, TaskForUpdate,
+	Task, TaskBmc, TaskDeletePolicy, TaskFilter, TaskForCreate, TaskForUpdate,
+	TaskMove, TaskNode, TaskOccurrence, TaskOccurrenceScope, TaskRevision,
+	TaskSearchHit,
 };
-use lib_core::model::ModelManager;
+use lib_core::model::{BulkTarget, ModelManager};
+use lib_utils::time::Rfc3339;
+use lib_utils::validate::Validate;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_with::{serde_as, OneOrMany};
+use std::time::Duration;
+use time::OffsetDateTime;
+
+pub fn rpc_router() -> RpcRouter {
+	crate::rpc_router!(
+		create_task => RpcMethodConfig::new().idempotent(),
+		get_task,
+		list_tasks,
+		search_tasks,
+		update_task => RpcMethodConfig::new().idempotent(),
+		delete_task => RpcMethodConfig::new().idempotent(),
+		create_tasks => bulk_method_config(),
+		update_tasks => bulk_method_config(),
+		delete_tasks => bulk_method_config(),
+		set_task_parent => RpcMethodConfig::new().idempotent(),
+		list_task_tree,
+		move_task => RpcMethodConfig::new().idempotent(),
+		assign_task => RpcMethodConfig::new().idempotent(),
+		unassign_task => RpcMethodConfig::new().idempotent(),
+		watch_task => RpcMethodConfig::new().idempotent(),
+		unwatch_task => RpcMethodConfig::new().idempotent(),
+		update_task_occurrence => RpcMethodConfig::new().idempotent(),
+		list_task_occurrences,
+		get_task_history,
+		revert_task => RpcMethodConfig::new().idempotent(),
+	)
+}
+
+/// The config of the bulk methods, rate limited (as they write up to 1000
+/// items per call), and labeled for their metrics.
+fn bulk_method_config() -> RpcMethodConfig {
+	RpcMethodConfig::new()
+		.idempotent()
+		.rate_limit(30, Duration::from_secs(60))
+		.label("kind", "bulk")
+}
 
 pub async fn create_task(
 	ctx: Ctx,
@@ -20,17 +69,68 @@ pub async fn create_task(
 	Ok(task)
 }
 
+pub async fn get_task(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsGet,
+) -> Result<Projected<Task>> {
+	let ParamsGet { id, fields } = params;
+
+	let task = match fields {
+		Some(fields) => {
+			Projected::Fields(TaskBmc::get_projected(&ctx, &mm, id, &fields).await?)
+		}
+		None => Projected::Full(TaskBmc::get(&ctx, &mm, id).await?),
+	};
+
+	Ok(task)
+}
+
 pub async fn list_tasks(
 	ctx: Ctx,
 	mm: ModelManager,
-	params: ParamsList<TaskFilter>,
-) -> Result<Vec<Task>> {
-	let tasks =
-		TaskBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+	params: ParamsListProjected<TaskFilter>,
+) -> Result<Vec<Projected<Task>>> {
+	let ParamsListProjected {
+		filters,
+		list_options,
+		fields,
+	} = params;
+
+	let tasks = match fields {
+		Some(fields) => {
+			TaskBmc::list_projected(&ctx, &mm, filters, list_options, &fields)
+				.await?
+				.into_iter()
+				.map(Projected::Fields)
+				.collect()
+		}
+		None => TaskBmc::list(&ctx, &mm, filters, list_options)
+			.await?
+			.into_iter()
+			.map(Projected::Full)
+			.collect(),
+	};
 
 	Ok(tasks)
 }
 
+pub async fn search_tasks(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsSearch<TaskFilter>,
+) -> Result<Vec<TaskSearchHit>> {
+	let ParamsSearch {
+		query,
+		filters,
+		list_options,
+	} = params;
+
+	let hits = TaskBmc::search(&ctx, &mm, &query, filters, list_options).await?;
+
+	Ok(hits)
+}
+
 pub async fn update_task(
 	ctx: Ctx,
 	mm: ModelManager,
@@ -45,15 +145,565 @@ pub async fn update_task(
 	Ok(task)
 }
 
+/// Params for `delete_task`. The `policy` (default `reparent`) applies to the subtasks.
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForDeleteTask {
+	pub id: i64,
+	#[serde(default)]
+	pub policy: TaskDeletePolicy,
+}
+
 pub async fn delete_task(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForDeleteTask,
+) -> Result<Task> {
+	let ParamsForDeleteTask { id, policy } = params;
+
+	let task = TaskBmc::get(&ctx, &mm, id).await?;
+	TaskBmc::delete_with_policy(&ctx, &mm, id, policy).await?;
+
+	Ok(task)
+}
+
+// region:    --- Tree
+
+/// Params for `set_task_parent`. A `null` parent_id makes the task a root task.
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForSetParent {
+	pub id: i64,
+	pub parent_id: Option<i64>,
+}
+
+/// Params for `list_task_tree`. Without `root_id`, returns the trees of all root tasks.
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForListTree {
+	pub root_id: Option<i64>,
+}
+
+pub async fn set_task_parent(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForSetParent,
+) -> Result<Task> {
+	let ParamsForSetParent { id, parent_id } = params;
+
+	TaskBmc::set_parent(&ctx, &mm, id, parent_id).await?;
+
+	let task = TaskBmc::get(&ctx, &mm, id).await?;
+
+	Ok(task)
+}
+
+pub async fn list_task_tree(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForListTree,
+) -> Result<Vec<TaskNode>> {
+	let nodes = TaskBmc::list_tree(&ctx, &mm, params.root_id).await?;
+
+	Ok(nodes)
+}
+
+// endregion: --- Tree
+
+// region:    --- Rank
+
+/// Params for `move_task`. Exactly one of `before_id` or `after_id` must be given.
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForMoveTask {
+	pub id: i64,
+	pub before_id: Option<i64>,
+	pub after_id: Option<i64>,
+}
+
+pub async fn move_task(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForMoveTask,
+) -> Result<Task> {
+	let ParamsForMoveTask {
+		id,
+		before_id,
+		after_id,
+	} = params;
+
+	let to = match (before_id, after_id) {
+		(Some(before_id), None) => TaskMove::Before(before_id),
+		(None, Some(after_id)) => TaskMove::After(after_id),
+		_ => {
+			return Err(Error::RpcInvalidParams {
+				rpc_method: "move_task".to_string(),
+				cause: "exactly one of 'before_id' or 'after_id' must be given",
+			})
+		}
+	};
+
+	TaskBmc::move_task(&ctx, &mm, id, to).await?;
+
+	let task = TaskBmc::get(&ctx, &mm, id).await?;
+
+	Ok(task)
+}
+
+// endregion: --- Rank
+
+// region:    --- Assignment
+
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForAssignTask {
+	pub id: i64,
+	pub assignee_id: i64,
+}
+
+pub async fn assign_task(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForAssignTask,
+) -> Result<Task> {
+	let ParamsForAssignTask { id, assignee_id } = params;
+
+	TaskBmc::assign(&ctx, &mm, id, assignee_id).await?;
+
+	let task = TaskBmc::get(&ctx, &mm, id).await?;
+
+	Ok(task)
+}
+
+pub async fn unassign_task(
 	ctx: Ctx,
 	mm: ModelManager,
 	params: ParamsIded,
 ) -> Result<Task> {
 	let ParamsIded { id } = params;
 
+	TaskBmc::unassign(&ctx, &mm, id).await?;
+
 	let task = TaskBmc::get(&ctx, &mm, id).await?;
-	TaskBmc::delete(&ctx, &mm, id).await?;
 
 	Ok(task)
 }
+
+/// Watches the task as the ctx user, and returns its watcher ids.
+pub async fn watch_task(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Vec<i64>> {
+	let ParamsIded { id } = params;
+
+	TaskBmc::watch(&ctx, &mm, id).await?;
+
+	let watcher_ids = TaskBmc::list_watcher_ids(&ctx, &mm, id).await?;
+
+	Ok(watcher_ids)
+}
+
+/// Unwatches the task as the ctx user, and returns its watcher ids.
+pub async fn unwatch_task(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Vec<i64>> {
+	let ParamsIded { id } = params;
+
+	TaskBmc::unwatch(&ctx, &mm, id).await?;
+
+	let watcher_ids = TaskBmc::list_watcher_ids(&ctx, &mm, id).await?;
+
+	Ok(watcher_ids)
+}
+
+// endregion: --- Assignment
+
+// region:    --- Recurrence
+
+/// Params for `update_task_occurrence`, with the `scope` as `this` or `all_future`.
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForUpdateOccurrence {
+	pub id: i64,
+	#[validate(nested)]
+	pub data: TaskForUpdate,
+	pub scope: TaskOccurrenceScope,
+}
+
+/// Params for `list_task_occurrences`, for the tasks due in `[from, to)`.
+#[serde_as]
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForListOccurrences {
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "Rfc3339Schema")]
+	pub from: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "Rfc3339Schema")]
+	pub to: OffsetDateTime,
+	#[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
+	#[schemars(with = "Option<OneOrManySchema<TaskFilter>>")]
+	pub filters: Option<Vec<TaskFilter>>,
+}
+
+pub async fn update_task_occurrence(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdateOccurrence,
+) -> Result<Task> {
+	let ParamsForUpdateOccurrence { id, data, scope } = params;
+
+	TaskBmc::update_occurrence(&ctx, &mm, id, data, scope).await?;
+
+	let task = TaskBmc::get(&ctx, &mm, id).await?;
+
+	Ok(task)
+}
+
+pub async fn list_task_occurrences(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForListOccurrences,
+) -> Result<Vec<TaskOccurrence>> {
+	let ParamsForListOccurrences { from, to, filters } = params;
+
+	let occurrences =
+		TaskBmc::list_occurrences(&ctx, &mm, from, to, filters).await?;
+
+	Ok(occurrences)
+}
+
+// endregion: --- Recurrence
+
+// region:    --- History
+
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForRevertTask {
+	pub id: i64,
+	#[validate(range(min = 1))]
+	pub revision: i32,
+}
+
+pub async fn get_task_history(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Vec<TaskRevision>> {
+	let ParamsIded { id } = params;
+
+	let revisions = TaskBmc::list_revisions(&ctx, &mm, id).await?;
+
+	Ok(revisions)
+}
+
+pub async fn revert_task(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForRevertTask,
+) -> Result<Task> {
+	let ParamsForRevertTask { id, revision } = params;
+
+	TaskBmc::revert(&ctx, &mm, id, revision).await?;
+
+	let task = TaskBmc::get(&ctx, &mm, id).await?;
+
+	Ok(task)
+}
+
+// endregion: --- History
+
+// region:    --- Bulk
+
+pub async fn create_tasks(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreateMany<TaskForCreate>,
+) -> Result<Vec<ItemResult<Task>>> {
+	let ParamsForCreateMany { data, per_item } = params;
+
+	// -- Per item, each task is created on its own.
+	if per_item {
+		let mut items = Vec::with_capacity(data.len());
+		for task_c in data {
+			let res = match TaskBmc::create(&ctx, &mm, task_c).await {
+				Ok(id) => TaskBmc::get(&ctx, &mm, id).await,
+				Err(err) => Err(err),
+			};
+			items.push(res.into());
+		}
+		return Ok(items);
+	}
+
+	// -- Atomic, all tasks are created with one multi-row insert.
+	let ids = TaskBmc::create_many(&ctx, &mm, data).await?;
+	let tasks = TaskBmc::list_by_ids(&ctx, &mm, &ids).await?;
+
+	Ok(tasks.into_iter().map(ItemResult::Ok).collect())
+}
+
+pub async fn update_tasks(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdateMany<TaskForUpdate, TaskFilter>,
+) -> Result<Vec<ItemResult<Task>>> {
+	let ParamsForUpdateMany {
+		ids,
+		filters,
+		data,
+		per_item,
+	} = params;
+	let target = bulk_target("update_tasks", ids, filters)?;
+
+	// -- Per item, each id is updated on its own.
+	if let (true, BulkTarget::Ids(ids)) = (per_item, &target) {
+		let mut items = Vec::with_capacity(ids.len());
+		for &id in ids {
+			let res = match TaskBmc::update(&ctx, &mm, id, data.clone()).await {
+				Ok(()) => TaskBmc::get(&ctx, &mm, id).await,
+				Err(err) => Err(err),
+			};
+			items.push(res.into());
+		}
+		return Ok(items);
+	}
+
+	// -- Atomic, all targeted tasks are updated with one statement.
+	let ids = TaskBmc::update_many(&ctx, &mm, target, data).await?;
+	let tasks = TaskBmc::list_by_ids(&ctx, &mm, &ids).await?;
+
+	Ok(tasks.into_iter().map(ItemResult::Ok).collect())
+}
+
+/// Note: Unlike `delete_task`, returns the deleted ids (not the tasks),
+///       as filter targets are only known once deleted.
+///       In both modes, the subtasks are reparented (the default `TaskDeletePolicy`).
+pub async fn delete_tasks(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForDeleteMany<TaskFilter>,
+) -> Result<Vec<ItemResult<i64>>> {
+	let ParamsForDeleteMany {
+		ids,
+		filters,
+		per_item,
+	} = params;
+	let target = bulk_target("delete_tasks", ids, filters)?;
+
+	// -- Per item, each id is deleted on its own.
+	if let (true, BulkTarget::Ids(ids)) = (per_item, &target) {
+		let mut items = Vec::with_capacity(ids.len());
+		for &id in ids {
+			let res = TaskBmc::delete(&ctx, &mm, id).await.map(|_| id);
+			items.push(res.into());
+		}
+		return Ok(items);
+	}
+
+	// -- Atomic, all targeted tasks are deleted with one statement.
+	let ids = TaskBmc::delete_many(&ctx, &mm, target).await?;
+
+	Ok(ids.into_iter().map(ItemResult::Ok).collect())
+}
+
+// endregion: --- Bulk
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use lib_core::_dev_utils;
+	use lib_core::model;
+	use serde_json::json;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_delete_tasks_ok_reparent() -> Result<()> {
+		// -- Setup & Fixtures
+		// Note: With its own pool, as the pool connections are bound to the
+		//       test runtime (see the `router::idempotency` tests).
+		_dev_utils::init_test().await;
+		let mm = ModelManager::new().await?;
+		let ctx = Ctx::root_ctx();
+
+		for per_item in [false, true] {
+			let fx_parent = TaskBmc::create(
+				&ctx,
+				&mm,
+				TaskForCreate {
+					title: "test_delete_tasks_ok_reparent-parent".to_string(),
+					..Default::default()
+				},
+			)
+			.await?;
+			let fx_child = TaskBmc::create(
+				&ctx,
+				&mm,
+				TaskForCreate {
+					title: "test_delete_tasks_ok_reparent-child".to_string(),
+					parent_id: Some(fx_parent),
+					..Default::default()
+				},
+			)
+			.await?;
+
+			// -- Exec
+			let params = ParamsForDeleteMany::<TaskFilter> {
+				ids: Some(vec![fx_parent]),
+				filters: None,
+				per_item,
+			};
+			let items = delete_tasks(ctx.clone(), mm.clone(), params).await?;
+
+			// -- Check
+			assert!(
+				matches!(items[..], [ItemResult::Ok(id)] if id == fx_parent),
+				"per_item: {per_item}"
+			);
+			let child = TaskBmc::get(&ctx, &mm, fx_child).await?;
+			assert_eq!(child.parent_id, None, "per_item: {per_item}");
+
+			// -- Clean
+			TaskBmc::delete(&ctx, &mm, fx_child).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_writes_err_not_visible() -> Result<()> {
+		// -- Setup & Fixtures
+		_dev_utils::init_test().await;
+		let mm = ModelManager::new().await?;
+		let ctx = Ctx::new(9039)?;
+		let ctx_other = Ctx::new(9040)?;
+		let fx_title = "test_writes_err_not_visible";
+		let fx_parent_id = TaskBmc::create(
+			&ctx,
+			&mm,
+			TaskForCreate {
+				title: format!("{fx_title}-parent"),
+				..Default::default()
+			},
+		)
+		.await?;
+		let fx_id = TaskBmc::create(
+			&ctx,
+			&mm,
+			TaskForCreate {
+				title: fx_title.to_string(),
+				parent_id: Some(fx_parent_id),
+				..Default::default()
+			},
+		)
+		.await?;
+		// A revision 2, so a revert to 1 would change the description.
+		let task_u = TaskForUpdate {
+			description: Some("description 02".to_string()),
+			..Default::default()
+		};
+		TaskBmc::update(&ctx, &mm, fx_id, task_u).await?;
+		let fx_task = TaskBmc::get(&ctx, &mm, fx_id).await?;
+		let fx_data = json!({"title": "test_writes_err_not_visible - new"});
+
+		// -- Exec
+		let ctx_o = || ctx_other.clone();
+		let mm_o = || mm.clone();
+		let results: Vec<(&str, Result<(), Error>)> = vec![
+			(
+				"update_task",
+				update_task(
+					ctx_o(),
+					mm_o(),
+					serde_json::from_value(json!({"id": fx_id, "data": fx_data}))?,
+				)
+				.await
+				.map(|_| ()),
+			),
+			(
+				"update_tasks",
+				update_tasks(
+					ctx_o(),
+					mm_o(),
+					serde_json::from_value(
+						json!({"ids": [fx_id], "data": fx_data}),
+					)?,
+				)
+				.await
+				.map(|_| ()),
+			),
+			(
+				"delete_tasks",
+				delete_tasks(
+					ctx_o(),
+					mm_o(),
+					serde_json::from_value(json!({"ids": [fx_id]}))?,
+				)
+				.await
+				.map(|_| ()),
+			),
+			(
+				"delete_task",
+				delete_task(
+					ctx_o(),
+					mm_o(),
+					serde_json::from_value(json!({"id": fx_id}))?,
+				)
+				.await
+				.map(|_| ()),
+			),
+			(
+				"set_task_parent",
+				set_task_parent(
+					ctx_o(),
+					mm_o(),
+					serde_json::from_value(json!({"id": fx_id, "parent_id": null}))?,
+				)
+				.await
+				.map(|_| ()),
+			),
+			(
+				"update_task_occurrence",
+				update_task_occurrence(
+					ctx_o(),
+					mm_o(),
+					serde_json::from_value(
+						json!({"id": fx_id, "data": fx_data, "scope": "this"}),
+					)?,
+				)
+				.await
+				.map(|_| ()),
+			),
+			(
+				"revert_task",
+				revert_task(
+					ctx_o(),
+					mm_o(),
+					serde_json::from_value(json!({"id": fx_id, "revision": 1}))?,
+				)
+				.await
+				.map(|_| ()),
+			),
+		];
+
+		// -- Check
+		for (rpc_method, res) in results {
+			assert!(
+				matches!(
+					res,
+					Err(Error::Model(model::Error::TaskNotVisible { id, .. })) if id == fx_id
+				),
+				"{rpc_method} - TaskNotVisible not matching"
+			);
+		}
+		let task = TaskBmc::get(&ctx, &mm, fx_id).await?;
+		assert_eq!(task.title, fx_task.title);
+		assert_eq!(task.description, fx_task.description);
+		assert_eq!(task.parent_id, Some(fx_parent_id));
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_parent_id).await?;
+		TaskBmc::delete(&ctx, &mm, fx_id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
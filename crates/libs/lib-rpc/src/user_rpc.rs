@@ -0,0 +1,68 @@
+use crate::{Result, RpcMethodConfig, RpcRouter};
+use lib_core::ctx::Ctx;
+use lib_core::model::user::{User, UserBmc, UserForUpdate};
+use lib_core::model::ModelManager;
+use lib_utils::validate::Validate;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::time::Duration;
+
+pub fn rpc_router() -> RpcRouter {
+	crate::rpc_router!(
+		get_me,
+		update_me => RpcMethodConfig::new().idempotent(),
+		// Note: Rate limited, as it checks the old password (i.e., against guessing).
+		change_password => RpcMethodConfig::new()
+			.rate_limit(5, Duration::from_secs(60))
+			.idempotent(),
+	)
+}
+
+/// Params for `update_me`.
+/// Note: A `username` change requires a new login (see `UserForUpdate`).
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForUpdateMe {
+	#[validate(nested)]
+	pub data: UserForUpdate,
+}
+
+#[derive(Deserialize, JsonSchema, Validate)]
+pub struct ParamsForChangePassword {
+	pub pwd_old: String,
+	#[validate(non_empty)]
+	pub pwd_new: String,
+}
+
+/// Returns the ctx user.
+pub async fn get_me(ctx: Ctx, mm: ModelManager) -> Result<User> {
+	let user = UserBmc::get(&ctx, &mm, ctx.user_id()).await?;
+
+	Ok(user)
+}
+
+pub async fn update_me(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdateMe,
+) -> Result<User> {
+	let ParamsForUpdateMe { data } = params;
+
+	UserBmc::update(&ctx, &mm, ctx.user_id(), data).await?;
+
+	let user = UserBmc::get(&ctx, &mm, ctx.user_id()).await?;
+
+	Ok(user)
+}
+
+/// Changes the ctx user password, when `pwd_old` matches the current password.
+pub async fn change_password(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForChangePassword,
+) -> Result<()> {
+	let ParamsForChangePassword { pwd_old, pwd_new } = params;
+
+	UserBmc::change_pwd(&ctx, &mm, ctx.user_id(), &pwd_old, &pwd_new).await?;
+
+	Ok(())
+}
@@ -0,0 +1,18 @@
+//! The derive macros of the `lib-utils` modules
+//! (re-exported by their module, e.g., `lib_utils::validate::Validate`).
+
+mod validate;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Derives `lib_utils::validate::Validate` from the `#[validate(...)]`
+/// field attributes (see the `lib_utils::validate` module for the rules).
+#[proc_macro_derive(Validate, attributes(validate))]
+pub fn derive_validate(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+
+	validate::derive(input)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
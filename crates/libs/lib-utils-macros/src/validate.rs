@@ -0,0 +1,214 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{
+	parse_quote, Data, DeriveInput, Expr, Fields, GenericArgument, LitInt,
+	PathArguments, Result, Type,
+};
+
+/// A `#[validate(...)]` rule of a field.
+enum Rule {
+	NonEmpty,
+	Trimmed,
+	Length {
+		min: Option<LitInt>,
+		max: Option<LitInt>,
+	},
+	Range {
+		min: Option<Expr>,
+		max: Option<Expr>,
+	},
+	Regex(Expr),
+	Nested,
+}
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+	let Data::Struct(data) = &input.data else {
+		return Err(syn::Error::new_spanned(
+			&input.ident,
+			"Validate can only be derived for structs",
+		));
+	};
+	let Fields::Named(fields) = &data.fields else {
+		return Err(syn::Error::new_spanned(
+			&input.ident,
+			"Validate can only be derived for structs with named fields",
+		));
+	};
+
+	let mut generics = input.generics.clone();
+	let mut checks = Vec::new();
+
+	for field in fields.named.iter() {
+		let Some(ident) = &field.ident else {
+			continue;
+		};
+		let name = ident.to_string();
+
+		// -- Parse the rules
+		let mut rules = Vec::new();
+		for attr in field.attrs.iter().filter(|a| a.path().is_ident("validate")) {
+			attr.parse_nested_meta(|meta| {
+				rules.push(parse_rule(&meta)?);
+				Ok(())
+			})?;
+		}
+		if rules.is_empty() {
+			continue;
+		}
+
+		// -- The rules apply to the value of the `Option` fields.
+		let option_ty = option_inner_type(&field.ty);
+		let value_ty = option_ty.unwrap_or(&field.ty);
+		if rules.iter().any(|rule| matches!(rule, Rule::Nested)) {
+			generics
+				.make_where_clause()
+				.predicates
+				.push(parse_quote!(#value_ty: ::lib_utils::validate::Validate));
+		}
+
+		let rule_checks = rules.iter().map(|rule| rule_check(rule, &name));
+		let check = if option_ty.is_some() {
+			quote! {
+				if let ::core::option::Option::Some(value) = &self.#ident {
+					#(#rule_checks)*
+				}
+			}
+		} else {
+			quote! {
+				{
+					let value = &self.#ident;
+					#(#rule_checks)*
+				}
+			}
+		};
+		checks.push(check);
+	}
+
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+	Ok(quote! {
+		impl #impl_generics ::lib_utils::validate::Validate for #ident #ty_generics #where_clause {
+			fn validate(&self) -> ::lib_utils::validate::Result<()> {
+				#(#checks)*
+				::core::result::Result::Ok(())
+			}
+		}
+	})
+}
+
+fn parse_rule(meta: &ParseNestedMeta) -> Result<Rule> {
+	let path = &meta.path;
+
+	if path.is_ident("non_empty") {
+		Ok(Rule::NonEmpty)
+	} else if path.is_ident("trimmed") {
+		Ok(Rule::Trimmed)
+	} else if path.is_ident("nested") {
+		Ok(Rule::Nested)
+	} else if path.is_ident("regex") {
+		let mut regex = None;
+		meta.parse_nested_meta(|arg| {
+			if arg.path.is_ident("path") {
+				regex = Some(arg.value()?.parse()?);
+				Ok(())
+			} else {
+				Err(arg.error("expected `path`"))
+			}
+		})?;
+		let regex =
+			regex.ok_or_else(|| meta.error("expected `regex(path = ...)`"))?;
+		Ok(Rule::Regex(regex))
+	} else if path.is_ident("length") {
+		let (mut min, mut max) = (None, None);
+		meta.parse_nested_meta(|bound| {
+			if bound.path.is_ident("min") {
+				min = Some(bound.value()?.parse()?);
+			} else if bound.path.is_ident("max") {
+				max = Some(bound.value()?.parse()?);
+			} else {
+				return Err(bound.error("expected `min` or `max`"));
+			}
+			Ok(())
+		})?;
+		Ok(Rule::Length { min, max })
+	} else if path.is_ident("range") {
+		let (mut min, mut max) = (None, None);
+		meta.parse_nested_meta(|bound| {
+			if bound.path.is_ident("min") {
+				min = Some(bound.value()?.parse()?);
+			} else if bound.path.is_ident("max") {
+				max = Some(bound.value()?.parse()?);
+			} else {
+				return Err(bound.error("expected `min` or `max`"));
+			}
+			Ok(())
+		})?;
+		Ok(Rule::Range { min, max })
+	} else {
+		Err(meta.error(
+			"expected `non_empty`, `trimmed`, `length`, `range`, `regex`, or `nested`",
+		))
+	}
+}
+
+/// Returns the check of the rule on `value` (a reference to the field value).
+fn rule_check(rule: &Rule, name: &str) -> TokenStream {
+	let validate = quote!(::lib_utils::validate);
+
+	match rule {
+		Rule::NonEmpty => quote! {
+			#validate::check_non_empty(#name, value)?;
+		},
+		Rule::Trimmed => quote! {
+			#validate::check_trimmed(#name, value)?;
+		},
+		Rule::Length { min, max } => {
+			let min = option_tokens(min.as_ref());
+			let max = option_tokens(max.as_ref());
+			quote! {
+				#validate::check_length(#name, value, #min, #max)?;
+			}
+		}
+		Rule::Range { min, max } => {
+			let min = option_tokens(min.as_ref());
+			let max = option_tokens(max.as_ref());
+			quote! {
+				#validate::check_range(#name, value, #min, #max)?;
+			}
+		}
+		Rule::Regex(regex) => quote! {
+			#validate::check_regex(#name, value, &#regex)?;
+		},
+		Rule::Nested => quote! {
+			#validate::Validate::validate(value).map_err(|err| err.prefixed(#name))?;
+		},
+	}
+}
+
+fn option_tokens<T: quote::ToTokens>(value: Option<&T>) -> TokenStream {
+	match value {
+		Some(value) => quote!(::core::option::Option::Some(#value)),
+		None => quote!(::core::option::Option::None),
+	}
+}
+
+/// Returns `T` when the type is `Option<T>`.
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+	let Type::Path(type_path) = ty else {
+		return None;
+	};
+	let segment = type_path.path.segments.last()?;
+	if segment.ident != "Option" {
+		return None;
+	}
+	let PathArguments::AngleBracketed(args) = &segment.arguments else {
+		return None;
+	};
+
+	match args.args.first()? {
+		GenericArgument::Type(inner) => Some(inner),
+		_ => None,
+	}
+}
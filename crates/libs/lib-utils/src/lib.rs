@@ -6,4 +6,9 @@
 
 pub mod b64;
 pub mod envs;
+pub mod rank;
 pub mod time;
+pub mod validate;
+
+// Note: For the `Validate` derive (which uses `::lib_utils` paths) in this crate tests.
+extern crate self as lib_utils;
@@ -0,0 +1,228 @@
+//! Lexicographic fractional indexing keys (e.g., for the manual ordering of rows).
+//!
+//! A key is a base 62 fraction in `(0, 1)` (e.g., `"V"` is about 0.5), without
+//! trailing `'0'`, so that the byte order of the keys is their numeric order.
+//!
+//! Note: When stored in the db, the column must use a byte order collation
+//!       (e.g., `COLLATE "C"` in Postgres).
+
+const DIGITS: &[u8; 62] =
+	b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const BASE: usize = 62;
+
+/// Returns a key strictly between `before` and `after`
+/// (`None` being the start or the end of the list).
+pub fn key_between(before: Option<&str>, after: Option<&str>) -> Result<String> {
+	if let Some(before) = before {
+		validate_key(before)?;
+	}
+	if let Some(after) = after {
+		validate_key(after)?;
+	}
+	if let (Some(before), Some(after)) = (before, after) {
+		if before >= after {
+			return Err(Error::KeysNotOrdered {
+				before: before.to_string(),
+				after: after.to_string(),
+			});
+		}
+	}
+
+	let before = before.unwrap_or_default().as_bytes();
+	let key = midpoint(before, after.map(str::as_bytes));
+
+	// Note: Only made of DIGITS, so always valid utf8.
+	Ok(String::from_utf8(key).unwrap_or_default())
+}
+
+/// Returns `count` ordered keys, evenly spread over the key space
+/// (e.g., to rebalance keys which became too long).
+pub fn evenly_spaced_keys(count: usize) -> Vec<String> {
+	// The key width, with one extra digit to leave room between keys.
+	let mut width = 1;
+	while (BASE as u128).pow(width) <= count as u128 {
+		width += 1;
+	}
+	width += 1;
+	let space = (BASE as u128).pow(width);
+
+	(1..=count as u128)
+		.map(|i| {
+			let mut value = i * space / (count as u128 + 1);
+			let mut key = vec![DIGITS[0]; width as usize];
+			for digit in key.iter_mut().rev() {
+				*digit = DIGITS[(value % BASE as u128) as usize];
+				value /= BASE as u128;
+			}
+			while key.last() == Some(&DIGITS[0]) {
+				key.pop();
+			}
+			String::from_utf8(key).unwrap_or_default()
+		})
+		.collect()
+}
+
+/// The midpoint of the fractions `a` and `b` (`b` being 1 when `None`),
+/// with `a < b`, and no trailing zeros.
+fn midpoint(a: &[u8], b: Option<&[u8]>) -> Vec<u8> {
+	// -- Keep the common prefix (with `a` padded with zeros)
+	if let Some(b) = b {
+		let prefix_len = b
+			.iter()
+			.enumerate()
+			.take_while(|(i, c)| a.get(*i).unwrap_or(&DIGITS[0]) == *c)
+			.count();
+		if prefix_len > 0 {
+			let mut key = b[..prefix_len].to_vec();
+			let a_rest = a.get(prefix_len..).unwrap_or_default();
+			key.extend(midpoint(a_rest, Some(&b[prefix_len..])));
+			return key;
+		}
+	}
+
+	// -- First digits differ
+	let digit_a = a.first().map(|c| digit_index(*c)).unwrap_or(0);
+	let digit_b = b.map(|b| digit_index(b[0])).unwrap_or(BASE);
+
+	if digit_b - digit_a > 1 {
+		vec![DIGITS[(digit_a + digit_b) / 2]]
+	}
+	// Consecutive digits, but `b` has more digits, so its first digit is in between.
+	else if let Some(b) = b.filter(|b| b.len() > 1) {
+		vec![b[0]]
+	}
+	// Consecutive digits, so go one digit deeper after `a`.
+	else {
+		let mut key = vec![DIGITS[digit_a]];
+		key.extend(midpoint(a.get(1..).unwrap_or_default(), None));
+		key
+	}
+}
+
+fn digit_index(c: u8) -> usize {
+	DIGITS.iter().position(|d| *d == c).unwrap_or(0)
+}
+
+fn validate_key(key: &str) -> Result<()> {
+	let bytes = key.as_bytes();
+	let valid = !bytes.is_empty()
+		&& bytes.iter().all(|c| DIGITS.contains(c))
+		&& bytes.last() != Some(&DIGITS[0]);
+
+	if valid {
+		Ok(())
+	} else {
+		Err(Error::KeyInvalid(key.to_string()))
+	}
+}
+
+// region:    --- Error
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+	KeyInvalid(String),
+	KeysNotOrdered { before: String, after: String },
+}
+
+// region:    --- Error Boilerplate
+impl core::fmt::Display for Error {
+	fn fmt(
+		&self,
+		fmt: &mut core::fmt::Formatter,
+	) -> core::result::Result<(), core::fmt::Error> {
+		write!(fmt, "{self:?}")
+	}
+}
+
+impl std::error::Error for Error {}
+// endregion: --- Error Boilerplate
+
+// endregion: --- Error
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn test_key_between_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_cases: &[(Option<&str>, Option<&str>)] = &[
+			(None, None),
+			(Some("V"), None),
+			(None, Some("V")),
+			(Some("V"), Some("W")),
+			(Some("V"), Some("V1")),
+			(Some("z"), None),
+			(None, Some("01")),
+			(Some("Vzz"), Some("W")),
+		];
+
+		for (before, after) in fx_cases {
+			// -- Exec
+			let key = key_between(*before, *after)?;
+
+			// -- Check
+			assert!(!key.ends_with('0'), "trailing zero in {key}");
+			if let Some(before) = before {
+				assert!(*before < key.as_str(), "{before} < {key}");
+			}
+			if let Some(after) = after {
+				assert!(key.as_str() < *after, "{key} < {after}");
+			}
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_key_between_repeated_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mut low = key_between(None, None)?;
+		let high = key_between(Some(&low), None)?;
+
+		// -- Exec & Check (always inserting right after the `low` key)
+		for _ in 0..100 {
+			let key = key_between(Some(&low), Some(&high))?;
+			assert!(low < key && key < high);
+			low = key;
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_key_between_err_not_ordered() -> Result<()> {
+		// -- Exec
+		let res = key_between(Some("W"), Some("V"));
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::KeysNotOrdered { .. })),
+			"KeysNotOrdered not matching"
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_evenly_spaced_keys_ok() -> Result<()> {
+		for count in [1, 61, 62, 1000] {
+			// -- Exec
+			let keys = evenly_spaced_keys(count);
+
+			// -- Check
+			assert_eq!(keys.len(), count);
+			assert!(keys.windows(2).all(|w| w[0] < w[1]), "keys not ordered");
+			for key in keys.iter() {
+				validate_key(key)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
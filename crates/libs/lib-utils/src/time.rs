@@ -1,4 +1,7 @@
-use time::{Duration, OffsetDateTime};
+use core::fmt;
+use core::str::FromStr;
+use time::format_description;
+use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Weekday};
 
 pub use time::format_description::well_known::Rfc3339;
 
@@ -20,6 +23,325 @@ pub fn parse_utc(moment: &str) -> Result<OffsetDateTime> {
 		.map_err(|_| Error::FailToDateParse(moment.to_string()))
 }
 
+// region:    --- Recurrence
+
+/// The RRULE `UNTIL` formats (UTC date-time, or date for the whole day).
+const UNTIL_FORMAT: &str = "[year][month][day]T[hour][minute][second]Z";
+const UNTIL_DATE_FORMAT: &str = "[year][month][day]";
+
+/// Max number of occurrences scanned by `Recurrence::occurrences`
+/// (e.g., for a daily rule starting years before `from`).
+const OCCURRENCES_SCAN_MAX: usize = 100_000;
+
+/// A recurrence rule, as a subset of the iCalendar RRULE (RFC 5545),
+/// e.g., `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;COUNT=10`.
+///
+/// - `FREQ` - `DAILY`, `WEEKLY` or `MONTHLY` (required).
+/// - `INTERVAL` - Every n days/weeks/months (default 1).
+/// - `BYDAY` - e.g., `MO,FR`, only the days of the list (not for `MONTHLY`).
+/// - `UNTIL` - e.g., `20241231T235959Z` or `20241231` (the whole day).
+/// - `COUNT` - The number of occurrences, including the first one.
+///
+/// Note: The occurrences are computed in UTC, and a `MONTHLY` rule skips
+///       the months without the day of the month (e.g., the 31st).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+	pub freq: RecurFreq,
+	pub interval: u32,
+	pub by_weekday: Vec<Weekday>,
+	pub until: Option<OffsetDateTime>,
+	pub count: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecurFreq {
+	Daily,
+	Weekly,
+	Monthly,
+}
+
+impl Recurrence {
+	/// Returns the occurrence after the `occurrence`, with the recurrence of the
+	/// next ones (i.e., `count` decremented), or `None` when it was the last one.
+	pub fn next_occurrence(
+		&self,
+		occurrence: OffsetDateTime,
+	) -> Option<(OffsetDateTime, Recurrence)> {
+		if self.count == Some(1) {
+			return None;
+		}
+
+		let next = match self.freq {
+			RecurFreq::Daily => self.next_daily(occurrence),
+			RecurFreq::Weekly => self.next_weekly(occurrence),
+			RecurFreq::Monthly => self.next_monthly(occurrence),
+		}?;
+		if self.until.is_some_and(|until| next > until) {
+			return None;
+		}
+
+		let recurrence = Recurrence {
+			count: self.count.map(|count| count - 1),
+			..self.clone()
+		};
+
+		Some((next, recurrence))
+	}
+
+	/// Returns the occurrences in `[from, to)` (at most `max`), with `start`
+	/// as the first occurrence.
+	pub fn occurrences(
+		&self,
+		start: OffsetDateTime,
+		from: OffsetDateTime,
+		to: OffsetDateTime,
+		max: usize,
+	) -> Vec<OffsetDateTime> {
+		let mut occurrences = Vec::new();
+
+		let mut current = Some((start, self.clone()));
+		for _ in 0..OCCURRENCES_SCAN_MAX {
+			let Some((occurrence, recurrence)) = current else {
+				break;
+			};
+			if occurrence >= to || occurrences.len() >= max {
+				break;
+			}
+			if occurrence >= from {
+				occurrences.push(occurrence);
+			}
+			current = recurrence.next_occurrence(occurrence);
+		}
+
+		occurrences
+	}
+
+	fn next_daily(&self, occurrence: OffsetDateTime) -> Option<OffsetDateTime> {
+		let step = Duration::days(self.interval as i64);
+
+		// Note: The weekdays cycle within 7 steps.
+		let mut next = occurrence + step;
+		for _ in 0..7 {
+			if self.by_weekday.is_empty()
+				|| self.by_weekday.contains(&next.weekday())
+			{
+				return Some(next);
+			}
+			next += step;
+		}
+
+		None
+	}
+
+	fn next_weekly(&self, occurrence: OffsetDateTime) -> Option<OffsetDateTime> {
+		let interval = Duration::weeks(self.interval as i64);
+		let days: Vec<u8> = self
+			.by_weekday
+			.iter()
+			.map(|weekday| weekday.number_days_from_monday())
+			.collect();
+		let day = occurrence.weekday().number_days_from_monday();
+
+		// -- Same weekday, every interval weeks
+		let Some(first_day) = days.first() else {
+			return Some(occurrence + interval);
+		};
+
+		// -- Next day of the same week, otherwise, first day of the next interval week
+		match days.iter().find(|d| **d > day) {
+			Some(next_day) => {
+				Some(occurrence + Duration::days((next_day - day) as i64))
+			}
+			None => {
+				let week_start = occurrence - Duration::days(day as i64);
+				Some(week_start + interval + Duration::days(*first_day as i64))
+			}
+		}
+	}
+
+	fn next_monthly(&self, occurrence: OffsetDateTime) -> Option<OffsetDateTime> {
+		let mut month_index = occurrence.year() * 12 + occurrence.month() as i32 - 1;
+
+		// Note: 48 months covers the Feb 29 (with an interval of 12).
+		for _ in 0..48 {
+			month_index += self.interval as i32;
+			let month =
+				Month::try_from((month_index.rem_euclid(12) + 1) as u8).ok()?;
+			let date = Date::from_calendar_date(
+				month_index.div_euclid(12),
+				month,
+				occurrence.day(),
+			);
+			if let Ok(date) = date {
+				return Some(occurrence.replace_date(date));
+			}
+		}
+
+		None
+	}
+}
+
+impl FromStr for Recurrence {
+	type Err = Error;
+
+	fn from_str(rule: &str) -> Result<Self> {
+		let invalid = |cause: &'static str| Error::RecurrenceInvalid {
+			rule: rule.to_string(),
+			cause,
+		};
+
+		let mut freq = None;
+		let mut interval = 1;
+		let mut by_weekday = Vec::new();
+		let mut until = None;
+		let mut count = None;
+
+		let parts = rule.trim().trim_start_matches("RRULE:").split(';');
+		for part in parts.filter(|part| !part.is_empty()) {
+			let (name, value) = part
+				.split_once('=')
+				.ok_or_else(|| invalid("parts must be NAME=VALUE"))?;
+
+			match name.to_ascii_uppercase().as_str() {
+				"FREQ" => {
+					freq = Some(match value.to_ascii_uppercase().as_str() {
+						"DAILY" => RecurFreq::Daily,
+						"WEEKLY" => RecurFreq::Weekly,
+						"MONTHLY" => RecurFreq::Monthly,
+						_ => {
+							return Err(invalid(
+								"FREQ must be DAILY, WEEKLY or MONTHLY",
+							))
+						}
+					})
+				}
+				"INTERVAL" => {
+					interval = parse_positive(value).ok_or_else(|| {
+						invalid("INTERVAL must be a positive integer")
+					})?
+				}
+				"BYDAY" => {
+					by_weekday = value
+						.split(',')
+						.map(parse_weekday)
+						.collect::<Option<Vec<_>>>()
+						.ok_or_else(|| invalid("BYDAY must be a list of MO to SU"))?
+				}
+				"UNTIL" => {
+					until = Some(parse_until(value).ok_or_else(|| {
+						invalid("UNTIL must be YYYYMMDD[THHMMSSZ]")
+					})?)
+				}
+				"COUNT" => {
+					count = Some(parse_positive(value).ok_or_else(|| {
+						invalid("COUNT must be a positive integer")
+					})?)
+				}
+				_ => {
+					return Err(invalid(
+						"only FREQ, INTERVAL, BYDAY, UNTIL and COUNT",
+					))
+				}
+			}
+		}
+
+		let freq = freq.ok_or_else(|| invalid("FREQ is required"))?;
+		if until.is_some() && count.is_some() {
+			return Err(invalid("UNTIL and COUNT cannot be both given"));
+		}
+		if freq == RecurFreq::Monthly && !by_weekday.is_empty() {
+			return Err(invalid("BYDAY is not supported with MONTHLY"));
+		}
+		by_weekday.sort_by_key(|weekday| weekday.number_days_from_monday());
+		by_weekday.dedup();
+
+		Ok(Recurrence {
+			freq,
+			interval,
+			by_weekday,
+			until,
+			count,
+		})
+	}
+}
+
+impl fmt::Display for Recurrence {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let freq = match self.freq {
+			RecurFreq::Daily => "DAILY",
+			RecurFreq::Weekly => "WEEKLY",
+			RecurFreq::Monthly => "MONTHLY",
+		};
+		write!(f, "FREQ={freq}")?;
+
+		if self.interval > 1 {
+			write!(f, ";INTERVAL={}", self.interval)?;
+		}
+		if !self.by_weekday.is_empty() {
+			let days: Vec<&str> = self
+				.by_weekday
+				.iter()
+				.map(|weekday| weekday_code(*weekday))
+				.collect();
+			write!(f, ";BYDAY={}", days.join(","))?;
+		}
+		if let Some(until) = self.until {
+			let until = format_description::parse(UNTIL_FORMAT)
+				.ok()
+				.and_then(|format| until.format(&format).ok())
+				.ok_or(fmt::Error)?;
+			write!(f, ";UNTIL={until}")?;
+		}
+		if let Some(count) = self.count {
+			write!(f, ";COUNT={count}")?;
+		}
+
+		Ok(())
+	}
+}
+
+fn parse_positive(value: &str) -> Option<u32> {
+	value.parse().ok().filter(|value| *value > 0)
+}
+
+const WEEKDAY_CODES: [(&str, Weekday); 7] = [
+	("MO", Weekday::Monday),
+	("TU", Weekday::Tuesday),
+	("WE", Weekday::Wednesday),
+	("TH", Weekday::Thursday),
+	("FR", Weekday::Friday),
+	("SA", Weekday::Saturday),
+	("SU", Weekday::Sunday),
+];
+
+fn parse_weekday(code: &str) -> Option<Weekday> {
+	WEEKDAY_CODES
+		.iter()
+		.find(|(c, _)| c.eq_ignore_ascii_case(code.trim()))
+		.map(|(_, weekday)| *weekday)
+}
+
+fn weekday_code(weekday: Weekday) -> &'static str {
+	WEEKDAY_CODES
+		.iter()
+		.find(|(_, w)| *w == weekday)
+		.map(|(code, _)| *code)
+		.unwrap_or_default()
+}
+
+fn parse_until(value: &str) -> Option<OffsetDateTime> {
+	if !value.contains('T') {
+		let format = format_description::parse(UNTIL_DATE_FORMAT).ok()?;
+		let date = Date::parse(value, &format).ok()?;
+		Some(date.with_hms(23, 59, 59).ok()?.assume_utc())
+	} else {
+		let format = format_description::parse(UNTIL_FORMAT).ok()?;
+		Some(PrimitiveDateTime::parse(value, &format).ok()?.assume_utc())
+	}
+}
+
+// endregion: --- Recurrence
+
 // region:    --- Error
 
 pub type Result<T> = core::result::Result<T, Error>;
@@ -27,6 +349,7 @@ pub type Result<T> = core::result::Result<T, Error>;
 #[derive(Debug)]
 pub enum Error {
 	FailToDateParse(String),
+	RecurrenceInvalid { rule: String, cause: &'static str },
 }
 
 // region:    --- Error Boilerplate
@@ -43,3 +366,127 @@ impl std::error::Error for Error {}
 // endregion: --- Error Boilerplate
 
 // endregion: --- Error
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::{Context, Result};
+
+	#[test]
+	fn test_recurrence_parse_display_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_cases = &[
+			("FREQ=DAILY", "FREQ=DAILY"),
+			(
+				"RRULE:freq=weekly;interval=2;byday=we,mo",
+				"FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE",
+			),
+			(
+				"FREQ=MONTHLY;UNTIL=20241231",
+				"FREQ=MONTHLY;UNTIL=20241231T235959Z",
+			),
+			(
+				"FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR;COUNT=5",
+				"FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR;COUNT=5",
+			),
+		];
+
+		for (rule, fx_display) in fx_cases {
+			// -- Exec
+			let recurrence: Recurrence = rule.parse()?;
+
+			// -- Check
+			assert_eq!(recurrence.to_string(), *fx_display);
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_recurrence_parse_err_invalid() -> Result<()> {
+		for rule in [
+			"",
+			"FREQ=YEARLY",
+			"FREQ=DAILY;INTERVAL=0",
+			"FREQ=WEEKLY;BYDAY=XX",
+			"FREQ=MONTHLY;BYDAY=MO",
+			"FREQ=DAILY;COUNT=2;UNTIL=20241231",
+		] {
+			// -- Exec
+			let res = rule.parse::<Recurrence>();
+
+			// -- Check
+			assert!(
+				matches!(res, Err(Error::RecurrenceInvalid { .. })),
+				"RecurrenceInvalid not matching for {rule:?}"
+			);
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_recurrence_occurrences_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		// Note: 2024-01-01 is a Monday.
+		let fx_start = parse_utc("2024-01-01T09:00:00Z")?;
+		let fx_cases = &[
+			(
+				"FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE",
+				&["2024-01-01", "2024-01-03", "2024-01-15", "2024-01-17"][..],
+			),
+			(
+				"FREQ=DAILY;BYDAY=FR,SA;COUNT=3",
+				&["2024-01-01", "2024-01-05", "2024-01-06"],
+			),
+			(
+				"FREQ=MONTHLY;UNTIL=20240401",
+				&["2024-01-01", "2024-02-01", "2024-03-01", "2024-04-01"],
+			),
+		];
+
+		for (rule, fx_days) in fx_cases {
+			// -- Exec
+			let recurrence: Recurrence = rule.parse()?;
+			let occurrences = recurrence.occurrences(
+				fx_start,
+				fx_start,
+				parse_utc("2025-01-01T00:00:00Z")?,
+				4,
+			);
+
+			// -- Check
+			let days: Vec<String> = occurrences
+				.iter()
+				.map(|o| format_time(*o)[..10].to_string())
+				.collect();
+			assert_eq!(&days, fx_days, "for {rule}");
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_recurrence_next_monthly_skip_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_occurrence = parse_utc("2024-01-31T09:00:00Z")?;
+		let recurrence: Recurrence = "FREQ=MONTHLY;COUNT=3".parse()?;
+
+		// -- Exec
+		let (next, recurrence) = recurrence
+			.next_occurrence(fx_occurrence)
+			.context("Should have a next occurrence")?;
+		let (_, recurrence) = recurrence
+			.next_occurrence(next)
+			.context("Should have a next occurrence")?;
+
+		// -- Check (Feb has no 31st)
+		assert_eq!(format_time(next), "2024-03-31T09:00:00Z");
+		assert_eq!(recurrence.count, Some(1));
+		assert!(recurrence.next_occurrence(next).is_none());
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
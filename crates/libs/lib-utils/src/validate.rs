@@ -0,0 +1,295 @@
+//! Declarative validation of the input types, with the `Validate` derive
+//! and its `#[validate(...)]` field rules:
+//!
+//! - `non_empty`: the string is not empty.
+//! - `trimmed`: the string has no leading or trailing whitespace.
+//! - `length(min = 1, max = 256)`: the string length, in chars (bounds optional).
+//! - `range(min = 1, max = 100)`: the value bounds, inclusive (bounds optional).
+//! - `regex(path = CODE_REGEX)`: the string matches the `LazyRegex` static.
+//! - `nested`: the value is itself `Validate`, and its error field is prefixed
+//!   (e.g., `data.title`, or `data[2].title` for a `Vec`).
+//!
+//! Note: The rules of an `Option` field only apply when `Some`.
+//!
+//! Note: The rules follow the `validator` crate syntax, which the `JsonSchema`
+//!       derive also reads (e.g., `length(max = 256)` as the `maxLength`).
+
+use regex::Regex;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+pub use lib_utils_macros::Validate;
+
+pub trait Validate {
+	/// Returns the error of the first failed rule.
+	fn validate(&self) -> Result<()>;
+}
+
+impl<T: Validate> Validate for Vec<T> {
+	fn validate(&self) -> Result<()> {
+		for (i, item) in self.iter().enumerate() {
+			item.validate()
+				.map_err(|err| err.prefixed(&format!("[{i}]")))?;
+		}
+		Ok(())
+	}
+}
+
+impl<T: Validate> Validate for Option<T> {
+	fn validate(&self) -> Result<()> {
+		match self {
+			Some(value) => value.validate(),
+			None => Ok(()),
+		}
+	}
+}
+
+// region:    --- Rule Checks
+
+pub fn check_non_empty(field: &str, value: &str) -> Result<()> {
+	if value.is_empty() {
+		return Err(Error::new(field, "non_empty", "must not be empty"));
+	}
+	Ok(())
+}
+
+pub fn check_trimmed(field: &str, value: &str) -> Result<()> {
+	if value.trim() != value {
+		return Err(Error::new(
+			field,
+			"trimmed",
+			"must not have leading or trailing whitespace",
+		));
+	}
+	Ok(())
+}
+
+pub fn check_length(
+	field: &str,
+	value: &str,
+	min: Option<usize>,
+	max: Option<usize>,
+) -> Result<()> {
+	let length = value.chars().count();
+	if let Some(min) = min.filter(|min| length < *min) {
+		let message = format!("must have at least {min} characters");
+		return Err(Error::new(field, "length", message));
+	}
+	if let Some(max) = max.filter(|max| length > *max) {
+		let message = format!("must have at most {max} characters");
+		return Err(Error::new(field, "length", message));
+	}
+	Ok(())
+}
+
+pub fn check_range<T>(
+	field: &str,
+	value: &T,
+	min: Option<T>,
+	max: Option<T>,
+) -> Result<()>
+where
+	T: PartialOrd + Display,
+{
+	if let Some(min) = min.filter(|min| value < min) {
+		return Err(Error::new(
+			field,
+			"range",
+			format!("must be at least {min}"),
+		));
+	}
+	if let Some(max) = max.filter(|max| value > max) {
+		return Err(Error::new(field, "range", format!("must be at most {max}")));
+	}
+	Ok(())
+}
+
+pub fn check_regex(field: &str, value: &str, regex: &LazyRegex) -> Result<()> {
+	if !regex.get().is_match(value) {
+		let message = format!("must match the pattern {}", regex.pattern);
+		return Err(Error::new(field, "regex", message));
+	}
+	Ok(())
+}
+
+/// The regex of a `regex` rule, compiled on its first check,
+/// and displayed as its pattern.
+pub struct LazyRegex {
+	pattern: &'static str,
+	regex: OnceLock<Regex>,
+}
+
+impl LazyRegex {
+	pub const fn new(pattern: &'static str) -> Self {
+		Self {
+			pattern,
+			regex: OnceLock::new(),
+		}
+	}
+
+	fn get(&self) -> &Regex {
+		self.regex
+			.get_or_init(|| Regex::new(self.pattern).expect("Invalid regex pattern"))
+	}
+}
+
+impl Display for LazyRegex {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.pattern)
+	}
+}
+
+// endregion: --- Rule Checks
+
+// region:    --- Error
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// The first failed rule of a validation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+	/// The field path (e.g., `title`, or `data.title` with a nested rule).
+	pub field: String,
+	/// The rule name (e.g., `length`).
+	pub rule: &'static str,
+	pub message: String,
+}
+
+impl Error {
+	pub fn new(field: &str, rule: &'static str, message: impl Into<String>) -> Self {
+		Self {
+			field: field.to_string(),
+			rule,
+			message: message.into(),
+		}
+	}
+
+	/// Returns the error with its field prefixed by the parent field
+	/// (e.g., `data.title`, or `data[2].title` for the `Vec` items).
+	pub fn prefixed(mut self, prefix: &str) -> Self {
+		self.field = if self.field.starts_with('[') {
+			format!("{prefix}{}", self.field)
+		} else {
+			format!("{prefix}.{}", self.field)
+		};
+		self
+	}
+}
+
+// region:    --- Error Boilerplate
+impl core::fmt::Display for Error {
+	fn fmt(
+		&self,
+		fmt: &mut core::fmt::Formatter,
+	) -> core::result::Result<(), core::fmt::Error> {
+		write!(fmt, "{self:?}")
+	}
+}
+
+impl std::error::Error for Error {}
+// endregion: --- Error Boilerplate
+
+// endregion: --- Error
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	static CODE_REGEX: LazyRegex = LazyRegex::new("^[a-z]+$");
+
+	#[derive(Validate)]
+	struct ItemForCreate {
+		#[validate(non_empty, trimmed, length(max = 8))]
+		name: String,
+		#[validate(range(min = 1, max = 5))]
+		priority: Option<i32>,
+		#[validate(regex(path = CODE_REGEX))]
+		code: Option<String>,
+	}
+
+	#[derive(Validate)]
+	struct ParamsForCreate<D> {
+		#[validate(nested)]
+		data: Vec<D>,
+	}
+
+	fn fx_item(name: &str) -> ItemForCreate {
+		ItemForCreate {
+			name: name.to_string(),
+			priority: Some(3),
+			code: None,
+		}
+	}
+
+	#[test]
+	fn test_validate_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_item = ItemForCreate {
+			code: Some("abc".to_string()),
+			..fx_item("item 01")
+		};
+
+		// -- Exec & Check
+		fx_item.validate()?;
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_validate_err_rules() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_cases = [
+			(fx_item(""), "name", "non_empty"),
+			(fx_item(" item"), "name", "trimmed"),
+			(fx_item("item 0001"), "name", "length"),
+			(
+				ItemForCreate {
+					priority: Some(6),
+					..fx_item("item")
+				},
+				"priority",
+				"range",
+			),
+			(
+				ItemForCreate {
+					code: Some("ABC".to_string()),
+					..fx_item("item")
+				},
+				"code",
+				"regex",
+			),
+		];
+
+		for (item, fx_field, fx_rule) in fx_cases {
+			// -- Exec
+			let err = item.validate().expect_err("Should fail");
+
+			// -- Check
+			assert_eq!(err.field, fx_field);
+			assert_eq!(err.rule, fx_rule);
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_validate_err_nested() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_params = ParamsForCreate {
+			data: vec![fx_item("item 01"), fx_item("")],
+		};
+
+		// -- Exec
+		let err = fx_params.validate().expect_err("Should fail");
+
+		// -- Check
+		assert_eq!(err.field, "data[1].name");
+		assert_eq!(err.rule, "non_empty");
+		assert_eq!(err.message, "must not be empty");
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -19,28 +19,30 @@ async fn main() -> Result<()> {
 	req_login.await?.print().await?;
 
 	// -- Create Tasks
-	let mut task_ids: Vec<i64> = Vec::new();
-	for i in 0..=4 {
-		let req_create_task = hc.do_post(
-			"/api/rpc",
-			json!({
-				"id": 1,
-				"method": "create_task",
-				"params": {
-					"data": {
-						"title": format!("task AAA {i}")
-					}
-				}
-			}),
-		);
-		let result = req_create_task.await?;
-		task_ids.push(result.json_value::<i64>("/result/id")?);
-	}
+	let tasks_data: Vec<_> = (0..=4)
+		.map(|i| json!({"title": format!("task AAA {i}")}))
+		.collect();
+	let req_create_tasks = hc.do_post(
+		"/api/rpc",
+		json!({
+			"jsonrpc": "2.0",
+			"id": 1,
+			"method": "create_tasks",
+			"params": {
+				"data": tasks_data
+			}
+		}),
+	);
+	let result = req_create_tasks.await?;
+	let task_ids: Vec<i64> = (0..=4)
+		.map(|i| result.json_value::<i64>(&format!("/result/{i}/ok/id")))
+		.collect::<Result<_, _>>()?;
 
 	// -- Update first Task
 	let req_update_task = hc.do_post(
 		"/api/rpc",
 		json!({
+			"jsonrpc": "2.0",
 			"id": 1,
 			"method": "update_task",
 			"params": {
@@ -57,6 +59,7 @@ async fn main() -> Result<()> {
 	let req_delete_task = hc.do_post(
 		"/api/rpc",
 		json!({
+			"jsonrpc": "2.0",
 			"id": 1,
 			"method": "delete_task",
 			"params": {
@@ -70,6 +73,7 @@ async fn main() -> Result<()> {
 	let req_list_tasks = hc.do_post(
 		"/api/rpc",
 		json!({
+			"jsonrpc": "2.0",
 			"id": 1,
 			"method": "list_tasks",
 			"params": {
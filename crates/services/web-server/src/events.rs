@@ -0,0 +1,78 @@
+//! The in-process subscribers of the domain events, which are published
+//! from the model outbox by the outbox relay job (see `jobs::spawn_outbox_relay`),
+//! and the live task changes (see `jobs::spawn_task_change_listener`).
+
+use crate::Result;
+use async_trait::async_trait;
+use lib_core::model::outbox::{DomainEvent, OutboxEvent};
+use std::sync::Arc;
+use tracing::info;
+
+#[async_trait]
+pub trait EventSubscriber: Send + Sync {
+	/// The name of the subscriber (for the logs).
+	fn name(&self) -> &'static str;
+
+	/// Handles an event.
+	///
+	/// Note: The delivery is at-least-once, so an event can be handled more
+	///       than once (e.g., when an other subscriber failed on it),
+	///       and must be handled idempotently (e.g., by `event.id`).
+	async fn handle(&self, event: &OutboxEvent) -> Result<()>;
+}
+
+/// The registered subscribers, which get every event, in outbox order.
+#[derive(Clone, Default)]
+pub struct Subscribers(Vec<Arc<dyn EventSubscriber>>);
+
+impl Subscribers {
+	pub fn register(mut self, subscriber: impl EventSubscriber + 'static) -> Self {
+		self.0.push(Arc::new(subscriber));
+		self
+	}
+
+	/// Delivers the event to all the subscribers, and fails on the first
+	/// subscriber failure (with its name).
+	pub async fn publish(
+		&self,
+		event: &OutboxEvent,
+	) -> core::result::Result<(), (&'static str, crate::Error)> {
+		for subscriber in self.0.iter() {
+			subscriber
+				.handle(event)
+				.await
+				.map_err(|ex| (subscriber.name(), ex))?;
+		}
+
+		Ok(())
+	}
+}
+
+// region:    --- LogSubscriber
+/// Logs the events.
+pub struct LogSubscriber;
+
+#[async_trait]
+impl EventSubscriber for LogSubscriber {
+	fn name(&self) -> &'static str {
+		"log"
+	}
+
+	async fn handle(&self, event: &OutboxEvent) -> Result<()> {
+		info!("{:<12} - {} - {:?}", "EVENT", event.id, event.event);
+
+		Ok(())
+	}
+}
+// endregion: --- LogSubscriber
+
+// region:    --- TaskChange
+/// A live task change, broadcast to the `/api/events` clients.
+#[derive(Debug, Clone)]
+pub enum TaskChange {
+	Event(DomainEvent),
+	/// Some changes were lost (e.g., listener reconnection), so the
+	/// clients must refetch their tasks.
+	Resync,
+}
+// endregion: --- TaskChange
@@ -0,0 +1,168 @@
+//! Background jobs of the service, spawned at startup.
+
+use crate::events::{Subscribers, TaskChange};
+use lib_core::ctx::Ctx;
+use lib_core::model::attachment::AttachmentBmc;
+use lib_core::model::idempotency::IdempotencyKeyBmc;
+use lib_core::model::notify::TaskChangeListener;
+use lib_core::model::outbox::{OutboxBmc, OutboxOffset};
+use lib_core::model::ModelManager;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{error, info};
+
+/// The interval between the orphan blobs cleanups (1 hour).
+const BLOB_CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// The interval between the expired idempotency keys cleanups (1 hour).
+const IDEMPOTENCY_CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// The interval between the outbox polls (1 second).
+const OUTBOX_RELAY_INTERVAL: Duration = Duration::from_secs(1);
+/// The max number of events per outbox poll.
+const OUTBOX_RELAY_BATCH: i64 = 100;
+/// The name of the relay, which keys its offset in the outbox.
+const OUTBOX_RELAY_NAME: &str = "web-server";
+
+/// The delay before reconnecting the task changes listener.
+const TASK_CHANGES_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Periodically deletes the blobs left without attachment
+/// (e.g., when their task was deleted).
+pub fn spawn_blob_cleanup(mm: ModelManager) {
+	tokio::spawn(async move {
+		let ctx = Ctx::root_ctx();
+		let mut interval = tokio::time::interval(BLOB_CLEANUP_INTERVAL);
+
+		loop {
+			interval.tick().await;
+
+			match AttachmentBmc::cleanup_orphan_blobs(&ctx, &mm).await {
+				Ok(count) => info!("{:<12} - blob cleanup - {count} deleted", "JOB"),
+				Err(ex) => error!("{:<12} - blob cleanup - {ex:?}", "JOB"),
+			}
+		}
+	});
+}
+
+/// Periodically deletes the expired idempotency keys.
+pub fn spawn_idempotency_cleanup(mm: ModelManager) {
+	tokio::spawn(async move {
+		let ctx = Ctx::root_ctx();
+		let mut interval = tokio::time::interval(IDEMPOTENCY_CLEANUP_INTERVAL);
+
+		loop {
+			interval.tick().await;
+
+			match IdempotencyKeyBmc::delete_expired(&ctx, &mm).await {
+				Ok(count) => {
+					info!("{:<12} - idempotency cleanup - {count} deleted", "JOB")
+				}
+				Err(ex) => error!("{:<12} - idempotency cleanup - {ex:?}", "JOB"),
+			}
+		}
+	});
+}
+
+/// Publishes the outbox events to the subscribers, in order, and saves
+/// the offset of the last delivered event.
+///
+/// Note: On a subscriber failure, the event and the ones after it are
+///       delivered again at the next poll (i.e., at-least-once delivery).
+pub fn spawn_outbox_relay(mm: ModelManager, subscribers: Subscribers) {
+	tokio::spawn(async move {
+		let ctx = Ctx::root_ctx();
+		let mut interval = tokio::time::interval(OUTBOX_RELAY_INTERVAL);
+		let mut offset: Option<OutboxOffset> = None;
+
+		loop {
+			interval.tick().await;
+
+			// -- Load the offset (once, or again after a failed save).
+			let current = match offset {
+				Some(current) => current,
+				None => {
+					match OutboxBmc::get_offset(&ctx, &mm, OUTBOX_RELAY_NAME).await {
+						Ok(current) => current,
+						Err(ex) => {
+							error!("{:<12} - outbox relay - {ex:?}", "JOB");
+							continue;
+						}
+					}
+				}
+			};
+
+			let events =
+				match OutboxBmc::list_after(&ctx, &mm, current, OUTBOX_RELAY_BATCH)
+					.await
+				{
+					Ok(events) => events,
+					Err(ex) => {
+						error!("{:<12} - outbox relay - {ex:?}", "JOB");
+						continue;
+					}
+				};
+
+			// -- Publish, up to the first failure.
+			let mut delivered = current;
+			for event in events.iter() {
+				if let Err((name, ex)) = subscribers.publish(event).await {
+					error!(
+						"{:<12} - outbox relay - event {} - subscriber {name} - {ex:?}",
+						"JOB", event.id
+					);
+					break;
+				}
+				delivered = event.into();
+			}
+
+			// -- Save the offset.
+			if delivered == current {
+				offset = Some(current);
+				continue;
+			}
+			match OutboxBmc::set_offset(&ctx, &mm, OUTBOX_RELAY_NAME, delivered)
+				.await
+			{
+				Ok(()) => offset = Some(delivered),
+				Err(ex) => {
+					error!("{:<12} - outbox relay - {ex:?}", "JOB");
+					offset = None;
+				}
+			}
+		}
+	});
+}
+
+/// Holds the `LISTEN` connection on the task changes, and fans them out
+/// to the `task_changes` receivers (e.g., the `/api/events` clients).
+pub fn spawn_task_change_listener(task_changes: broadcast::Sender<TaskChange>) {
+	tokio::spawn(async move {
+		let mut listener = loop {
+			match TaskChangeListener::connect().await {
+				Ok(listener) => break listener,
+				Err(ex) => {
+					error!("{:<12} - task change listener - {ex:?}", "JOB");
+					tokio::time::sleep(TASK_CHANGES_RETRY_DELAY).await;
+				}
+			}
+		};
+		info!("{:<12} - task change listener - listening", "JOB");
+
+		loop {
+			// Note: The send fails only when there is no receiver, which is fine.
+			match listener.recv().await {
+				Ok(event) => {
+					let _ = task_changes.send(TaskChange::Event(event));
+				}
+				// Note: The listener reconnects on the next `recv`, and the changes
+				//       in between are lost, so the receivers must resync.
+				Err(ex) => {
+					error!("{:<12} - task change listener - {ex:?}", "JOB");
+					let _ = task_changes.send(TaskChange::Resync);
+					tokio::time::sleep(TASK_CHANGES_RETRY_DELAY).await;
+				}
+			}
+		}
+	});
+}
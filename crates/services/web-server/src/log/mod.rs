@@ -3,6 +3,7 @@ use crate::web::{self, ClientError};
 use crate::Result;
 use axum::http::{Method, Uri};
 use lib_core::ctx::Ctx;
+use lib_rpc::RpcCallMetric;
 use serde::Serialize;
 use serde_json::{json, Value};
 use serde_with::skip_serializing_none;
@@ -14,45 +15,65 @@ pub async fn log_request(
 	uuid: Uuid,
 	req_method: Method,
 	uri: Uri,
-	rpc_info: Option<&RpcInfo>,
 	ctx: Option<Ctx>,
 	web_error: Option<&web::Error>,
 	client_error: Option<ClientError>,
 ) -> Result<()> {
-	let timestamp = SystemTime::now()
-		.duration_since(UNIX_EPOCH)
-		.unwrap()
-		.as_millis();
+	let log_line = RequestLogLine {
+		http_path: Some(uri.to_string()),
+		http_method: Some(req_method.to_string()),
+		..RequestLogLine::new(uuid, ctx.as_ref(), web_error, client_error)
+	};
 
-	let error_type = web_error.map(|se| se.as_ref().to_string());
-	let error_data = serde_json::to_value(web_error)
-		.ok()
-		.and_then(|mut v| v.get_mut("data").map(|v| v.take()));
+	debug!("REQUEST LOG LINE:\n{}", json!(log_line));
 
-	// Create the RequestLogLine
-	let log_line = RequestLogLine {
-		uuid: uuid.to_string(),
-		timestamp: timestamp.to_string(),
+	// TODO - Send to cloud-watch.
 
-		http_path: uri.to_string(),
-		http_method: req_method.to_string(),
+	Ok(())
+}
 
+/// Logs a JSON-RPC call (a request can have many, e.g., a batch or a websocket).
+pub fn log_rpc_call(
+	uuid: Uuid,
+	rpc_info: Option<&RpcInfo>,
+	ctx: &Ctx,
+	web_error: Option<&web::Error>,
+	client_error: Option<ClientError>,
+) {
+	let log_line = RequestLogLine {
 		rpc_id: rpc_info.and_then(|rpc| rpc.id.as_ref().map(|id| id.to_string())),
 		rpc_method: rpc_info.map(|rpc| rpc.method.to_string()),
-
-		user_id: ctx.map(|c| c.user_id()),
-
-		client_error_type: client_error.map(|e| e.as_ref().to_string()),
-
-		error_type,
-		error_data,
+		..RequestLogLine::new(uuid, Some(ctx), web_error, client_error)
 	};
 
-	debug!("REQUEST LOG LINE:\n{}", json!(log_line));
+	debug!("RPC CALL LOG LINE:\n{}", json!(log_line));
 
 	// TODO - Send to cloud-watch.
+}
 
-	Ok(())
+/// Logs the metric of a JSON-RPC call (see `lib_rpc::MetricsMiddleware`).
+pub fn log_rpc_metric(metric: RpcCallMetric) {
+	let RpcCallMetric {
+		rpc_method,
+		labels,
+		duration,
+		ok,
+	} = metric;
+	let labels: serde_json::Map<String, Value> = labels
+		.into_iter()
+		.map(|(name, value)| (name.to_string(), json!(value)))
+		.collect();
+
+	let metric_line = json!({
+		"rpc_method": rpc_method,
+		"labels": labels,
+		"duration_ms": duration.as_secs_f64() * 1000.,
+		"ok": ok,
+	});
+
+	debug!("RPC CALL METRIC:\n{metric_line}");
+
+	// TODO - Send to the metrics backend.
 }
 
 #[skip_serializing_none]
@@ -65,8 +86,8 @@ struct RequestLogLine {
 	user_id: Option<i64>,
 
 	// -- http request attributes.
-	http_path: String,
-	http_method: String,
+	http_path: Option<String>,
+	http_method: Option<String>,
 
 	// -- rpc info.
 	rpc_id: Option<String>,
@@ -77,3 +98,40 @@ struct RequestLogLine {
 	error_type: Option<String>,
 	error_data: Option<Value>,
 }
+
+impl RequestLogLine {
+	fn new(
+		uuid: Uuid,
+		ctx: Option<&Ctx>,
+		web_error: Option<&web::Error>,
+		client_error: Option<ClientError>,
+	) -> Self {
+		let timestamp = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.unwrap()
+			.as_millis();
+
+		let error_type = web_error.map(|se| se.as_ref().to_string());
+		let error_data = serde_json::to_value(web_error)
+			.ok()
+			.and_then(|mut v| v.get_mut("data").map(|v| v.take()));
+
+		RequestLogLine {
+			uuid: uuid.to_string(),
+			timestamp: timestamp.to_string(),
+
+			user_id: ctx.map(|c| c.user_id()),
+
+			http_path: None,
+			http_method: None,
+
+			rpc_id: None,
+			rpc_method: None,
+
+			client_error_type: client_error.map(|e| e.as_ref().to_string()),
+
+			error_type,
+			error_data,
+		}
+	}
+}
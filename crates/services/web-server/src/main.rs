@@ -4,25 +4,28 @@
 
 mod config;
 mod error;
+mod events;
+mod jobs;
 mod log;
 mod web;
 
 pub use self::error::{Error, Result};
 use config::web_config;
 
-use crate::web::mw_auth::{mw_ctx_require, mw_ctx_resolve};
-use crate::web::mw_res_map::mw_reponse_map;
-use crate::web::{routes_login, routes_rpc, routes_static};
-use axum::{middleware, Router};
+use crate::events::{LogSubscriber, Subscribers};
 use lib_core::_dev_utils;
 use lib_core::model::ModelManager;
 use std::net::SocketAddr;
-use tower_cookies::CookieManagerLayer;
+use tokio::sync::broadcast;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 
 // endregion: --- Modules
 
+/// The task changes buffered per `/api/events` client (a client further
+/// behind gets a resync).
+const TASK_CHANGES_CAPACITY: usize = 256;
+
 #[tokio::main]
 async fn main() -> Result<()> {
 	tracing_subscriber::fmt()
@@ -37,17 +40,16 @@ async fn main() -> Result<()> {
 	// Initialize ModelManager.
 	let mm = ModelManager::new().await?;
 
+	// -- Start the background jobs.
+	jobs::spawn_blob_cleanup(mm.clone());
+	jobs::spawn_idempotency_cleanup(mm.clone());
+	let subscribers = Subscribers::default().register(LogSubscriber);
+	jobs::spawn_outbox_relay(mm.clone(), subscribers);
+	let (task_changes, _) = broadcast::channel(TASK_CHANGES_CAPACITY);
+	jobs::spawn_task_change_listener(task_changes.clone());
+
 	// -- Define Routes
-	let routes_rpc = routes_rpc::routes(mm.clone())
-		.route_layer(middleware::from_fn(mw_ctx_require));
-
-	let routes_all = Router::new()
-		.merge(routes_login::routes(mm.clone()))
-		.nest("/api", routes_rpc)
-		.layer(middleware::map_response(mw_reponse_map))
-		.layer(middleware::from_fn_with_state(mm.clone(), mw_ctx_resolve))
-		.layer(CookieManagerLayer::new())
-		.fallback_service(routes_static::serve_dir());
+	let routes_all = web::routes_all(mm, task_changes);
 
 	// region:    --- Start Server
 	let addr = SocketAddr::from(([127, 0, 0, 1], 8080));
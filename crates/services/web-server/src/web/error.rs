@@ -1,9 +1,12 @@
 use crate::web;
+use crate::web::routes_attachment::ATTACHMENT_SIZE_MAX;
+use axum::extract::multipart::MultipartError;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use derive_more::From;
 use lib_auth::{pwd, token};
 use lib_core::model;
+use lib_rpc::ClientError;
 use serde::Serialize;
 use serde_with::{serde_as, DisplayFromStr};
 use tracing::debug;
@@ -23,6 +26,21 @@ pub enum Error {
 		user_id: i64,
 	},
 
+	// -- Attachment
+	AttachmentFileMissing,
+	AttachmentContentTypeNotAllowed {
+		content_type: String,
+	},
+	AttachmentSizeOverMax {
+		max: usize,
+	},
+
+	// -- Websocket
+	WsSessionExpired,
+	WsReauthUserNotMatching {
+		user_id: i64,
+	},
+
 	// -- CtxExtError
 	#[from]
 	CtxExt(web::mw_auth::CtxExtError),
@@ -40,6 +58,8 @@ pub enum Error {
 	// -- External Modules
 	#[from]
 	SerdeJson(#[serde_as(as = "DisplayFromStr")] serde_json::Error),
+	#[from]
+	Multipart(#[serde_as(as = "DisplayFromStr")] MultipartError),
 }
 
 // region:    --- Axum IntoResponse
@@ -90,12 +110,74 @@ impl Error {
 			// -- Auth
 			CtxExt(_) => (StatusCode::FORBIDDEN, ClientError::NO_AUTH),
 
-			// -- Model
-			Model(model::Error::EntityNotFound { entity, id }) => (
+			// -- Websocket
+			WsSessionExpired => (StatusCode::UNAUTHORIZED, ClientError::NO_AUTH),
+			WsReauthUserNotMatching { .. } => {
+				(StatusCode::FORBIDDEN, ClientError::LOGIN_FAIL)
+			}
+
+			// -- Attachment
+			// Note: Over the body limit, the multipart stream fails before the size check.
+			Multipart(ex) if ex.status() == StatusCode::PAYLOAD_TOO_LARGE => (
+				StatusCode::PAYLOAD_TOO_LARGE,
+				ClientError::ATTACHMENT_SIZE_OVER_MAX {
+					max: ATTACHMENT_SIZE_MAX,
+				},
+			),
+			AttachmentFileMissing | Multipart(_) => {
+				(StatusCode::BAD_REQUEST, ClientError::ATTACHMENT_INVALID)
+			}
+			AttachmentContentTypeNotAllowed { .. } => (
+				StatusCode::UNSUPPORTED_MEDIA_TYPE,
+				ClientError::ATTACHMENT_CONTENT_TYPE_NOT_ALLOWED,
+			),
+			AttachmentSizeOverMax { max } => (
+				StatusCode::PAYLOAD_TOO_LARGE,
+				ClientError::ATTACHMENT_SIZE_OVER_MAX { max: *max },
+			),
+
+			// -- JSON-RPC
+			Rpc(lib_rpc::Error::RpcParseError) => {
+				(StatusCode::BAD_REQUEST, ClientError::PARSE_ERROR)
+			}
+			Rpc(lib_rpc::Error::RpcInvalidRequest { .. }) => {
+				(StatusCode::BAD_REQUEST, ClientError::INVALID_REQUEST)
+			}
+			Rpc(lib_rpc::Error::RpcMethodUnknown(_)) => {
+				(StatusCode::NOT_FOUND, ClientError::METHOD_NOT_FOUND)
+			}
+			Rpc(
+				lib_rpc::Error::RpcMissingParams { .. }
+				| lib_rpc::Error::RpcFailJsonParams { .. }
+				| lib_rpc::Error::RpcInvalidParams { .. },
+			) => (StatusCode::BAD_REQUEST, ClientError::INVALID_PARAMS),
+			Rpc(lib_rpc::Error::RpcRateLimited {
+				retry_after_sec, ..
+			}) => (
+				StatusCode::TOO_MANY_REQUESTS,
+				ClientError::RATE_LIMITED {
+					retry_after_sec: *retry_after_sec,
+				},
+			),
+			Rpc(lib_rpc::Error::ValidationFailed {
+				field,
+				rule,
+				message,
+			}) => (
 				StatusCode::BAD_REQUEST,
-				ClientError::ENTITY_NOT_FOUND { entity, id: *id },
+				ClientError::VALIDATION_FAILED {
+					field: field.to_string(),
+					rule: rule.to_string(),
+					message: message.to_string(),
+				},
 			),
 
+			// -- Model
+			Model(model_error) | Rpc(lib_rpc::Error::Model(model_error)) => {
+				let client_error = ClientError::from(model_error);
+				(model_client_status(&client_error), client_error)
+			}
+
 			// -- Fallback.
 			_ => (
 				StatusCode::INTERNAL_SERVER_ERROR,
@@ -105,14 +187,137 @@ impl Error {
 	}
 }
 
-#[derive(Debug, Serialize, strum_macros::AsRefStr)]
-#[serde(tag = "message", content = "detail")]
-#[allow(non_camel_case_types)]
-pub enum ClientError {
-	LOGIN_FAIL,
-	NO_AUTH,
-	ENTITY_NOT_FOUND { entity: &'static str, id: i64 },
+/// The http status code of the model error `ClientError`
+/// (see `From<&model::Error> for ClientError`).
+fn model_client_status(client_error: &ClientError) -> StatusCode {
+	use ClientError::*;
 
-	SERVICE_ERROR,
+	match client_error {
+		ADMIN_REQUIRED | PWD_NOT_MATCHING | ACCESS_DENIED { .. } => {
+			StatusCode::FORBIDDEN
+		}
+		TASK_NOT_VISIBLE { .. } => StatusCode::FORBIDDEN,
+		UNIQUE_VIOLATION { .. } | TASK_STATUS_TRANSITION_INVALID { .. } => {
+			StatusCode::CONFLICT
+		}
+		IDEMPOTENCY_KEY_IN_PROGRESS => StatusCode::CONFLICT,
+		IDEMPOTENCY_KEY_REUSED
+		| TASK_PARENT_CYCLE { .. }
+		| TASK_TREE_DEPTH_OVER_MAX { .. }
+		| TASK_RANK_SCOPE_MISMATCH { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+		SERVICE_ERROR => StatusCode::INTERNAL_SERVER_ERROR,
+		_ => StatusCode::BAD_REQUEST,
+	}
 }
+
 // endregion: --- Client Error
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use lib_core::model::task::TaskStatus;
+	use lib_utils::time;
+
+	#[test]
+	fn test_client_status_and_error_model_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_cases = [
+			(
+				Error::Model(model::Error::AccessDenied {
+					entity: "comment",
+					id: 1,
+				}),
+				StatusCode::FORBIDDEN,
+				ClientError::ACCESS_DENIED {
+					entity: "comment".to_string(),
+					id: 1,
+				},
+			),
+			(
+				Error::Model(model::Error::UserPwdNotMatching { user_id: 1 }),
+				StatusCode::FORBIDDEN,
+				ClientError::PWD_NOT_MATCHING,
+			),
+			(
+				Error::Model(model::Error::IdempotencyKeyInProgress {
+					key: "k".to_string(),
+				}),
+				StatusCode::CONFLICT,
+				ClientError::IDEMPOTENCY_KEY_IN_PROGRESS,
+			),
+			(
+				Error::Rpc(lib_rpc::Error::Model(
+					model::Error::TaskStatusTransitionInvalid {
+						id: 1,
+						from: TaskStatus::Done,
+						to: TaskStatus::Blocked,
+					},
+				)),
+				StatusCode::CONFLICT,
+				ClientError::TASK_STATUS_TRANSITION_INVALID {
+					id: 1,
+					from: TaskStatus::Done,
+					to: TaskStatus::Blocked,
+				},
+			),
+			(
+				Error::Rpc(lib_rpc::Error::Model(model::Error::ListLimitOverMax {
+					max: 1000,
+					actual: 2000,
+				})),
+				StatusCode::BAD_REQUEST,
+				ClientError::LIST_LIMIT_OVER_MAX { max: 1000 },
+			),
+			(
+				Error::Rpc(lib_rpc::Error::Model(model::Error::BulkFiltersEmpty)),
+				StatusCode::BAD_REQUEST,
+				ClientError::BULK_FILTERS_EMPTY,
+			),
+			(
+				Error::Rpc(lib_rpc::Error::Model(model::Error::TaskParentCycle {
+					id: 1,
+					parent_id: 2,
+				})),
+				StatusCode::UNPROCESSABLE_ENTITY,
+				ClientError::TASK_PARENT_CYCLE {
+					id: 1,
+					parent_id: 2,
+				},
+			),
+			(
+				Error::Rpc(lib_rpc::Error::Model(model::Error::Time(
+					time::Error::RecurrenceInvalid {
+						rule: "FREQ=NEVER".to_string(),
+						cause: "freq",
+					},
+				))),
+				StatusCode::BAD_REQUEST,
+				ClientError::RECURRENCE_INVALID {
+					rule: "FREQ=NEVER".to_string(),
+					cause: "freq".to_string(),
+				},
+			),
+			(
+				Error::Model(model::Error::SerdeJson(
+					serde_json::from_str::<i64>("not json").unwrap_err(),
+				)),
+				StatusCode::INTERNAL_SERVER_ERROR,
+				ClientError::SERVICE_ERROR,
+			),
+		];
+
+		// -- Exec & Check
+		for (error, status, client_error) in fx_cases {
+			assert_eq!(
+				error.client_status_and_error(),
+				(status, client_error),
+				"for {error:?}"
+			);
+		}
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -3,30 +3,70 @@
 mod error;
 pub mod mw_auth;
 pub mod mw_res_map;
+pub mod routes_attachment;
+pub mod routes_events;
 pub mod routes_login;
 pub mod routes_rpc;
 pub mod routes_static;
+pub mod routes_ws;
 
-pub use self::error::ClientError;
 pub use self::error::{Error, Result};
+use crate::events::TaskChange;
+use crate::web::mw_auth::{mw_ctx_require, mw_ctx_resolve};
+use crate::web::mw_res_map::mw_reponse_map;
+use crate::web::routes_events::EventsState;
+use crate::web::routes_rpc::RpcState;
+use axum::{middleware, Router};
 use lib_auth::token::generate_web_token;
-use tower_cookies::{Cookie, Cookies};
+use lib_core::model::ModelManager;
+pub use lib_rpc::ClientError;
+use tokio::sync::broadcast;
+use tower_cookies::{Cookie, CookieManagerLayer, Cookies};
 use uuid::Uuid;
 
 // endregion: --- Modules
 
 pub const AUTH_TOKEN: &str = "auth-token";
 
-fn set_token_cookie(cookies: &Cookies, user: &str, salt: Uuid) -> Result<()> {
-	let token = generate_web_token(user, salt)?;
+/// All the routes, with the auth and response map middlewares,
+/// and the static files as fallback.
+pub fn routes_all(
+	mm: ModelManager,
+	task_changes: broadcast::Sender<TaskChange>,
+) -> Router {
+	let rpc_state = RpcState::new(mm.clone());
+	let events_state = EventsState {
+		mm: mm.clone(),
+		task_changes,
+		rpc_router: rpc_state.rpc_router.clone(),
+	};
+	let routes_api = routes_rpc::routes(rpc_state)
+		.merge(routes_attachment::routes(mm.clone()))
+		.merge(routes_events::routes(events_state.clone()))
+		.merge(routes_ws::routes(events_state))
+		.route_layer(middleware::from_fn(mw_ctx_require));
 
-	let mut cookie = Cookie::new(AUTH_TOKEN, token.to_string());
+	Router::new()
+		.merge(routes_login::routes(mm.clone()))
+		.nest("/api", routes_api)
+		.layer(middleware::map_response(mw_reponse_map))
+		.layer(middleware::from_fn_with_state(mm, mw_ctx_resolve))
+		.layer(CookieManagerLayer::new())
+		.fallback_service(routes_static::serve_dir())
+}
+
+/// Sets the token cookie, and returns the token
+/// (e.g., for the bearer clients, see `mw_ctx_resolve`).
+fn set_token_cookie(cookies: &Cookies, user: &str, salt: Uuid) -> Result<String> {
+	let token = generate_web_token(user, salt)?.to_string();
+
+	let mut cookie = Cookie::new(AUTH_TOKEN, token.clone());
 	cookie.set_http_only(true);
 	cookie.set_path("/");
 
 	cookies.add(cookie);
 
-	Ok(())
+	Ok(token)
 }
 
 fn remove_token_cookie(cookies: &Cookies) -> Result<()> {
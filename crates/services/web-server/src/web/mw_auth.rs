@@ -2,8 +2,9 @@ use crate::web::{set_token_cookie, AUTH_TOKEN};
 use crate::web::{Error, Result};
 use async_trait::async_trait;
 use axum::extract::{FromRequestParts, State};
+use axum::http::header::AUTHORIZATION;
 use axum::http::request::Parts;
-use axum::http::Request;
+use axum::http::{HeaderMap, Request};
 use axum::middleware::Next;
 use axum::response::Response;
 use lib_auth::token::{validate_web_token, Token};
@@ -27,6 +28,8 @@ pub async fn mw_ctx_require<B>(
 	Ok(next.run(req).await)
 }
 
+/// Resolves the ctx from the `Authorization: Bearer <token>` header (e.g., for
+/// the service clients), or otherwise, from the token cookie (which is renewed).
 pub async fn mw_ctx_resolve<B>(
 	mm: State<ModelManager>,
 	cookies: Cookies,
@@ -35,13 +38,10 @@ pub async fn mw_ctx_resolve<B>(
 ) -> Result<Response> {
 	debug!("{:<12} - mw_ctx_resolve", "MIDDLEWARE");
 
-	let ctx_ext_result = _ctx_resolve(mm, &cookies).await;
-
-	if ctx_ext_result.is_err()
-		&& !matches!(ctx_ext_result, Err(CtxExtError::TokenNotInCookie))
-	{
-		cookies.remove(Cookie::named(AUTH_TOKEN))
-	}
+	let ctx_ext_result = match bearer_token(req.headers()) {
+		Some(token) => _ctx_resolve(mm, token, None).await,
+		None => _ctx_resolve_cookie(mm, &cookies).await,
+	};
 
 	// Store the ctx_ext_result in the request extension
 	// (for Ctx extractor).
@@ -50,13 +50,29 @@ pub async fn mw_ctx_resolve<B>(
 	Ok(next.run(req).await)
 }
 
-async fn _ctx_resolve(mm: State<ModelManager>, cookies: &Cookies) -> CtxExtResult {
+async fn _ctx_resolve_cookie(
+	mm: State<ModelManager>,
+	cookies: &Cookies,
+) -> CtxExtResult {
 	// -- Get Token String
-	let token = cookies
-		.get(AUTH_TOKEN)
-		.map(|c| c.value().to_string())
-		.ok_or(CtxExtError::TokenNotInCookie)?;
+	let Some(token) = cookies.get(AUTH_TOKEN).map(|c| c.value().to_string()) else {
+		return Err(CtxExtError::TokenNotInCookie);
+	};
+
+	let ctx_ext_result = _ctx_resolve(mm, &token, Some(cookies)).await;
+	if ctx_ext_result.is_err() {
+		cookies.remove(Cookie::named(AUTH_TOKEN))
+	}
 
+	ctx_ext_result
+}
+
+/// Resolves the ctx of the token, and renews the token cookie (when given).
+async fn _ctx_resolve(
+	mm: State<ModelManager>,
+	token: &str,
+	cookies: Option<&Cookies>,
+) -> CtxExtResult {
 	// -- Parse Token
 	let token: Token = token.parse().map_err(|_| CtxExtError::TokenWrongFormat)?;
 
@@ -72,8 +88,10 @@ async fn _ctx_resolve(mm: State<ModelManager>, cookies: &Cookies) -> CtxExtResul
 		.map_err(|_| CtxExtError::FailValidate)?;
 
 	// -- Update Token
-	set_token_cookie(cookies, &user.username, user.token_salt)
-		.map_err(|_| CtxExtError::CannotSetTokenCookie)?;
+	if let Some(cookies) = cookies {
+		set_token_cookie(cookies, &user.username, user.token_salt)
+			.map_err(|_| CtxExtError::CannotSetTokenCookie)?;
+	}
 
 	// -- Create CtxExtResult
 	Ctx::new(user.id)
@@ -81,6 +99,13 @@ async fn _ctx_resolve(mm: State<ModelManager>, cookies: &Cookies) -> CtxExtResul
 		.map_err(|ex| CtxExtError::CtxCreateFail(ex.to_string()))
 }
 
+pub(super) fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+	headers
+		.get(AUTHORIZATION)
+		.and_then(|value| value.to_str().ok())
+		.and_then(|value| value.strip_prefix("Bearer "))
+}
+
 // region:    --- Ctx Extractor
 #[derive(Debug, Clone)]
 pub struct CtxW(pub Ctx);
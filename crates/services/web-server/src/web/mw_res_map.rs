@@ -1,11 +1,12 @@
 use crate::log::log_request;
 use crate::web;
 use crate::web::mw_auth::CtxW;
-use crate::web::routes_rpc::RpcInfo;
+use crate::web::ClientError;
 use axum::http::{Method, Uri};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
-use serde_json::{json, to_value};
+use lib_rpc::JSONRPC_VERSION;
+use serde_json::{json, to_value, Value};
 use tracing::debug;
 use uuid::Uuid;
 
@@ -20,8 +21,6 @@ pub async fn mw_reponse_map(
 	debug!("{:<12} - mw_reponse_map", "RES_MAPPER");
 	let uuid = Uuid::new_v4();
 
-	let rpc_info = res.extensions().get::<RpcInfo>();
-
 	// -- Get the eventual response error.
 	let web_error = res.extensions().get::<web::Error>();
 	let client_status_error = web_error.map(|se| se.client_status_and_error());
@@ -31,20 +30,8 @@ pub async fn mw_reponse_map(
 		client_status_error
 			.as_ref()
 			.map(|(status_code, client_error)| {
-				let client_error = to_value(client_error).ok();
-				let message = client_error.as_ref().and_then(|v| v.get("message"));
-				let detail = client_error.as_ref().and_then(|v| v.get("detail"));
-
-				let client_error_body = json!({
-					"id": rpc_info.as_ref().map(|rpc| rpc.id.clone()),
-					"error": {
-						"message": message, // Variant name
-						"data": {
-							"req_uuid": uuid.to_string(),
-							"detail": detail
-						},
-					}
-				});
+				let client_error_body =
+					client_error_body(Value::Null, client_error, uuid);
 
 				debug!("CLIENT ERROR BODY:\n{client_error_body}");
 
@@ -55,18 +42,34 @@ pub async fn mw_reponse_map(
 	// -- Build and log the server log line.
 	let client_error = client_status_error.unzip().1;
 	// TODO: Need to hander if log_request fail (but should not fail request)
-	let _ = log_request(
-		uuid,
-		req_method,
-		uri,
-		rpc_info,
-		ctx,
-		web_error,
-		client_error,
-	)
-	.await;
+	let _ = log_request(uuid, req_method, uri, ctx, web_error, client_error).await;
 
 	debug!("\n");
 
 	error_response.unwrap_or(res)
 }
+
+/// The JSON-RPC 2.0 error response of a client error (also for the non-rpc
+/// routes, e.g., login, with a `null` id).
+pub fn client_error_body(
+	rpc_id: Value,
+	client_error: &ClientError,
+	req_uuid: Uuid,
+) -> Value {
+	let client_error_value = to_value(client_error).ok();
+	let message = client_error_value.as_ref().and_then(|v| v.get("message"));
+	let detail = client_error_value.as_ref().and_then(|v| v.get("detail"));
+
+	json!({
+		"jsonrpc": JSONRPC_VERSION,
+		"id": rpc_id,
+		"error": {
+			"code": client_error.code(),
+			"message": message, // Variant name
+			"data": {
+				"req_uuid": req_uuid.to_string(),
+				"detail": detail
+			},
+		}
+	})
+}
@@ -0,0 +1,312 @@
+use crate::web::mw_auth::CtxW;
+use crate::web::{Error, Result};
+use axum::extract::{DefaultBodyLimit, Multipart, Path, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use lib_core::model::attachment::{Attachment, AttachmentBmc, AttachmentForCreate};
+use lib_core::model::ModelManager;
+use tracing::debug;
+
+/// The max size of an attachment content (10 MB).
+pub const ATTACHMENT_SIZE_MAX: usize = 10 * 1024 * 1024;
+
+/// The allowed attachment content types.
+pub const ATTACHMENT_CONTENT_TYPES: &[&str] = &[
+	"application/pdf",
+	"application/zip",
+	"image/gif",
+	"image/jpeg",
+	"image/png",
+	"image/webp",
+	"text/csv",
+	"text/markdown",
+	"text/plain",
+];
+
+/// The multipart field name of the uploaded file.
+const UPLOAD_FIELD_NAME: &str = "file";
+
+/// The body limit headroom for the multipart boundaries and headers.
+const MULTIPART_OVERHEAD_MAX: usize = 64 * 1024;
+
+pub fn routes(mm: ModelManager) -> Router {
+	Router::new()
+		.route(
+			"/tasks/:task_id/attachments",
+			post(upload_handler).layer(DefaultBodyLimit::max(
+				ATTACHMENT_SIZE_MAX + MULTIPART_OVERHEAD_MAX,
+			)),
+		)
+		.route("/attachments/:id", get(download_handler))
+		.with_state(mm)
+}
+
+async fn upload_handler(
+	State(mm): State<ModelManager>,
+	ctx: CtxW,
+	Path(task_id): Path<i64>,
+	mut multipart: Multipart,
+) -> Result<Json<Attachment>> {
+	debug!("{:<12} - upload_handler - task_id: {task_id}", "HANDLER");
+
+	let ctx = ctx.0;
+
+	// -- Find the file field.
+	let mut field = loop {
+		match multipart.next_field().await? {
+			Some(field) if field.name() == Some(UPLOAD_FIELD_NAME) => break field,
+			Some(_) => continue,
+			None => return Err(Error::AttachmentFileMissing),
+		}
+	};
+
+	// -- Check the content type.
+	let content_type = field.content_type().unwrap_or_default().to_string();
+	if !ATTACHMENT_CONTENT_TYPES.contains(&content_type.as_str()) {
+		return Err(Error::AttachmentContentTypeNotAllowed { content_type });
+	}
+	let file_name = field.file_name().unwrap_or("unnamed").to_string();
+
+	// -- Read the content, up to the max size.
+	let mut content = Vec::new();
+	while let Some(chunk) = field.chunk().await? {
+		if content.len() + chunk.len() > ATTACHMENT_SIZE_MAX {
+			return Err(Error::AttachmentSizeOverMax {
+				max: ATTACHMENT_SIZE_MAX,
+			});
+		}
+		content.extend_from_slice(&chunk);
+	}
+
+	// -- Create the attachment.
+	let attachment_c = AttachmentForCreate {
+		task_id,
+		file_name,
+		content_type,
+		content,
+	};
+	let id = AttachmentBmc::create(&ctx, &mm, attachment_c).await?;
+	let attachment = AttachmentBmc::get(&ctx, &mm, id).await?;
+
+	Ok(Json(attachment))
+}
+
+async fn download_handler(
+	State(mm): State<ModelManager>,
+	ctx: CtxW,
+	Path(id): Path<i64>,
+) -> Result<Response> {
+	debug!("{:<12} - download_handler - id: {id}", "HANDLER");
+
+	let ctx = ctx.0;
+
+	let (attachment, content) = AttachmentBmc::get_content(&ctx, &mm, id).await?;
+
+	// Note: Quotes and control chars would break the header value.
+	let file_name: String = attachment
+		.file_name
+		.chars()
+		.filter(|c| *c != '"' && *c != '\\' && !c.is_control())
+		.collect();
+	let headers = [
+		(header::CONTENT_TYPE, attachment.content_type),
+		(
+			header::CONTENT_DISPOSITION,
+			format!("attachment; filename=\"{file_name}\""),
+		),
+		(header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+	];
+
+	Ok((headers, content).into_response())
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::web::routes_login::tests::serve_routes_all;
+	use anyhow::Result;
+	use axum::http::StatusCode;
+	use lib_core::_dev_utils;
+	use lib_core::ctx::Ctx;
+	use lib_core::model::task::{TaskBmc, TaskForCreate};
+	use lib_rpc_client::RpcClient;
+	use serde_json::Value;
+	use serial_test::serial;
+
+	const MULTIPART_BOUNDARY: &str = "test-attachment-boundary";
+
+	#[serial]
+	#[tokio::test]
+	async fn test_upload_download_ok_headers() -> Result<()> {
+		// -- Setup & Fixtures
+		let (fx, mm) = Fixture::new("test_upload_download_ok_headers").await?;
+		let fx_content = b"hello attachment".to_vec();
+
+		// -- Exec
+		let res = fx
+			.upload("he\\llo.txt", "text/plain", fx_content.clone())
+			.await?;
+		assert_eq!(res.status(), StatusCode::OK);
+		let attachment: Value = serde_json::from_slice(&res.bytes().await?)?;
+		let res = fx.download(&attachment["id"]).await?;
+
+		// -- Check
+		assert_eq!(res.status(), StatusCode::OK);
+		let headers = res.headers();
+		assert_eq!(headers[header::CONTENT_TYPE], "text/plain");
+		assert_eq!(
+			headers[header::CONTENT_DISPOSITION],
+			"attachment; filename=\"hello.txt\""
+		);
+		assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+		assert_eq!(res.bytes().await?.to_vec(), fx_content);
+
+		// -- Clean
+		fx.clean(&mm).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_upload_err_content_type_not_allowed() -> Result<()> {
+		// -- Setup & Fixtures
+		let (fx, mm) =
+			Fixture::new("test_upload_err_content_type_not_allowed").await?;
+
+		// -- Exec
+		let res = fx
+			.upload("page.html", "text/html", b"<html></html>".to_vec())
+			.await?;
+
+		// -- Check
+		assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+		let body: Value = serde_json::from_slice(&res.bytes().await?)?;
+		assert_eq!(
+			body["error"]["message"],
+			"ATTACHMENT_CONTENT_TYPE_NOT_ALLOWED"
+		);
+
+		// -- Clean
+		fx.clean(&mm).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_upload_err_size_over_max() -> Result<()> {
+		// -- Setup & Fixtures
+		let (fx, mm) = Fixture::new("test_upload_err_size_over_max").await?;
+		// Note: Over the content max (checked by the handler),
+		//       and over the body limit (the multipart stream fails).
+		let fx_sizes = [
+			ATTACHMENT_SIZE_MAX + 1,
+			ATTACHMENT_SIZE_MAX + MULTIPART_OVERHEAD_MAX + 1,
+		];
+
+		for fx_size in fx_sizes {
+			// -- Exec
+			let res = fx
+				.upload("big.txt", "text/plain", vec![b'a'; fx_size])
+				.await?;
+
+			// -- Check
+			assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+			let body: Value = serde_json::from_slice(&res.bytes().await?)?;
+			assert_eq!(body["error"]["message"], "ATTACHMENT_SIZE_OVER_MAX");
+		}
+
+		// -- Clean
+		fx.clean(&mm).await?;
+
+		Ok(())
+	}
+
+	/// The server, with the `demo1` bearer token and a task of the user.
+	struct Fixture {
+		base_url: String,
+		token: String,
+		task_id: i64,
+	}
+
+	impl Fixture {
+		async fn new(title: &str) -> Result<(Self, ModelManager)> {
+			// Note: Its own pool, as the pool connections are bound to the test runtime.
+			_dev_utils::init_test().await;
+			let mm = ModelManager::new().await?;
+			let base_url = serve_routes_all(mm.clone())?;
+			let token = RpcClient::new(&base_url)?.login("demo1", "welcome").await?;
+			let task = RpcClient::new(&base_url)?
+				.with_bearer_token(token.clone())
+				.create_task(TaskForCreate {
+					title: title.to_string(),
+					..Default::default()
+				})
+				.await?;
+
+			let fx = Fixture {
+				base_url,
+				token,
+				task_id: task.id,
+			};
+
+			Ok((fx, mm))
+		}
+
+		/// Uploads the file as the multipart `file` field.
+		async fn upload(
+			&self,
+			file_name: &str,
+			content_type: &str,
+			content: Vec<u8>,
+		) -> Result<reqwest::Response> {
+			let mut body = format!(
+				"--{MULTIPART_BOUNDARY}\r\n\
+				 Content-Disposition: form-data; name=\"{UPLOAD_FIELD_NAME}\"; \
+				 filename=\"{file_name}\"\r\n\
+				 Content-Type: {content_type}\r\n\r\n"
+			)
+			.into_bytes();
+			body.extend(content);
+			body.extend(format!("\r\n--{MULTIPART_BOUNDARY}--\r\n").into_bytes());
+
+			let url =
+				format!("{}/api/tasks/{}/attachments", self.base_url, self.task_id);
+			let res = reqwest::Client::new()
+				.post(url)
+				.bearer_auth(&self.token)
+				.header(
+					header::CONTENT_TYPE,
+					format!("multipart/form-data; boundary={MULTIPART_BOUNDARY}"),
+				)
+				.body(body)
+				.send()
+				.await?;
+
+			Ok(res)
+		}
+
+		async fn download(&self, id: &Value) -> Result<reqwest::Response> {
+			let url = format!("{}/api/attachments/{id}", self.base_url);
+			let res = reqwest::Client::new()
+				.get(url)
+				.bearer_auth(&self.token)
+				.send()
+				.await?;
+
+			Ok(res)
+		}
+
+		/// Deletes the task (with its attachments).
+		async fn clean(&self, mm: &ModelManager) -> Result<()> {
+			TaskBmc::delete(&Ctx::root_ctx(), mm, self.task_id).await?;
+
+			Ok(())
+		}
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,173 @@
+use crate::events::TaskChange;
+use crate::web::mw_auth::CtxW;
+use axum::extract::{FromRef, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::routing::get;
+use axum::Router;
+use futures::stream::{self, Stream};
+use lib_core::ctx::Ctx;
+use lib_core::model::task::TaskBmc;
+use lib_core::model::ModelManager;
+use lib_rpc::RpcRouter;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error};
+
+/// The SSE event name of the task domain events (with the `DomainEvent` json as data).
+const SSE_TASK_EVENT: &str = "task";
+/// The SSE event name sent when some changes were lost (the client must refetch).
+const SSE_RESYNC_EVENT: &str = "resync";
+
+/// The state of the live routes (`/api/events` and `/api/ws`).
+#[derive(Clone, FromRef)]
+pub struct EventsState {
+	pub mm: ModelManager,
+	pub task_changes: broadcast::Sender<TaskChange>,
+	/// The rpc methods of the `/api/ws` request frames.
+	pub rpc_router: Arc<RpcRouter>,
+}
+
+pub fn routes(state: EventsState) -> Router {
+	Router::new()
+		.route("/events", get(events_handler))
+		.with_state(state)
+}
+
+/// Streams the task changes visible to the ctx user, as Server-Sent Events.
+async fn events_handler(
+	State(mm): State<ModelManager>,
+	State(task_changes): State<broadcast::Sender<TaskChange>>,
+	ctx: CtxW,
+) -> Sse<impl Stream<Item = Result<Event, serde_json::Error>>> {
+	debug!("{:<12} - events_handler", "HANDLER");
+
+	let ctx = ctx.0;
+	let rx = task_changes.subscribe();
+
+	let events = stream::unfold((ctx, mm, rx), |(ctx, mm, mut rx)| async move {
+		let event = next_sse_event(&ctx, &mm, &mut rx).await?;
+		Some((event, (ctx, mm, rx)))
+	});
+
+	Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+/// Returns the next task change visible to the ctx user
+/// (`None` when the task changes are closed).
+async fn next_sse_event(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	rx: &mut broadcast::Receiver<TaskChange>,
+) -> Option<Result<Event, serde_json::Error>> {
+	loop {
+		let event = match rx.recv().await {
+			Ok(TaskChange::Event(event)) => event,
+			Ok(TaskChange::Resync) | Err(RecvError::Lagged(_)) => {
+				return Some(Ok(Event::default().event(SSE_RESYNC_EVENT).data("")));
+			}
+			Err(RecvError::Closed) => return None,
+		};
+
+		match TaskBmc::is_event_visible(ctx, mm, &event).await {
+			Ok(true) => {
+				return Some(
+					Event::default().event(SSE_TASK_EVENT).json_data(event),
+				);
+			}
+			Ok(false) => continue,
+			Err(ex) => {
+				error!("{:<12} - events_handler - {ex:?}", "HANDLER");
+				return Some(Ok(Event::default().event(SSE_RESYNC_EVENT).data("")));
+			}
+		}
+	}
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::{Context, Result};
+	use axum::body::{BoxBody, HttpBody};
+	use axum::response::IntoResponse;
+	use lib_core::_dev_utils;
+	use lib_core::model::outbox::DomainEvent;
+	use lib_core::model::user::{User, UserBmc};
+	use serde_json::Value;
+	use serial_test::serial;
+	use std::time::Duration;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_events_handler_ok_visible_only() -> Result<()> {
+		// -- Setup & Fixtures
+		_dev_utils::init_test().await;
+		let mm = ModelManager::new().await?;
+		let user: User = UserBmc::first_by_username(&Ctx::root_ctx(), &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+		let ctx = Ctx::new(user.id)?;
+		let other_ctx = Ctx::new(9040)?;
+		let fx_task = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			&["test_events_handler_ok_visible_only-task 01"],
+		)
+		.await?
+		.remove(0);
+		let fx_other_task = _dev_utils::seed_tasks(
+			&other_ctx,
+			&mm,
+			&["test_events_handler_ok_visible_only-task 02"],
+		)
+		.await?
+		.remove(0);
+		let (task_changes, _) = broadcast::channel(16);
+		let sse = events_handler(
+			State(mm.clone()),
+			State(task_changes.clone()),
+			CtxW(ctx.clone()),
+		)
+		.await;
+		let mut body = sse.into_response().into_body();
+
+		// -- Exec
+		for task_id in [fx_other_task.id, fx_task.id] {
+			task_changes.send(TaskChange::Event(DomainEvent::TaskUpdated {
+				task_id,
+				changed_fields: vec!["title".to_string()],
+			}))?;
+		}
+		task_changes.send(TaskChange::Resync)?;
+
+		// -- Check (the event of the other task is skipped)
+		let chunk = next_chunk(&mut body).await?;
+		assert!(chunk.starts_with("event:task\n"), "chunk: {chunk}");
+		let data = chunk
+			.lines()
+			.find_map(|line| line.strip_prefix("data:"))
+			.context("Should have data")?;
+		let data: Value = serde_json::from_str(data)?;
+		assert_eq!(data["data"]["task_id"], fx_task.id);
+
+		let chunk = next_chunk(&mut body).await?;
+		assert!(chunk.starts_with("event:resync\n"), "chunk: {chunk}");
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+		TaskBmc::delete(&other_ctx, &mm, fx_other_task.id).await?;
+
+		Ok(())
+	}
+
+	/// Returns the next chunk of the SSE body (one event per chunk).
+	async fn next_chunk(body: &mut BoxBody) -> Result<String> {
+		let chunk = tokio::time::timeout(Duration::from_secs(5), body.data())
+			.await?
+			.context("Should have a chunk")??;
+
+		Ok(String::from_utf8(chunk.to_vec())?)
+	}
+}
+// endregion: --- Tests
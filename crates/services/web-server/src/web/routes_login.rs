@@ -30,45 +30,61 @@ async fn api_login_handler(
 		username,
 		pwd: pwd_clear,
 	} = payload;
+
+	let user = validate_login(&mm, &username, pwd_clear).await?;
+
+	// -- Set web token.
+	let token = web::set_token_cookie(&cookies, &user.username, user.token_salt)?;
+
+	// Create the success body.
+	// Note: The token is also for the clients without cookies
+	//       (as `Authorization: Bearer <token>`).
+	let body = Json(json!({
+		"result": {
+			"success": true,
+			"token": token
+		}
+	}));
+
+	Ok(body)
+}
+
+/// Returns the user when the password matches (e.g., also for the
+/// websocket reauthentication).
+pub(super) async fn validate_login(
+	mm: &ModelManager,
+	username: &str,
+	pwd_clear: String,
+) -> Result<UserForLogin> {
 	let root_ctx = Ctx::root_ctx();
 
 	// -- Get the user.
-	let user: UserForLogin = UserBmc::first_by_username(&root_ctx, &mm, &username)
+	let user: UserForLogin = UserBmc::first_by_username(&root_ctx, mm, username)
 		.await?
 		.ok_or(Error::LoginFailUsernameNotFound)?;
 	let user_id = user.id;
 
 	// -- Validate the password.
-	let Some(pwd) = user.pwd else {
+	let Some(pwd) = &user.pwd else {
 		return Err(Error::LoginFailUserHasNoPwd { user_id });
 	};
 
 	pwd::validate_pwd(
 		&ContentToHash {
 			salt: user.pwd_salt,
-			content: pwd_clear.clone(),
+			content: pwd_clear,
 		},
-		&pwd,
+		pwd,
 	)
 	.map_err(|_| Error::LoginFailPwdNotMatching { user_id })?;
 
-	// -- Set web token.
-	web::set_token_cookie(&cookies, &user.username, user.token_salt)?;
-
-	// Create the success body.
-	let body = Json(json!({
-		"result": {
-			"success": true
-		}
-	}));
-
-	Ok(body)
+	Ok(user)
 }
 
 #[derive(Debug, Deserialize)]
-struct LoginPayload {
-	username: String,
-	pwd: String,
+pub(super) struct LoginPayload {
+	pub(super) username: String,
+	pub(super) pwd: String,
 }
 // endregion: --- Login
 
@@ -99,3 +115,64 @@ struct LogoffPayload {
 	logoff: bool,
 }
 // endregion: --- Logoff
+
+// region:    --- Tests
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use anyhow::Result;
+	use lib_core::_dev_utils;
+	use lib_rpc::ClientError;
+	use lib_rpc_client::RpcClient;
+	use serial_test::serial;
+	use std::net::TcpListener;
+	use tokio::sync::broadcast;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_login_ok_bearer_token() -> Result<()> {
+		// -- Setup & Fixtures
+		// Note: Its own pool, as the pool connections are bound to the test runtime.
+		_dev_utils::init_test().await;
+		let mm = ModelManager::new().await?;
+		let base_url = serve_routes_all(mm)?;
+
+		// -- Exec
+		let token = RpcClient::new(&base_url)?.login("demo1", "welcome").await?;
+		let client = RpcClient::new(&base_url)?.with_bearer_token(token);
+		let res_bearer = client.list_tasks(None, None).await;
+		let res_no_auth = RpcClient::new(&base_url)?.list_tasks(None, None).await;
+		let res_wrong_token = RpcClient::new(&base_url)?
+			.with_bearer_token("wrong-token")
+			.list_tasks(None, None)
+			.await;
+
+		// -- Check
+		assert!(
+			res_bearer.is_ok(),
+			"Bearer call should be ok: {res_bearer:?}"
+		);
+		for res in [res_no_auth, res_wrong_token] {
+			let client_error = res.err().and_then(|e| e.client_error().cloned());
+			assert!(
+				matches!(client_error, Some(ClientError::NO_AUTH)),
+				"Should be NO_AUTH, but was: {client_error:?}"
+			);
+		}
+
+		Ok(())
+	}
+
+	/// Serves the `routes_all` on a free local port, and returns its base url.
+	pub(crate) fn serve_routes_all(mm: ModelManager) -> Result<String> {
+		let (task_changes, _) = broadcast::channel(1);
+		let listener = TcpListener::bind("127.0.0.1:0")?;
+		let base_url = format!("http://{}", listener.local_addr()?);
+		let server = axum::Server::from_tcp(listener)?
+			.serve(web::routes_all(mm, task_changes).into_make_service());
+		tokio::spawn(server);
+
+		Ok(base_url)
+	}
+}
+// endregion: --- Tests
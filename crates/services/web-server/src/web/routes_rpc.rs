@@ -1,19 +1,73 @@
-use crate::web::mw_auth::CtxW;
-use crate::web::Result;
-use axum::extract::State;
+use crate::log::{log_rpc_call, log_rpc_metric};
+use crate::web;
+use crate::web::mw_auth::{bearer_token, CtxW};
+use crate::web::mw_res_map::client_error_body;
+use crate::web::ClientError;
+use axum::body::Bytes;
+use axum::extract::{FromRef, State};
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::{IntoResponse, Response};
-use axum::routing::post;
+use axum::routing::{get, post};
 use axum::{Json, Router};
 use lib_core::ctx::Ctx;
+use lib_core::model::user::{UserBmc, UserForAuth};
 use lib_core::model::ModelManager;
-use lib_rpc::{exec_rpc, RpcRequest};
+use lib_rpc::{
+	parse_rpc_body, MetricsMiddleware, PermissionMiddleware, RateLimitMiddleware,
+	RpcBody, RpcRequest, RpcResources, RpcRouter, SlowCallMiddleware,
+	JSONRPC_VERSION,
+};
 use serde_json::{json, Value};
-use tracing::debug;
+use std::sync::Arc;
+use std::time::Duration;
+use tower_cookies::Cookies;
+use tracing::{debug, error};
+use uuid::Uuid;
 
-pub fn routes(mm: ModelManager) -> Router {
+/// The default threshold of the slow rpc calls (see `SlowCallMiddleware`).
+const RPC_SLOW_CALL_THRESHOLD: Duration = Duration::from_secs(1);
+
+#[derive(Clone, FromRef)]
+pub struct RpcState {
+	pub mm: ModelManager,
+	pub rpc_router: Arc<RpcRouter>,
+	/// The OpenRPC document of the `rpc_router` methods.
+	pub openrpc_doc: Arc<Value>,
+}
+
+impl RpcState {
+	/// Builds the router of all the rpc methods, with `rpc.discover`,
+	/// and the middlewares (the metrics and slow calls include the rejected calls).
+	pub fn new(mm: ModelManager) -> Self {
+		let rpc_router = lib_rpc::all_rpc_router()
+			.layer(MetricsMiddleware::new(log_rpc_metric))
+			.layer(SlowCallMiddleware::new(RPC_SLOW_CALL_THRESHOLD))
+			.layer(PermissionMiddleware)
+			.layer(RateLimitMiddleware::default());
+		let openrpc_doc =
+			Arc::new(rpc_router.openrpc_document(&ClientError::openrpc_errors()));
+		let rpc_router = Arc::new(rpc_router.add_discover(openrpc_doc.clone()));
+
+		RpcState {
+			mm,
+			rpc_router,
+			openrpc_doc,
+		}
+	}
+}
+
+pub fn routes(state: RpcState) -> Router {
 	Router::new()
 		.route("/rpc", post(rpc_handler))
-		.with_state(mm)
+		.route("/openrpc.json", get(openrpc_handler))
+		.with_state(state)
+}
+
+/// Serves the OpenRPC document (as the `rpc.discover` method).
+async fn openrpc_handler(State(openrpc_doc): State<Arc<Value>>) -> Json<Value> {
+	debug!("{:<12} - openrpc_handler", "HANDLER");
+
+	Json(openrpc_doc.as_ref().clone())
 }
 
 /// RPC basic information containing the rpc request
@@ -24,41 +78,195 @@ pub struct RpcInfo {
 	pub method: String,
 }
 
+/// The header of the call idempotency key (as the request `idempotency_key`).
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// The methods which rotate the user token salt (so, invalidate its tokens).
+const TOKEN_SALT_ROTATING_METHODS: &[&str] = &["change_password"];
+
+/// The JSON-RPC 2.0 handler, with a `200` and the response (or the batch
+/// responses), or a `204` when there is no response (i.e., only notifications).
+///
+/// Note: The rpc errors are in the responses `error` object, and not http errors.
+///
+/// Note: When a call rotates the token salt (e.g., `change_password`), the token
+///       cookie is re-issued, but the bearer clients must get a new token.
 async fn rpc_handler(
 	State(mm): State<ModelManager>,
+	State(rpc_router): State<Arc<RpcRouter>>,
 	ctx: CtxW,
-	Json(rpc_req): Json<RpcRequest>,
+	cookies: Cookies,
+	headers: HeaderMap,
+	body: Bytes,
 ) -> Response {
-	let ctx = ctx.0;
-	// -- Create the RPC Info to be set to the response.extensions.
+	let resources = RpcResources {
+		ctx: ctx.0,
+		mm: mm.clone(),
+	};
+	let ctx = resources.ctx.clone();
+
+	let rpc_body = parse_rpc_body(&body)
+		.and_then(|rpc_body| with_idempotency_key_header(rpc_body, &headers));
+	let res = match rpc_body {
+		Ok(rpc_body) => {
+			let rotates_token_salt = has_token_salt_rotating_call(&rpc_body);
+			let res = exec_rpc_body(&rpc_router, resources, rpc_body).await;
+			if rotates_token_salt && bearer_token(&headers).is_none() {
+				renew_token_cookie(&mm, &ctx, &cookies).await;
+			}
+			res
+		}
+		Err(ex) => Some(rpc_error_response(Value::Null, None, &ctx, ex.into())),
+	};
+
+	match res {
+		Some(res) => Json(res).into_response(),
+		None => StatusCode::NO_CONTENT.into_response(),
+	}
+}
+
+/// Returns true when a call of the body is a token salt rotating method.
+fn has_token_salt_rotating_call(rpc_body: &RpcBody) -> bool {
+	let calls = match rpc_body {
+		RpcBody::Single(call) => std::slice::from_ref(call),
+		RpcBody::Batch(calls) => calls.as_slice(),
+	};
+
+	calls.iter().any(|call| {
+		call.get("method")
+			.and_then(Value::as_str)
+			.is_some_and(|method| TOKEN_SALT_ROTATING_METHODS.contains(&method))
+	})
+}
+
+/// Re-issues the token cookie with the current user token salt
+/// (unchanged when the rotating call failed).
+///
+/// Note: The cookie renewed by `mw_ctx_resolve` (before the call) is replaced.
+async fn renew_token_cookie(mm: &ModelManager, ctx: &Ctx, cookies: &Cookies) {
+	let res = match UserBmc::get::<UserForAuth>(ctx, mm, ctx.user_id()).await {
+		Ok(user) => web::set_token_cookie(cookies, &user.username, user.token_salt)
+			.map(|_| ()),
+		Err(ex) => Err(ex.into()),
+	};
+
+	if let Err(ex) = res {
+		error!("{:<12} - renew_token_cookie - {ex:?}", "HANDLER");
+	}
+}
+
+/// Sets the `Idempotency-Key` header as the call `idempotency_key`
+/// (unless the call has one), which is only for a single call.
+fn with_idempotency_key_header(
+	rpc_body: RpcBody,
+	headers: &HeaderMap,
+) -> lib_rpc::Result<RpcBody> {
+	let Some(key) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+		return Ok(rpc_body);
+	};
+	let key = key
+		.to_str()
+		.map_err(|_| lib_rpc::Error::RpcInvalidRequest {
+			cause: "idempotency key header not ascii",
+		})?;
+
+	match rpc_body {
+		RpcBody::Single(Value::Object(mut call)) => {
+			call.entry("idempotency_key")
+				.or_insert_with(|| Value::String(key.to_string()));
+			Ok(RpcBody::Single(Value::Object(call)))
+		}
+		RpcBody::Single(call) => Ok(RpcBody::Single(call)),
+		RpcBody::Batch(_) => Err(lib_rpc::Error::RpcInvalidRequest {
+			cause: "idempotency key header with a batch",
+		}),
+	}
+}
+
+/// Executes the calls (in order for a batch), and returns the response,
+/// or the batch responses array (`None` when only notifications).
+pub(super) async fn exec_rpc_body(
+	rpc_router: &RpcRouter,
+	resources: RpcResources,
+	rpc_body: RpcBody,
+) -> Option<Value> {
+	match rpc_body {
+		RpcBody::Single(call) => exec_rpc_call(rpc_router, resources, call).await,
+		RpcBody::Batch(calls) => {
+			let mut responses = Vec::new();
+			for call in calls {
+				let resources = resources.clone();
+				if let Some(res) = exec_rpc_call(rpc_router, resources, call).await {
+					responses.push(res);
+				}
+			}
+			(!responses.is_empty()).then_some(Value::Array(responses))
+		}
+	}
+}
+
+/// Executes a call, and returns its response (`None` for a notification).
+async fn exec_rpc_call(
+	rpc_router: &RpcRouter,
+	resources: RpcResources,
+	call: Value,
+) -> Option<Value> {
+	let ctx = resources.ctx.clone();
+
+	// Note: The invalid requests are answered, even without id.
+	let rpc_req = match RpcRequest::from_value(call) {
+		Ok(rpc_req) => rpc_req,
+		Err((id, ex)) => return Some(rpc_error_response(id, None, &ctx, ex.into())),
+	};
+
 	let rpc_info = RpcInfo {
 		id: rpc_req.id.clone(),
 		method: rpc_req.method.clone(),
 	};
+	let notification = rpc_req.is_notification();
 
-	// -- Exec & Store RpcInfo in response.
-	let mut res = _rpc_handler(ctx, mm, rpc_req).await.into_response();
-	res.extensions_mut().insert(rpc_info);
+	debug!(
+		"{:<12} - exec_rpc_call - method: {}",
+		"HANDLER", rpc_info.method
+	);
 
-	res
+	let res = match rpc_router.call(resources, rpc_req).await {
+		Ok(result) => {
+			log_rpc_call(Uuid::new_v4(), Some(&rpc_info), &ctx, None, None);
+			rpc_result_response(rpc_info.id.clone().unwrap_or_default(), result)
+		}
+		Err(ex) => {
+			let id = rpc_info.id.clone().unwrap_or_default();
+			rpc_error_response(id, Some(&rpc_info), &ctx, ex.into())
+		}
+	};
+
+	(!notification).then_some(res)
 }
 
-async fn _rpc_handler(
-	ctx: Ctx,
-	mm: ModelManager,
-	rpc_req: RpcRequest,
-) -> Result<Json<Value>> {
-	let rpc_method = rpc_req.method.clone();
-	let rpc_id = rpc_req.id.clone();
+pub(super) fn rpc_result_response(id: Value, result: Value) -> Value {
+	json!({
+		"jsonrpc": JSONRPC_VERSION,
+		"id": id,
+		"result": result
+	})
+}
 
-	debug!("{:<12} - _rpc_handler - method: {rpc_method}", "HANDLER");
+/// Returns the error response (with the error code of the client error),
+/// and logs the call.
+pub(super) fn rpc_error_response(
+	id: Value,
+	rpc_info: Option<&RpcInfo>,
+	ctx: &Ctx,
+	web_error: web::Error,
+) -> Value {
+	let uuid = Uuid::new_v4();
+	let (_, client_error) = web_error.client_status_and_error();
+	let res = client_error_body(id, &client_error, uuid);
 
-	let result = exec_rpc(ctx, mm, rpc_req).await?;
+	debug!("CLIENT ERROR BODY:\n{res}");
 
-	let body_response = json!({
-		"id": rpc_id,
-		"result": result
-	});
+	log_rpc_call(uuid, rpc_info, ctx, Some(&web_error), Some(client_error));
 
-	Ok(Json(body_response))
+	res
 }
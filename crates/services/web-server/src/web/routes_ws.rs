@@ -0,0 +1,575 @@
+//! The json-rpc over websocket endpoint (`/api/ws`).
+//!
+//! - Request frames: the JSON-RPC 2.0 calls or batches (as for `/api/rpc`),
+//!   executed concurrently, and answered with the response frames in
+//!   completion order (i.e., matched by `id`).
+//! - Notification frames (server initiated): the JSON-RPC notifications,
+//!   with the methods `task_changed`, `task_resync`, and `reauth_required`.
+//! - Reauthentication: the `reauth` request, with the login `{username, pwd}`
+//!   params (see `WsSession`).
+//! - Heartbeat: the server pings, and closes the connection without any
+//!   incoming frame for `HEARTBEAT_TIMEOUT`.
+
+use crate::events::TaskChange;
+use crate::web::mw_auth::CtxW;
+use crate::web::routes_events::EventsState;
+use crate::web::routes_login::{validate_login, LoginPayload};
+use crate::web::routes_rpc::{
+	exec_rpc_body, rpc_error_response, rpc_result_response, RpcInfo,
+};
+use crate::web::{Error, Result};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::Response;
+use axum::routing::get;
+use axum::Router;
+use futures::stream::SplitSink;
+use futures::{SinkExt, StreamExt};
+use lib_auth::token::{generate_web_token, validate_web_token, Token};
+use lib_core::ctx::Ctx;
+use lib_core::model::task::TaskBmc;
+use lib_core::model::user::{UserBmc, UserForAuth};
+use lib_core::model::ModelManager;
+use lib_rpc::{
+	parse_rpc_body, RpcBody, RpcRequest, RpcResources, RpcRouter, JSONRPC_VERSION,
+};
+use lib_utils::time::{now_utc, parse_utc};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{mpsc, Semaphore};
+use tokio::time::Instant;
+use tracing::{debug, error};
+
+/// The interval between the server pings.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+/// The max time without any incoming frame (e.g., pong) before closing.
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(90);
+/// The max concurrent in-flight requests per connection
+/// (the next request frames are read when one completes).
+const IN_FLIGHT_MAX: usize = 32;
+/// The buffered outgoing frames per connection.
+const OUTGOING_BUFFER: usize = 64;
+
+/// The reauthentication method, handled by the connection (not by the `RpcRouter`).
+const REAUTH_METHOD: &str = "reauth";
+
+pub fn routes(state: EventsState) -> Router {
+	Router::new()
+		.route("/ws", get(ws_handler))
+		.with_state(state)
+}
+
+async fn ws_handler(
+	State(state): State<EventsState>,
+	ctx: CtxW,
+	ws: WebSocketUpgrade,
+) -> Result<Response> {
+	debug!("{:<12} - ws_handler", "HANDLER");
+
+	let session = WsSession::start(&state.mm, ctx.0).await?;
+
+	Ok(ws.on_upgrade(move |socket| handle_socket(socket, state, session)))
+}
+
+// region:    --- WsSession
+/// The authentication of a connection, with its own web token, which is
+/// renewed by each request frame (as the token cookie by each http request).
+///
+/// When the token expires (i.e., no request for the token duration),
+/// the `reauth_required` notification is sent, and the requests fail
+/// with `WsSessionExpired` until a successful `reauth` request.
+struct WsSession {
+	ctx: Ctx,
+	username: String,
+	token: Token,
+	expires_at: Instant,
+	reauth_notified: bool,
+}
+
+impl WsSession {
+	async fn start(mm: &ModelManager, ctx: Ctx) -> Result<Self> {
+		let user: UserForAuth =
+			UserBmc::get(&Ctx::root_ctx(), mm, ctx.user_id()).await?;
+		let token = generate_web_token(&user.username, user.token_salt)?;
+
+		Ok(WsSession {
+			ctx,
+			username: user.username,
+			expires_at: expires_at(&token),
+			token,
+			reauth_notified: false,
+		})
+	}
+
+	/// Validates the current token (with the current user token salt),
+	/// and renews it.
+	async fn renew(&mut self, mm: &ModelManager) -> Result<()> {
+		let user: UserForAuth =
+			UserBmc::first_by_username(&Ctx::root_ctx(), mm, &self.username)
+				.await?
+				.ok_or(Error::WsSessionExpired)?;
+		validate_web_token(&self.token, user.token_salt)
+			.map_err(|_| Error::WsSessionExpired)?;
+
+		self.token = generate_web_token(&user.username, user.token_salt)?;
+		self.expires_at = expires_at(&self.token);
+		self.reauth_notified = false;
+
+		Ok(())
+	}
+
+	/// Reauthenticates with the login of the same user.
+	async fn reauth(
+		&mut self,
+		mm: &ModelManager,
+		params: Option<Value>,
+	) -> Result<()> {
+		let LoginPayload { username, pwd } =
+			serde_json::from_value(params.unwrap_or_default()).map_err(|_| {
+				lib_rpc::Error::RpcFailJsonParams {
+					rpc_method: REAUTH_METHOD.to_string(),
+				}
+			})?;
+		let user = validate_login(mm, &username, pwd).await?;
+		if user.id != self.ctx.user_id() {
+			return Err(Error::WsReauthUserNotMatching { user_id: user.id });
+		}
+
+		*self = WsSession::start(mm, self.ctx.clone()).await?;
+
+		Ok(())
+	}
+}
+
+fn expires_at(token: &Token) -> Instant {
+	let remaining = parse_utc(&token.exp)
+		.ok()
+		.and_then(|exp| Duration::try_from(exp - now_utc()).ok())
+		.unwrap_or_default();
+
+	Instant::now() + remaining
+}
+// endregion: --- WsSession
+
+// region:    --- Connection
+struct WsConnection {
+	mm: ModelManager,
+	rpc_router: Arc<RpcRouter>,
+	session: WsSession,
+	/// The outgoing frames, written by a dedicated task
+	/// (as the requests complete concurrently).
+	tx: mpsc::Sender<Message>,
+	in_flight: Arc<Semaphore>,
+}
+
+async fn handle_socket(socket: WebSocket, state: EventsState, session: WsSession) {
+	let EventsState {
+		mm,
+		task_changes,
+		rpc_router,
+	} = state;
+	let (sink, mut stream) = socket.split();
+
+	let (tx, rx) = mpsc::channel(OUTGOING_BUFFER);
+	let writer = tokio::spawn(write_frames(sink, rx));
+
+	let mut conn = WsConnection {
+		mm,
+		rpc_router,
+		session,
+		tx,
+		in_flight: Arc::new(Semaphore::new(IN_FLIGHT_MAX)),
+	};
+	let mut task_changes = task_changes.subscribe();
+	let mut heartbeat = tokio::time::interval_at(
+		Instant::now() + HEARTBEAT_INTERVAL,
+		HEARTBEAT_INTERVAL,
+	);
+	let mut last_seen = Instant::now();
+
+	loop {
+		let expires_at = conn.session.expires_at;
+		let reauth_notified = conn.session.reauth_notified;
+
+		tokio::select! {
+			// Note: The pongs are sent by axum, and any frame keeps
+			//       the connection alive.
+			frame = stream.next() => {
+				last_seen = Instant::now();
+				match frame {
+					Some(Ok(Message::Text(text))) => conn.on_request(text).await,
+					Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+					Some(Ok(_)) => continue,
+				}
+			}
+
+			change = task_changes.recv() => {
+				if let Err(RecvError::Closed) = change {
+					break;
+				}
+				conn.on_task_change(change).await;
+			}
+
+			_ = tokio::time::sleep_until(expires_at), if !reauth_notified => {
+				conn.session.reauth_notified = true;
+				conn.send(notification_frame("reauth_required", Value::Null)).await;
+			}
+
+			_ = heartbeat.tick() => {
+				if last_seen.elapsed() > HEARTBEAT_TIMEOUT {
+					debug!("{:<12} - ws - heartbeat timeout", "HANDLER");
+					break;
+				}
+				conn.send(Message::Ping(Vec::new())).await;
+			}
+		}
+	}
+
+	// -- Close (the in-flight requests complete without their response).
+	drop(conn);
+	writer.abort();
+}
+
+impl WsConnection {
+	async fn send(&self, frame: Message) {
+		// Note: Fails only when the connection is closing.
+		let _ = self.tx.send(frame).await;
+	}
+
+	async fn send_response(&self, res: Option<Value>) {
+		if let Some(res) = res {
+			self.send(Message::Text(res.to_string())).await;
+		}
+	}
+
+	/// Executes the request frame (a call or a batch), concurrently with the
+	/// other in-flight requests (up to `IN_FLIGHT_MAX`, otherwise, waits for
+	/// one to complete).
+	async fn on_request(&mut self, text: String) {
+		let ctx = self.session.ctx.clone();
+		let rpc_body = match parse_rpc_body(text.as_bytes()) {
+			Ok(rpc_body) => rpc_body,
+			Err(ex) => {
+				let res = rpc_error_response(Value::Null, None, &ctx, ex.into());
+				self.send_response(Some(res)).await;
+				return;
+			}
+		};
+
+		// -- Reauthenticate (only as a single call).
+		if let RpcBody::Single(call) = &rpc_body {
+			if call.get("method").and_then(Value::as_str) == Some(REAUTH_METHOD) {
+				let res = self.reauth(call.clone()).await;
+				self.send_response(res).await;
+				return;
+			}
+		}
+
+		// -- Authenticate the request.
+		if let Err(ex) = self.session.renew(&self.mm).await {
+			let id = match &rpc_body {
+				RpcBody::Single(call) => call.get("id").cloned().unwrap_or_default(),
+				RpcBody::Batch(_) => Value::Null,
+			};
+			let res = rpc_error_response(id, None, &ctx, ex);
+			self.send_response(Some(res)).await;
+			return;
+		}
+
+		// -- Exec the request.
+		let Ok(permit) = self.in_flight.clone().acquire_owned().await else {
+			return;
+		};
+		let resources = RpcResources {
+			ctx,
+			mm: self.mm.clone(),
+		};
+		let (rpc_router, tx) = (self.rpc_router.clone(), self.tx.clone());
+		tokio::spawn(async move {
+			if let Some(res) = exec_rpc_body(&rpc_router, resources, rpc_body).await
+			{
+				let _ = tx.send(Message::Text(res.to_string())).await;
+			}
+			drop(permit);
+		});
+	}
+
+	/// Returns the `reauth` call response (`None` for a notification).
+	async fn reauth(&mut self, call: Value) -> Option<Value> {
+		let ctx = self.session.ctx.clone();
+		let rpc_req = match RpcRequest::from_value(call) {
+			Ok(rpc_req) => rpc_req,
+			Err((id, ex)) => {
+				return Some(rpc_error_response(id, None, &ctx, ex.into()))
+			}
+		};
+		let rpc_info = RpcInfo {
+			id: rpc_req.id.clone(),
+			method: rpc_req.method.clone(),
+		};
+		let id = rpc_info.id.clone().unwrap_or_default();
+
+		let res = match self.session.reauth(&self.mm, rpc_req.params).await {
+			Ok(()) => rpc_result_response(id, json!({ "success": true })),
+			Err(ex) => rpc_error_response(id, Some(&rpc_info), &ctx, ex),
+		};
+
+		rpc_info.id.is_some().then_some(res)
+	}
+
+	/// Notifies the task change when visible to the user.
+	async fn on_task_change(
+		&self,
+		change: core::result::Result<TaskChange, RecvError>,
+	) {
+		let event = match change {
+			Ok(TaskChange::Event(event)) => event,
+			Ok(TaskChange::Resync) | Err(_) => {
+				self.send(notification_frame("task_resync", Value::Null))
+					.await;
+				return;
+			}
+		};
+
+		match TaskBmc::is_event_visible(&self.session.ctx, &self.mm, &event).await {
+			Ok(true) => {
+				self.send(notification_frame("task_changed", json!(event)))
+					.await
+			}
+			Ok(false) => (),
+			Err(ex) => {
+				error!("{:<12} - ws - {ex:?}", "HANDLER");
+				self.send(notification_frame("task_resync", Value::Null))
+					.await;
+			}
+		}
+	}
+}
+
+async fn write_frames(
+	mut sink: SplitSink<WebSocket, Message>,
+	mut rx: mpsc::Receiver<Message>,
+) {
+	while let Some(frame) = rx.recv().await {
+		if sink.send(frame).await.is_err() {
+			break;
+		}
+	}
+	let _ = sink.close().await;
+}
+// endregion: --- Connection
+
+// region:    --- Frames
+fn notification_frame(method: &str, params: Value) -> Message {
+	let body = json!({
+		"jsonrpc": JSONRPC_VERSION,
+		"method": method,
+		"params": params
+	});
+
+	Message::Text(body.to_string())
+}
+// endregion: --- Frames
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::web::routes_rpc::RpcState;
+	use anyhow::{bail, Context, Result};
+	use lib_core::_dev_utils;
+	use lib_core::model::outbox::DomainEvent;
+	use lib_core::model::user::User;
+	use lib_utils::time::now_utc_plus_sec_str;
+	use serial_test::serial;
+	use tokio::sync::mpsc::error::TryRecvError;
+
+	const FRAME_TIMEOUT: Duration = Duration::from_secs(5);
+
+	#[serial]
+	#[tokio::test]
+	async fn test_on_request_err_session_expired_then_reauth_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let (mut conn, mut rx) = fx_conn().await?;
+		// Note: As if not renewed for the token duration.
+		conn.session.token.exp = now_utc_plus_sec_str(-1.);
+
+		// -- Exec & Check (expired)
+		conn.on_request(fx_call(1, "list_tasks", json!({}))).await;
+		let res = next_frame(&mut rx).await?;
+		assert_eq!(res["id"], 1);
+		assert_eq!(res["error"]["message"], "NO_AUTH");
+
+		// -- Exec & Check (reauth)
+		let params = json!({"username": "demo1", "pwd": "welcome"});
+		conn.on_request(fx_call(2, REAUTH_METHOD, params)).await;
+		let res = next_frame(&mut rx).await?;
+		assert_eq!(res["id"], 2);
+		assert_eq!(res["result"]["success"], true);
+
+		// -- Exec & Check (renewed)
+		conn.on_request(fx_call(3, "list_tasks", json!({}))).await;
+		let res = next_frame(&mut rx).await?;
+		assert_eq!(res["id"], 3);
+		assert!(res.get("result").is_some(), "Should be a result: {res}");
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_reauth_err_user_not_matching() -> Result<()> {
+		// -- Setup & Fixtures
+		let (mut conn, mut rx) = fx_conn().await?;
+		// Note: As the session of another user (than the `demo1` login).
+		conn.session.ctx = Ctx::new(9040)?;
+		let params = json!({"username": "demo1", "pwd": "welcome"});
+
+		// -- Exec
+		let res = conn.session.reauth(&conn.mm, Some(params.clone())).await;
+		conn.on_request(fx_call(1, REAUTH_METHOD, params)).await;
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::WsReauthUserNotMatching { .. })),
+			"WsReauthUserNotMatching not matching"
+		);
+		let res = next_frame(&mut rx).await?;
+		assert_eq!(res["id"], 1);
+		assert_eq!(res["error"]["message"], "LOGIN_FAIL");
+		assert_eq!(conn.session.ctx.user_id(), 9040);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_on_request_ok_in_flight_max() -> Result<()> {
+		// -- Setup & Fixtures
+		let (mut conn, mut rx) = fx_conn().await?;
+		conn.in_flight = Arc::new(Semaphore::new(1));
+		let fx_permit = conn.in_flight.clone().acquire_owned().await?;
+
+		// -- Exec & Check (waits for an in-flight request to complete)
+		let res = tokio::time::timeout(
+			Duration::from_millis(100),
+			conn.on_request(fx_call(1, "list_tasks", json!({}))),
+		)
+		.await;
+		assert!(res.is_err(), "Should wait for the in-flight permit");
+		assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+
+		// -- Exec & Check (executed once completed)
+		drop(fx_permit);
+		conn.on_request(fx_call(2, "list_tasks", json!({}))).await;
+		let res = next_frame(&mut rx).await?;
+		assert_eq!(res["id"], 2);
+		assert!(res.get("result").is_some(), "Should be a result: {res}");
+		// The permit is released with the response.
+		let _permit = tokio::time::timeout(
+			FRAME_TIMEOUT,
+			conn.in_flight.clone().acquire_owned(),
+		)
+		.await??;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_on_task_change_ok_visible_only() -> Result<()> {
+		// -- Setup & Fixtures
+		let (conn, mut rx) = fx_conn().await?;
+		let ctx = conn.session.ctx.clone();
+		let other_ctx = Ctx::new(9040)?;
+		let fx_task = _dev_utils::seed_tasks(
+			&ctx,
+			&conn.mm,
+			&["test_on_task_change_ok_visible_only-task 01"],
+		)
+		.await?
+		.remove(0);
+		let fx_other_task = _dev_utils::seed_tasks(
+			&other_ctx,
+			&conn.mm,
+			&["test_on_task_change_ok_visible_only-task 02"],
+		)
+		.await?
+		.remove(0);
+		let fx_event = |task_id| {
+			Ok(TaskChange::Event(DomainEvent::TaskUpdated {
+				task_id,
+				changed_fields: vec!["title".to_string()],
+			}))
+		};
+
+		// -- Exec & Check (not visible)
+		conn.on_task_change(fx_event(fx_other_task.id)).await;
+		assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+
+		// -- Exec & Check (visible)
+		conn.on_task_change(fx_event(fx_task.id)).await;
+		let res = next_frame(&mut rx).await?;
+		assert_eq!(res["method"], "task_changed");
+		assert_eq!(res["params"]["data"]["task_id"], fx_task.id);
+
+		// -- Exec & Check (resync)
+		conn.on_task_change(Ok(TaskChange::Resync)).await;
+		let res = next_frame(&mut rx).await?;
+		assert_eq!(res["method"], "task_resync");
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &conn.mm, fx_task.id).await?;
+		TaskBmc::delete(&other_ctx, &conn.mm, fx_other_task.id).await?;
+
+		Ok(())
+	}
+
+	/// Returns the connection of the `demo1` user (with its own pool,
+	/// see `fx_resources` of lib-rpc), and its outgoing frames.
+	async fn fx_conn() -> Result<(WsConnection, mpsc::Receiver<Message>)> {
+		_dev_utils::init_test().await;
+		let mm = ModelManager::new().await?;
+		let user: User = UserBmc::first_by_username(&Ctx::root_ctx(), &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+		let session = WsSession::start(&mm, Ctx::new(user.id)?).await?;
+
+		let (tx, rx) = mpsc::channel(OUTGOING_BUFFER);
+		let conn = WsConnection {
+			rpc_router: RpcState::new(mm.clone()).rpc_router,
+			mm,
+			session,
+			tx,
+			in_flight: Arc::new(Semaphore::new(IN_FLIGHT_MAX)),
+		};
+
+		Ok((conn, rx))
+	}
+
+	fn fx_call(id: i64, method: &str, params: Value) -> String {
+		json!({
+			"jsonrpc": JSONRPC_VERSION,
+			"id": id,
+			"method": method,
+			"params": params
+		})
+		.to_string()
+	}
+
+	/// Returns the json of the next outgoing text frame
+	/// (waiting for the concurrent requests).
+	async fn next_frame(rx: &mut mpsc::Receiver<Message>) -> Result<Value> {
+		let frame = tokio::time::timeout(FRAME_TIMEOUT, rx.recv())
+			.await?
+			.context("Should have a frame")?;
+		let Message::Text(text) = frame else {
+			bail!("Should be a text frame, but was: {frame:?}");
+		};
+
+		Ok(serde_json::from_str(&text)?)
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,96 @@
+import type { ClientError } from "./types";
+
+/** The JSON-RPC error response, with its `ClientError` (`message` and `detail`). */
+export class RpcError extends Error {
+  readonly code: number;
+  readonly clientError: ClientError;
+  /** The server request uuid (for the server logs). */
+  readonly reqUuid?: string;
+
+  constructor(code: number, clientError: ClientError, reqUuid?: string) {
+    super(clientError.message);
+    this.name = "RpcError";
+    this.code = code;
+    this.clientError = clientError;
+    this.reqUuid = reqUuid;
+  }
+}
+
+export interface RpcClientOptions {
+  /** Authenticates with `Authorization: Bearer <token>`, instead of the login cookie. */
+  bearerToken?: string;
+}
+
+/** The fetch client of the `/api/rpc` methods (see the generated methods below). */
+export class RpcClient {
+  private nextId = 1;
+
+  constructor(
+    private readonly baseUrl = "",
+    private readonly options: RpcClientOptions = {},
+  ) {}
+
+  async login(username: string, pwd: string): Promise<void> {
+    await this.post("/api/login", { username, pwd });
+  }
+
+  async logoff(): Promise<void> {
+    await this.post("/api/logoff", { logoff: true });
+  }
+
+  /** Calls the rpc method, and returns its result (or throws an `RpcError`). */
+  async call<R>(method: string, params?: unknown): Promise<R> {
+    const body = await this.post("/api/rpc", {
+      jsonrpc: "2.0",
+      id: this.nextId++,
+      method,
+      params,
+    });
+    return body.result as R;
+  }
+
+  /**
+   * Same as `call`, with the idempotency key of a mutating method call, which
+   * returns the first call result when retried with the same key and params.
+   */
+  async callIdempotent<R>(
+    method: string,
+    params: unknown,
+    idempotencyKey: string,
+  ): Promise<R> {
+    const body = await this.post("/api/rpc", {
+      jsonrpc: "2.0",
+      id: this.nextId++,
+      method,
+      params,
+      idempotency_key: idempotencyKey,
+    });
+    return body.result as R;
+  }
+
+  // Note: The error responses have an error http status,
+  //       and their `error` in the body (as the rpc error responses).
+  private async post(path: string, payload: unknown): Promise<any> {
+    const headers: Record<string, string> = {
+      "content-type": "application/json",
+    };
+    if (this.options.bearerToken) {
+      headers["authorization"] = `Bearer ${this.options.bearerToken}`;
+    }
+
+    const res = await fetch(this.baseUrl + path, {
+      method: "POST",
+      credentials: "include",
+      headers,
+      body: JSON.stringify(payload),
+    });
+    const body = await res.json();
+
+    if (body.error) {
+      const { code, message, data } = body.error;
+      const clientError = { message, detail: data?.detail } as ClientError;
+      throw new RpcError(code, clientError, data?.req_uuid);
+    }
+
+    return body;
+  }
@@ -0,0 +1,209 @@
+//! Generates the TypeScript types and fetch client of the rpc methods,
+//! from the lib-rpc OpenRPC document, for the `web-folder` frontend.
+//!
+//! Usage: `cargo run -p gen-ts -- [--check] [out_dir]`, where `out_dir` defaults
+//! to the workspace `web-folder/src/rpc`. With `--check`, fails when the files
+//! are not up to date (i.e., when the API changed since the last generation).
+
+mod ts;
+
+use crate::ts::{collect_refs, ts_declaration, ts_type};
+use anyhow::{bail, Result};
+use lib_rpc::{all_rpc_router, ClientError};
+use schemars::schema_for;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+const DEFAULT_OUT_DIR: &str =
+	concat!(env!("CARGO_MANIFEST_DIR"), "/../../../web-folder/src/rpc");
+const HEADER: &str = "// Generated by `cargo run -p gen-ts`, do not edit.\n";
+const CLIENT_BASE: &str = include_str!("client_base.ts");
+
+fn main() -> Result<()> {
+	let mut check = false;
+	let mut out_dir = PathBuf::from(DEFAULT_OUT_DIR);
+	for arg in std::env::args().skip(1) {
+		match arg.as_str() {
+			"--check" => check = true,
+			_ => out_dir = PathBuf::from(arg),
+		}
+	}
+
+	let openrpc_doc =
+		all_rpc_router().openrpc_document(&ClientError::openrpc_errors());
+	let client_error = schema_for!(ClientError).to_value();
+
+	let files = [
+		("types.ts", gen_types(&openrpc_doc, &client_error)),
+		("client.ts", gen_client(&openrpc_doc)),
+	];
+
+	// -- Check the files are up to date.
+	if check {
+		let stale: Vec<&str> = files
+			.iter()
+			.filter(|(name, content)| {
+				fs::read_to_string(out_dir.join(name)).ok().as_ref() != Some(content)
+			})
+			.map(|(name, _)| *name)
+			.collect();
+		if !stale.is_empty() {
+			bail!(
+				"{stale:?} not up to date in {}, run `cargo run -p gen-ts`",
+				out_dir.display()
+			);
+		}
+		println!("\nTypeScript files up to date in {}", out_dir.display());
+		return Ok(());
+	}
+
+	// -- Write the files.
+	fs::create_dir_all(&out_dir)?;
+	for (name, content) in files {
+		let path = out_dir.join(name);
+		fs::write(&path, content)?;
+		println!("\nGenerated:\n{}", path.display());
+	}
+
+	Ok(())
+}
+
+/// Returns the `types.ts` content, with the types of the document schemas,
+/// the `ClientError`, and the params of each method (e.g., `CreateTaskParams`).
+fn gen_types(openrpc_doc: &Value, client_error: &Value) -> String {
+	let mut out = HEADER.to_string();
+
+	// -- Schemas
+	// Note: The `ClientError` (e.g., of the bulk `ItemResult`) is declared below.
+	if let Some(schemas) = openrpc_doc["components"]["schemas"].as_object() {
+		for (name, schema) in
+			schemas.iter().filter(|(name, _)| *name != "ClientError")
+		{
+			out.push('\n');
+			out.push_str(&ts_declaration(name, schema));
+		}
+	}
+
+	// -- ClientError
+	out.push('\n');
+	out.push_str(&ts_declaration("ClientError", client_error));
+
+	// -- Method Params
+	for method in methods(openrpc_doc) {
+		let Some(params_schema) = params_schema(method) else {
+			continue;
+		};
+		out.push('\n');
+		out.push_str(&ts_declaration(&params_name(method), &params_schema));
+	}
+
+	out
+}
+
+/// Returns the `client.ts` content, the `RpcClient` with a method per rpc method
+/// (e.g., `createTask(params: CreateTaskParams): Promise<Task>`).
+fn gen_client(openrpc_doc: &Value) -> String {
+	let mut imports = BTreeSet::from(["ClientError".to_string()]);
+	let mut client_methods = String::new();
+
+	for method in methods(openrpc_doc) {
+		let rpc_method = method["name"].as_str().unwrap_or_default();
+		let result_schema = &method["result"]["schema"];
+		collect_refs(result_schema, &mut imports);
+		let result = ts_type(result_schema, 1);
+
+		let (args, params) = match params_schema(method) {
+			Some(params_schema) => {
+				let params_name = params_name(method);
+				let has_required = params_schema["required"]
+					.as_array()
+					.is_some_and(|names| !names.is_empty());
+				let default = if has_required { "" } else { " = {}" };
+				let args = format!("params: {params_name}{default}");
+				imports.insert(params_name);
+				(args, ", params")
+			}
+			None => (String::new(), ""),
+		};
+
+		client_methods.push_str(&format!(
+			"\n  {}({args}): Promise<{result}> {{\n    return this.call(\"{rpc_method}\"{params});\n  }}\n",
+			camel_case(rpc_method)
+		));
+	}
+
+	let imports: Vec<String> = imports.into_iter().collect();
+	let import = format!(
+		"import type {{\n  {},\n}} from \"./types\";\n",
+		imports.join(",\n  ")
+	);
+
+	let client_base = CLIENT_BASE
+		.replace("import type { ClientError } from \"./types\";\n", &import);
+
+	format!("{HEADER}\n{client_base}{client_methods}}}\n")
+}
+
+// region:    --- Support
+
+fn methods(openrpc_doc: &Value) -> impl Iterator<Item = &Value> {
+	openrpc_doc["methods"].as_array().into_iter().flatten()
+}
+
+/// Returns the params object schema of the method
+/// (`None` for the methods without params).
+fn params_schema(method: &Value) -> Option<Value> {
+	let params = method["params"].as_array().filter(|p| !p.is_empty())?;
+
+	let mut properties = serde_json::Map::new();
+	let mut required = Vec::new();
+	for param in params {
+		let name = param["name"].as_str().unwrap_or_default();
+		properties.insert(name.to_string(), param["schema"].clone());
+		if param["required"] == true {
+			required.push(name);
+		}
+	}
+
+	Some(json!({
+		"type": "object",
+		"properties": properties,
+		"required": required,
+	}))
+}
+
+/// e.g., `CreateTaskParams` for `create_task`.
+fn params_name(method: &Value) -> String {
+	let rpc_method = method["name"].as_str().unwrap_or_default();
+	let name = camel_case(rpc_method);
+	let mut chars = name.chars();
+	let first = chars.next().map(|c| c.to_ascii_uppercase());
+
+	format!(
+		"{}{}Params",
+		first.into_iter().collect::<String>(),
+		chars.as_str()
+	)
+}
+
+/// e.g., `createTask` for `create_task`.
+fn camel_case(rpc_method: &str) -> String {
+	let mut out = String::new();
+	let mut upper = false;
+	for c in rpc_method.chars() {
+		match c {
+			'_' | '.' => upper = true,
+			_ if upper => {
+				out.push(c.to_ascii_uppercase());
+				upper = false;
+			}
+			_ => out.push(c),
+		}
+	}
+
+	out
+}
+
+// endregion: --- Support
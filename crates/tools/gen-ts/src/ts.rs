@@ -0,0 +1,232 @@
+//! The TypeScript types of the JSON schemas (draft 7, as generated by schemars),
+//! where the `$ref` schemas are the types of the same name.
+
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+const INDENT: &str = "  ";
+
+/// Returns the declaration of the named schema,
+/// an `interface` for the object schemas, and a `type` otherwise.
+pub fn ts_declaration(name: &str, schema: &Value) -> String {
+	let mut out = ts_doc(schema, "");
+
+	match schema.as_object().filter(|obj| has_properties(obj)) {
+		Some(obj) => {
+			out.push_str(&format!("export interface {name} {}\n", ts_object(obj, 0)))
+		}
+		None => {
+			out.push_str(&format!("export type {name} = {};\n", ts_type(schema, 0)))
+		}
+	}
+
+	out
+}
+
+/// Returns the type of the schema, where `depth` is the indentation
+/// depth of the inline object types.
+pub fn ts_type(schema: &Value, depth: usize) -> String {
+	// -- The boolean schemas (`true` for any value, `false` for none).
+	let Some(obj) = schema.as_object() else {
+		let ts_type = if schema == &Value::Bool(false) {
+			"never"
+		} else {
+			"unknown"
+		};
+		return ts_type.to_string();
+	};
+
+	if let Some(name) = obj.get("$ref").and_then(Value::as_str).and_then(ref_name) {
+		return name.to_string();
+	}
+	// Note: The json literals are also valid TypeScript literals.
+	if let Some(val) = obj.get("const") {
+		return val.to_string();
+	}
+	if let Some(vals) = obj.get("enum").and_then(Value::as_array) {
+		return ts_union(vals.iter().map(Value::to_string));
+	}
+	for key in ["anyOf", "oneOf"] {
+		if let Some(schemas) = obj.get(key).and_then(Value::as_array) {
+			return ts_union(schemas.iter().map(|s| ts_type(s, depth)));
+		}
+	}
+	if let Some(schemas) = obj.get("allOf").and_then(Value::as_array) {
+		let types: Vec<String> = schemas
+			.iter()
+			.map(|s| ts_wrapped(ts_type(s, depth)))
+			.collect();
+		return types.join(" & ");
+	}
+
+	match obj.get("type") {
+		Some(Value::String(json_type)) => ts_json_type(json_type, obj, depth),
+		Some(Value::Array(json_types)) => ts_union(
+			json_types
+				.iter()
+				.filter_map(Value::as_str)
+				.map(|json_type| ts_json_type(json_type, obj, depth)),
+		),
+		_ if has_properties(obj) => ts_object(obj, depth),
+		_ => "unknown".to_string(),
+	}
+}
+
+/// Adds the names of the `$ref` schemas of the schema to `names`.
+pub fn collect_refs(schema: &Value, names: &mut BTreeSet<String>) {
+	match schema {
+		Value::Object(obj) => {
+			if let Some(name) =
+				obj.get("$ref").and_then(Value::as_str).and_then(ref_name)
+			{
+				names.insert(name.to_string());
+			}
+			for val in obj.values() {
+				collect_refs(val, names);
+			}
+		}
+		Value::Array(vals) => {
+			for val in vals {
+				collect_refs(val, names);
+			}
+		}
+		_ => (),
+	}
+}
+
+/// Returns the JSDoc comment of the schema `description` (empty without).
+pub fn ts_doc(schema: &Value, indent: &str) -> String {
+	let Some(description) = schema.get("description").and_then(Value::as_str) else {
+		return String::new();
+	};
+	let description = description.replace("*/", "*\\/");
+
+	let lines: Vec<&str> = description.lines().collect();
+	if let [line] = lines.as_slice() {
+		return format!("{indent}/** {line} */\n");
+	}
+
+	let mut out = format!("{indent}/**\n");
+	for line in lines {
+		out.push_str(&format!("{indent} * {line}\n").replace(" * \n", " *\n"));
+	}
+	out.push_str(&format!("{indent} */\n"));
+
+	out
+}
+
+// region:    --- Support
+
+fn ts_json_type(json_type: &str, obj: &Map<String, Value>, depth: usize) -> String {
+	match json_type {
+		"string" => "string".to_string(),
+		"integer" | "number" => "number".to_string(),
+		"boolean" => "boolean".to_string(),
+		"null" => "null".to_string(),
+		"array" => {
+			let item = obj
+				.get("items")
+				.map(|items| ts_type(items, depth))
+				.unwrap_or_else(|| "unknown".to_string());
+			format!("{}[]", ts_wrapped(item))
+		}
+		"object" => ts_object(obj, depth),
+		_ => "unknown".to_string(),
+	}
+}
+
+/// Returns the inline object type of the properties, or a `Record` without.
+fn ts_object(obj: &Map<String, Value>, depth: usize) -> String {
+	let properties = obj
+		.get("properties")
+		.and_then(Value::as_object)
+		.filter(|properties| !properties.is_empty());
+
+	let Some(properties) = properties else {
+		let val = match obj.get("additionalProperties") {
+			Some(schema @ Value::Object(_)) => ts_type(schema, depth),
+			_ => "unknown".to_string(),
+		};
+		return format!("Record<string, {val}>");
+	};
+
+	let required: Vec<&str> = obj
+		.get("required")
+		.and_then(Value::as_array)
+		.map(|names| names.iter().filter_map(Value::as_str).collect())
+		.unwrap_or_default();
+	let indent = INDENT.repeat(depth + 1);
+
+	let mut out = "{\n".to_string();
+	for (name, schema) in properties {
+		let optional = if required.contains(&name.as_str()) {
+			""
+		} else {
+			"?"
+		};
+		out.push_str(&ts_doc(schema, &indent));
+		out.push_str(&format!(
+			"{indent}{}{optional}: {};\n",
+			ts_key(name),
+			ts_type(schema, depth + 1)
+		));
+	}
+	out.push_str(&INDENT.repeat(depth));
+	out.push('}');
+
+	out
+}
+
+/// Returns the union of the types (without duplicates, e.g., `null`).
+fn ts_union(types: impl Iterator<Item = String>) -> String {
+	let mut union: Vec<String> = Vec::new();
+	for ts_type in types {
+		if !union.contains(&ts_type) {
+			union.push(ts_type);
+		}
+	}
+
+	union.join(" | ")
+}
+
+/// Returns the type in parentheses when it is a union or an intersection
+/// (e.g., for the array items).
+fn ts_wrapped(ts_type: String) -> String {
+	if ts_type.contains(" | ") || ts_type.contains(" & ") {
+		format!("({ts_type})")
+	} else {
+		ts_type
+	}
+}
+
+/// Returns the property key, quoted when not an identifier.
+fn ts_key(name: &str) -> String {
+	let is_ident = name.chars().enumerate().all(|(i, c)| {
+		c == '_'
+			|| c == '$'
+			|| c.is_ascii_alphabetic()
+			|| (i > 0 && c.is_ascii_digit())
+	});
+
+	if is_ident && !name.is_empty() {
+		name.to_string()
+	} else {
+		Value::String(name.to_string()).to_string()
+	}
+}
+
+fn ref_name(reference: &str) -> Option<&str> {
+	reference.rsplit('/').next()
+}
+
+fn has_properties(obj: &Map<String, Value>) -> bool {
+	let is_object = match obj.get("type") {
+		Some(Value::String(json_type)) => json_type == "object",
+		None => true,
+		_ => false,
+	};
+
+	is_object && obj.get("properties").is_some()
+}
+
+// endregion: --- Support
@@ -24,6 +24,7 @@ async fn main() -> Result<()> {
 		let req_create_task = hc.do_post(
 			"/api/rpc",
 			json!({
+				"jsonrpc": "2.0",
 				"id": 1,
 				"method": "create_task",
 				"params": {
@@ -41,6 +42,7 @@ async fn main() -> Result<()> {
 	let req_update_task = hc.do_post(
 		"/api/rpc",
 		json!({
+			"jsonrpc": "2.0",
 			"id": 1,
 			"method": "update_task",
 			"params": {
@@ -57,6 +59,7 @@ async fn main() -> Result<()> {
 	let req_delete_task = hc.do_post(
 		"/api/rpc",
 		json!({
+			"jsonrpc": "2.0",
 			"id": 1,
 			"method": "delete_task",
 			"params": {
@@ -70,6 +73,7 @@ async fn main() -> Result<()> {
 	let req_list_tasks = hc.do_post(
 		"/api/rpc",
 		json!({
+			"jsonrpc": "2.0",
 			"id": 1,
 			"method": "list_tasks",
 			"params": {
@@ -5,6 +5,8 @@ CREATE TABLE "user" (
   id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
 
   username varchar(128) NOT NULL UNIQUE,
+  -- Can list the audit events
+  admin boolean NOT NULL DEFAULT false,
 
   -- Auth
   pwd varchar(256),
@@ -14,8 +16,177 @@ CREATE TABLE "user" (
 
 
 -- Task
+CREATE TYPE task_priority AS ENUM ('low', 'medium', 'high', 'urgent');
+CREATE TYPE task_status AS ENUM ('todo', 'in_progress', 'blocked', 'done');
+
+-- Note: No FK on owner_id, as the root ctx (user_id 0) can own entities.
 CREATE TABLE task (
   id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
-  done BOOL NOT NULL DEFAULT FALSE,
-  title varchar(256) NOT NULL
+  owner_id BIGINT NOT NULL,
+  assignee_id BIGINT REFERENCES "user"(id) ON DELETE SET NULL,
+  title varchar(256) NOT NULL,
+  description text, -- markdown
+  priority task_priority NOT NULL DEFAULT 'medium',
+  due_at timestamp with time zone,
+  status task_status NOT NULL DEFAULT 'todo',
+  recurrence varchar(256), -- RRULE (e.g., FREQ=DAILY)
+
+  -- Hierarchy (the subtasks are deleted with their parent, unless reparented first)
+  parent_id BIGINT REFERENCES task(id) ON DELETE CASCADE,
+  close_with_subtasks BOOL NOT NULL DEFAULT FALSE,
+
+  -- Manual ordering (per owner), with fractional index keys (byte ordered)
+  rank text COLLATE "C" NOT NULL,
+
+  -- Compatibility field, derived from the status.
+  done BOOL GENERATED ALWAYS AS (status = 'done') STORED,
+
+  -- Full-text search (the title matches weighted higher than the description ones)
+  search_tsv tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', title), 'A') ||
+    setweight(to_tsvector('english', coalesce(description, '')), 'B')
+  ) STORED
+);
+
+CREATE INDEX task_search_tsv_idx ON task USING GIN (search_tsv);
+CREATE INDEX task_parent_id_idx ON task (parent_id);
+CREATE INDEX task_owner_id_rank_idx ON task (owner_id, rank);
+CREATE INDEX task_assignee_id_idx ON task (assignee_id);
+
+-- Task Revision
+-- Note: The full task state after each create and update (see `DbBmc::REVISIONED`).
+CREATE TABLE task_revision (
+  task_id BIGINT NOT NULL REFERENCES task(id) ON DELETE CASCADE,
+  revision integer NOT NULL,
+  actor_id BIGINT NOT NULL,
+  state jsonb NOT NULL,
+
+  -- Timestamps
+  ctime timestamp with time zone NOT NULL DEFAULT now(),
+
+  PRIMARY KEY (task_id, revision)
+);
+
+-- Tag
+-- Note: No FK on owner_id, as the root ctx (user_id 0) can own entities.
+CREATE TABLE tag (
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+  owner_id BIGINT NOT NULL,
+  name varchar(64) NOT NULL,
+
+  -- Note: Named for its `name` field (see `model::Error::UniqueViolation`).
+  CONSTRAINT tag_name_key UNIQUE (owner_id, name)
+);
+
+-- Task Tag
+CREATE TABLE task_tag (
+  task_id BIGINT NOT NULL REFERENCES task(id) ON DELETE CASCADE,
+  tag_id BIGINT NOT NULL REFERENCES tag(id) ON DELETE CASCADE,
+
+  PRIMARY KEY (task_id, tag_id)
+);
+
+CREATE INDEX task_tag_tag_id_idx ON task_tag (tag_id);
+
+-- Task Watcher
+CREATE TABLE task_watcher (
+  task_id BIGINT NOT NULL REFERENCES task(id) ON DELETE CASCADE,
+  user_id BIGINT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+  PRIMARY KEY (task_id, user_id)
 );
+
+CREATE INDEX task_watcher_user_id_idx ON task_watcher (user_id);
+
+-- Comment
+-- Note: No FK on author_id, as the root ctx (user_id 0) can author entities.
+CREATE TABLE comment (
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+  task_id BIGINT NOT NULL REFERENCES task(id) ON DELETE CASCADE,
+  author_id BIGINT NOT NULL,
+  body text NOT NULL,
+
+  -- Timestamps
+  ctime timestamp with time zone NOT NULL DEFAULT now(),
+  mtime timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX comment_task_id_idx ON comment (task_id);
+
+-- Attachment
+-- Note: The content is in the blob store (under blob_key), and the blobs
+--       left by deleted rows (e.g., task cascade) are removed by the orphan cleanup.
+CREATE TABLE attachment (
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+  task_id BIGINT NOT NULL REFERENCES task(id) ON DELETE CASCADE,
+  uploader_id BIGINT NOT NULL,
+  file_name varchar(256) NOT NULL,
+  content_type varchar(128) NOT NULL,
+  size BIGINT NOT NULL,
+  blob_key varchar(64) NOT NULL UNIQUE,
+
+  -- Timestamps
+  ctime timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX attachment_task_id_idx ON attachment (task_id);
+
+-- Audit Event
+-- Note: Written by the model layer in the same transaction as the audited write
+--       (see 03-audit-guard.sql for the enforcement).
+CREATE TYPE audit_op AS ENUM ('create', 'update', 'delete');
+
+CREATE TABLE audit_event (
+  id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+  actor_id BIGINT NOT NULL,
+  entity varchar(64) NOT NULL,
+  entity_id BIGINT NOT NULL,
+  op audit_op NOT NULL,
+  diff jsonb NOT NULL, -- {field: {old, new}}
+
+  -- Timestamps
+  ctime timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX audit_event_entity_idx ON audit_event (entity, entity_id);
+CREATE INDEX audit_event_actor_id_idx ON audit_event (actor_id);
+
+-- Outbox
+-- Note: The domain events, appended by the model layer in the same transaction
+--       as the write, and delivered in (txid, id) order by the relays.
+CREATE TABLE outbox (
+  id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+  txid xid8 NOT NULL DEFAULT pg_current_xact_id(),
+  event jsonb NOT NULL, -- {type, data}
+
+  -- Timestamps
+  ctime timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX outbox_txid_id_idx ON outbox (txid, id);
+
+-- The last delivered event of each relay.
+CREATE TABLE outbox_offset (
+  relay varchar(64) NOT NULL PRIMARY KEY,
+  txid BIGINT NOT NULL,
+  id BIGINT NOT NULL
+);
+
+-- Idempotency Key
+-- Note: The results of the mutating rpc calls by key, replayed on the calls with
+--       the same key, until the key expires (see `SERVICE_IDEMPOTENCY_TTL_SEC`).
+CREATE TABLE idempotency_key (
+  user_id BIGINT NOT NULL,
+  key varchar(128) NOT NULL,
+  request_hash varchar(64) NOT NULL,
+  result jsonb, -- NULL while the first call is in progress
+
+  -- Timestamps
+  ctime timestamp with time zone NOT NULL DEFAULT now(),
+  -- The start of the call lease, taken over by a retry once expired
+  -- (e.g., when the call was cancelled)
+  reserved_at timestamp with time zone NOT NULL DEFAULT now(),
+
+  PRIMARY KEY (user_id, key)
+);
+
+CREATE INDEX idempotency_key_ctime_idx ON idempotency_key (ctime);
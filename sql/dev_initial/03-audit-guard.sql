@@ -0,0 +1,43 @@
+---- Audit guard (after the seed, as the seed writes are not audited)
+
+-- Rejects the writes made outside of the model layer audited transactions
+-- (i.e., `base::WriteTx`, which sets the `app.audited` transaction setting).
+CREATE FUNCTION audit_guard() RETURNS trigger AS $$
+BEGIN
+  IF current_setting('app.audited', true) IS DISTINCT FROM 'on' THEN
+    RAISE EXCEPTION 'unaudited write on %', TG_TABLE_NAME;
+  END IF;
+  RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER user_audit_guard BEFORE INSERT OR UPDATE OR DELETE ON "user"
+  FOR EACH STATEMENT EXECUTE FUNCTION audit_guard();
+CREATE TRIGGER task_audit_guard BEFORE INSERT OR UPDATE OR DELETE ON task
+  FOR EACH STATEMENT EXECUTE FUNCTION audit_guard();
+CREATE TRIGGER task_revision_audit_guard BEFORE INSERT OR UPDATE OR DELETE ON task_revision
+  FOR EACH STATEMENT EXECUTE FUNCTION audit_guard();
+CREATE TRIGGER task_watcher_audit_guard BEFORE INSERT OR UPDATE OR DELETE ON task_watcher
+  FOR EACH STATEMENT EXECUTE FUNCTION audit_guard();
+CREATE TRIGGER tag_audit_guard BEFORE INSERT OR UPDATE OR DELETE ON tag
+  FOR EACH STATEMENT EXECUTE FUNCTION audit_guard();
+CREATE TRIGGER task_tag_audit_guard BEFORE INSERT OR UPDATE OR DELETE ON task_tag
+  FOR EACH STATEMENT EXECUTE FUNCTION audit_guard();
+CREATE TRIGGER comment_audit_guard BEFORE INSERT OR UPDATE OR DELETE ON comment
+  FOR EACH STATEMENT EXECUTE FUNCTION audit_guard();
+CREATE TRIGGER attachment_audit_guard BEFORE INSERT OR UPDATE OR DELETE ON attachment
+  FOR EACH STATEMENT EXECUTE FUNCTION audit_guard();
+CREATE TRIGGER audit_event_audit_guard BEFORE INSERT ON audit_event
+  FOR EACH STATEMENT EXECUTE FUNCTION audit_guard();
+CREATE TRIGGER outbox_audit_guard BEFORE INSERT ON outbox
+  FOR EACH STATEMENT EXECUTE FUNCTION audit_guard();
+
+-- The audit events are immutable.
+CREATE FUNCTION audit_event_immutable() RETURNS trigger AS $$
+BEGIN
+  RAISE EXCEPTION 'audit_event is immutable';
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_event_immutable BEFORE UPDATE OR DELETE OR TRUNCATE ON audit_event
+  FOR EACH STATEMENT EXECUTE FUNCTION audit_event_immutable();
@@ -0,0 +1,294 @@
+// Generated by `cargo run -p gen-ts`, do not edit.
+
+import type {
+  AddCommentParams,
+  AssignTaskParams,
+  AttachTagParams,
+  Attachment,
+  AuditEvent,
+  ChangePasswordParams,
+  ClientError,
+  Comment,
+  CreateTagParams,
+  CreateTaskParams,
+  CreateTasksParams,
+  DeleteAttachmentParams,
+  DeleteCommentParams,
+  DeleteTagParams,
+  DeleteTaskParams,
+  DeleteTasksParams,
+  DetachTagParams,
+  EditCommentParams,
+  GetTaskHistoryParams,
+  GetTaskParams,
+  ItemResult_Task,
+  ItemResult_int64,
+  ListAttachmentsParams,
+  ListAuditEventsParams,
+  ListCommentsParams,
+  ListTagsParams,
+  ListTaskOccurrencesParams,
+  ListTaskTreeParams,
+  ListTasksParams,
+  MoveTaskParams,
+  RevertTaskParams,
+  SearchTasksParams,
+  SetTaskParentParams,
+  Tag,
+  TagWithCount,
+  Task,
+  TaskNode,
+  TaskOccurrence,
+  TaskProjection,
+  TaskRevision,
+  TaskSearchHit,
+  UnassignTaskParams,
+  UnwatchTaskParams,
+  UpdateMeParams,
+  UpdateTagParams,
+  UpdateTaskOccurrenceParams,
+  UpdateTaskParams,
+  UpdateTasksParams,
+  User,
+  WatchTaskParams,
+} from "./types";
+
+/** The JSON-RPC error response, with its `ClientError` (`message` and `detail`). */
+export class RpcError extends Error {
+  readonly code: number;
+  readonly clientError: ClientError;
+  /** The server request uuid (for the server logs). */
+  readonly reqUuid?: string;
+
+  constructor(code: number, clientError: ClientError, reqUuid?: string) {
+    super(clientError.message);
+    this.name = "RpcError";
+    this.code = code;
+    this.clientError = clientError;
+    this.reqUuid = reqUuid;
+  }
+}
+
+export interface RpcClientOptions {
+  /** Authenticates with `Authorization: Bearer <token>`, instead of the login cookie. */
+  bearerToken?: string;
+}
+
+/** The fetch client of the `/api/rpc` methods (see the generated methods below). */
+export class RpcClient {
+  private nextId = 1;
+
+  constructor(
+    private readonly baseUrl = "",
+    private readonly options: RpcClientOptions = {},
+  ) {}
+
+  async login(username: string, pwd: string): Promise<void> {
+    await this.post("/api/login", { username, pwd });
+  }
+
+  async logoff(): Promise<void> {
+    await this.post("/api/logoff", { logoff: true });
+  }
+
+  /** Calls the rpc method, and returns its result (or throws an `RpcError`). */
+  async call<R>(method: string, params?: unknown): Promise<R> {
+    const body = await this.post("/api/rpc", {
+      jsonrpc: "2.0",
+      id: this.nextId++,
+      method,
+      params,
+    });
+    return body.result as R;
+  }
+
+  /**
+   * Same as `call`, with the idempotency key of a mutating method call, which
+   * returns the first call result when retried with the same key and params.
+   */
+  async callIdempotent<R>(
+    method: string,
+    params: unknown,
+    idempotencyKey: string,
+  ): Promise<R> {
+    const body = await this.post("/api/rpc", {
+      jsonrpc: "2.0",
+      id: this.nextId++,
+      method,
+      params,
+      idempotency_key: idempotencyKey,
+    });
+    return body.result as R;
+  }
+
+  // Note: The error responses have an error http status,
+  //       and their `error` in the body (as the rpc error responses).
+  private async post(path: string, payload: unknown): Promise<any> {
+    const headers: Record<string, string> = {
+      "content-type": "application/json",
+    };
+    if (this.options.bearerToken) {
+      headers["authorization"] = `Bearer ${this.options.bearerToken}`;
+    }
+
+    const res = await fetch(this.baseUrl + path, {
+      method: "POST",
+      credentials: "include",
+      headers,
+      body: JSON.stringify(payload),
+    });
+    const body = await res.json();
+
+    if (body.error) {
+      const { code, message, data } = body.error;
+      const clientError = { message, detail: data?.detail } as ClientError;
+      throw new RpcError(code, clientError, data?.req_uuid);
+    }
+
+    return body;
+  }
+
+  addComment(params: AddCommentParams): Promise<Comment> {
+    return this.call("add_comment", params);
+  }
+
+  assignTask(params: AssignTaskParams): Promise<Task> {
+    return this.call("assign_task", params);
+  }
+
+  attachTag(params: AttachTagParams): Promise<null> {
+    return this.call("attach_tag", params);
+  }
+
+  changePassword(params: ChangePasswordParams): Promise<null> {
+    return this.call("change_password", params);
+  }
+
+  createTag(params: CreateTagParams): Promise<Tag> {
+    return this.call("create_tag", params);
+  }
+
+  createTask(params: CreateTaskParams): Promise<Task> {
+    return this.call("create_task", params);
+  }
+
+  createTasks(params: CreateTasksParams): Promise<ItemResult_Task[]> {
+    return this.call("create_tasks", params);
+  }
+
+  deleteAttachment(params: DeleteAttachmentParams): Promise<Attachment> {
+    return this.call("delete_attachment", params);
+  }
+
+  deleteComment(params: DeleteCommentParams): Promise<Comment> {
+    return this.call("delete_comment", params);
+  }
+
+  deleteTag(params: DeleteTagParams): Promise<Tag> {
+    return this.call("delete_tag", params);
+  }
+
+  deleteTask(params: DeleteTaskParams): Promise<Task> {
+    return this.call("delete_task", params);
+  }
+
+  deleteTasks(params: DeleteTasksParams = {}): Promise<ItemResult_int64[]> {
+    return this.call("delete_tasks", params);
+  }
+
+  detachTag(params: DetachTagParams): Promise<null> {
+    return this.call("detach_tag", params);
+  }
+
+  editComment(params: EditCommentParams): Promise<Comment> {
+    return this.call("edit_comment", params);
+  }
+
+  getMe(): Promise<User> {
+    return this.call("get_me");
+  }
+
+  getTask(params: GetTaskParams): Promise<TaskProjection> {
+    return this.call("get_task", params);
+  }
+
+  getTaskHistory(params: GetTaskHistoryParams): Promise<TaskRevision[]> {
+    return this.call("get_task_history", params);
+  }
+
+  listAttachments(params: ListAttachmentsParams): Promise<Attachment[]> {
+    return this.call("list_attachments", params);
+  }
+
+  listAuditEvents(params: ListAuditEventsParams = {}): Promise<AuditEvent[]> {
+    return this.call("list_audit_events", params);
+  }
+
+  listComments(params: ListCommentsParams): Promise<Comment[]> {
+    return this.call("list_comments", params);
+  }
+
+  listTags(params: ListTagsParams = {}): Promise<TagWithCount[]> {
+    return this.call("list_tags", params);
+  }
+
+  listTaskOccurrences(params: ListTaskOccurrencesParams): Promise<TaskOccurrence[]> {
+    return this.call("list_task_occurrences", params);
+  }
+
+  listTaskTree(params: ListTaskTreeParams = {}): Promise<TaskNode[]> {
+    return this.call("list_task_tree", params);
+  }
+
+  listTasks(params: ListTasksParams = {}): Promise<TaskProjection[]> {
+    return this.call("list_tasks", params);
+  }
+
+  moveTask(params: MoveTaskParams): Promise<Task> {
+    return this.call("move_task", params);
+  }
+
+  revertTask(params: RevertTaskParams): Promise<Task> {
+    return this.call("revert_task", params);
+  }
+
+  searchTasks(params: SearchTasksParams): Promise<TaskSearchHit[]> {
+    return this.call("search_tasks", params);
+  }
+
+  setTaskParent(params: SetTaskParentParams): Promise<Task> {
+    return this.call("set_task_parent", params);
+  }
+
+  unassignTask(params: UnassignTaskParams): Promise<Task> {
+    return this.call("unassign_task", params);
+  }
+
+  unwatchTask(params: UnwatchTaskParams): Promise<number[]> {
+    return this.call("unwatch_task", params);
+  }
+
+  updateMe(params: UpdateMeParams): Promise<User> {
+    return this.call("update_me", params);
+  }
+
+  updateTag(params: UpdateTagParams): Promise<Tag> {
+    return this.call("update_tag", params);
+  }
+
+  updateTask(params: UpdateTaskParams): Promise<Task> {
+    return this.call("update_task", params);
+  }
+
+  updateTaskOccurrence(params: UpdateTaskOccurrenceParams): Promise<Task> {
+    return this.call("update_task_occurrence", params);
+  }
+
+  updateTasks(params: UpdateTasksParams): Promise<ItemResult_Task[]> {
+    return this.call("update_tasks", params);
+  }
+
+  watchTask(params: WatchTaskParams): Promise<number[]> {
+    return this.call("watch_task", params);
+  }
+}
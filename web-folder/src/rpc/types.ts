@@ -0,0 +1,684 @@
+// Generated by `cargo run -p gen-ts`, do not edit.
+
+export interface Attachment {
+  content_type: string;
+  ctime: string;
+  file_name: string;
+  id: number;
+  size: number;
+  task_id: number;
+  uploader_id: number;
+}
+
+/**
+ * An immutable record of a model write, with the `{field: {old, new}}` diff
+ * of the changed fields.
+ */
+export interface AuditEvent {
+  actor_id: number;
+  ctime: string;
+  diff: unknown;
+  entity: string;
+  entity_id: number;
+  id: number;
+  op: AuditOp;
+}
+
+/** e.g., `{"entity": "task", "entity_id": 1000, "ctime": {"$gte": "2024-01-01T00:00:00Z"}}` */
+export interface AuditEventFilter {
+  actor_id?: OpValsInt64 | null;
+  ctime?: OpValsTime | null;
+  entity?: OpValsString | null;
+  entity_id?: OpValsInt64 | null;
+  op?: OpValsString | null;
+}
+
+export type AuditOp = "create" | "update" | "delete";
+
+export interface Comment {
+  author_id: number;
+  body: string;
+  ctime: string;
+  id: number;
+  mtime: string;
+  task_id: number;
+}
+
+export interface CommentForCreate {
+  body: string;
+  task_id: number;
+}
+
+export interface CommentForUpdate {
+  body: string;
+}
+
+/**
+ * The result of one item of a bulk rpc call,
+ * serialized as `{"ok": T}` or `{"error": ClientError}`.
+ *
+ * Note: The item error is the `ClientError` of the model error,
+ *       as for the rpc call error responses (never the internal error).
+ */
+export type ItemResult_Task = {
+  ok: Task;
+} | {
+  error: ClientError;
+};
+
+/**
+ * The result of one item of a bulk rpc call,
+ * serialized as `{"ok": T}` or `{"error": ClientError}`.
+ *
+ * Note: The item error is the `ClientError` of the model error,
+ *       as for the rpc call error responses (never the internal error).
+ */
+export type ItemResult_int64 = {
+  ok: number;
+} | {
+  error: ClientError;
+};
+
+export interface ListOptions {
+  limit?: number;
+  offset?: number;
+  order_bys?: string | string[];
+}
+
+export type OpValsBool = boolean | {
+  $eq?: boolean;
+  $not?: boolean;
+};
+
+export type OpValsInt64 = number | {
+  $eq?: number;
+  $gt?: number;
+  $gte?: number;
+  $in?: number[];
+  $lt?: number;
+  $lte?: number;
+  $not?: number;
+  $notIn?: number[];
+};
+
+export type OpValsString = string | {
+  $contains?: string;
+  $containsAll?: string[];
+  $containsAny?: string[];
+  $empty?: boolean;
+  $endsWith?: string;
+  $endsWithAny?: string[];
+  $eq?: string;
+  $gt?: string;
+  $gte?: string;
+  $in?: string[];
+  $lt?: string;
+  $lte?: string;
+  $not?: string;
+  $notContains?: string;
+  $notContainsAny?: string[];
+  $notEndsWith?: string;
+  $notEndsWithAny?: string[];
+  $notIn?: string[];
+  $notStartsWith?: string;
+  $notStartsWithAny?: string[];
+  $null?: boolean;
+  $startsWith?: string;
+  $startsWithAny?: string[];
+};
+
+export type OpValsTime = string | {
+  $eq?: string;
+  $gt?: string;
+  $gte?: string;
+  $in?: string[];
+  $lt?: string;
+  $lte?: string;
+  $not?: string;
+  $notIn?: string[];
+};
+
+export interface Tag {
+  id: number;
+  name: string;
+  owner_id: number;
+}
+
+export interface TagFilter {
+  id?: OpValsInt64 | null;
+  name?: OpValsString | null;
+}
+
+export interface TagForCreate {
+  name: string;
+}
+
+export interface TagForUpdate {
+  name?: string | null;
+}
+
+export interface TagWithCount {
+  id: number;
+  name: string;
+  owner_id: number;
+  /** Number of tasks with this tag. */
+  task_count: number;
+}
+
+/** Note: `Deserialize` for the task revision states (see `TaskRevision`). */
+export interface Task {
+  /** The user responsible for the task (see `TaskBmc::assign`). */
+  assignee_id?: number | null;
+  /** When true, the task is done when all of its subtasks are done. */
+  close_with_subtasks: boolean;
+  description?: string | null;
+  /** Derived from the status (i.e., `status == done`). */
+  done: boolean;
+  due_at?: string | null;
+  id: number;
+  owner_id: number;
+  parent_id?: number | null;
+  priority: TaskPriority;
+  /** The manual order key of the task, among the tasks of its owner. */
+  rank: string;
+  /**
+   * The RRULE of a recurring task (see `lib_utils::time::Recurrence`),
+   * e.g., `FREQ=WEEKLY;BYDAY=MO,FR`.
+   */
+  recurrence?: string | null;
+  status: TaskStatus;
+  title: string;
+}
+
+/** What happens to the subtasks of a deleted task. */
+export type TaskDeletePolicy = "cascade" | "reparent";
+
+/**
+ * The task fields filter, extended with the `tags` relation filter,
+ * and the `assigned_to_me` flag (resolved from the ctx).
+ * e.g., `{"done": false, "tags": {"$hasAll": [1000, 1001]}, "assigned_to_me": true}`
+ */
+export interface TaskFilter {
+  assigned_to_me?: boolean;
+  assignee_id?: OpValsInt64 | null;
+  description?: OpValsString | null;
+  done?: OpValsBool | null;
+  /** RFC3339 values, e.g., `{"$gte": "2024-01-01T00:00:00Z", "$lt": "2024-02-01T00:00:00Z"}` */
+  due_at?: OpValsTime | null;
+  id?: OpValsInt64 | null;
+  owner_id?: OpValsInt64 | null;
+  parent_id?: OpValsInt64 | null;
+  priority?: OpValsString | null;
+  status?: OpValsString | null;
+  tags?: TaskTagsFilter | null;
+  title?: OpValsString | null;
+}
+
+export interface TaskForCreate {
+  close_with_subtasks?: boolean | null;
+  description?: string | null;
+  due_at?: string | null;
+  parent_id?: number | null;
+  priority?: TaskPriority | null;
+  recurrence?: string | null;
+  status?: TaskStatus | null;
+  title: string;
+}
+
+/**
+ * Note: `done` is kept for compatibility, and is set as the `status`
+ *       (`true` as `done`, `false` as `todo`), unless a `status` is given.
+ *       The `parent_id` is changed with `TaskBmc::set_parent`.
+ */
+export interface TaskForUpdate {
+  close_with_subtasks?: boolean | null;
+  description?: string | null;
+  done?: boolean | null;
+  due_at?: string | null;
+  priority?: TaskPriority | null;
+  /**
+   * Note: A recurrence is ended with a `COUNT=1` rule, or by editing
+   *       `this` occurrence (see `TaskBmc::update_occurrence`).
+   */
+  recurrence?: string | null;
+  status?: TaskStatus | null;
+  title?: string | null;
+}
+
+/** Note: `Deserialize` for the task revision states (see `TaskRevision`). */
+export interface TaskNode {
+  /** The user responsible for the task (see `TaskBmc::assign`). */
+  assignee_id?: number | null;
+  children: TaskNode[];
+  /** When true, the task is done when all of its subtasks are done. */
+  close_with_subtasks: boolean;
+  description?: string | null;
+  /** Derived from the status (i.e., `status == done`). */
+  done: boolean;
+  due_at?: string | null;
+  id: number;
+  owner_id: number;
+  parent_id?: number | null;
+  priority: TaskPriority;
+  /** The manual order key of the task, among the tasks of its owner. */
+  rank: string;
+  /**
+   * The RRULE of a recurring task (see `lib_utils::time::Recurrence`),
+   * e.g., `FREQ=WEEKLY;BYDAY=MO,FR`.
+   */
+  recurrence?: string | null;
+  status: TaskStatus;
+  title: string;
+}
+
+/**
+ * An occurrence of a task for calendar views, being the task itself,
+ * or an upcoming occurrence of a recurring task (not created yet).
+ */
+export interface TaskOccurrence {
+  due_at: string;
+  /** True for the upcoming occurrences. */
+  expanded: boolean;
+  task_id: number;
+  title: string;
+}
+
+/** Which occurrences of a recurring task an edit applies to. */
+export type TaskOccurrenceScope = "this" | "all_future";
+
+export type TaskPriority = "low" | "medium" | "high" | "urgent";
+
+/** The `Task` fields of the projection. */
+export interface TaskProjection {
+  /** The user responsible for the task (see `TaskBmc::assign`). */
+  assignee_id?: number | null;
+  /** When true, the task is done when all of its subtasks are done. */
+  close_with_subtasks?: boolean;
+  description?: string | null;
+  /** Derived from the status (i.e., `status == done`). */
+  done?: boolean;
+  due_at?: string | null;
+  id?: number;
+  owner_id?: number;
+  parent_id?: number | null;
+  priority?: TaskPriority;
+  /** The manual order key of the task, among the tasks of its owner. */
+  rank?: string;
+  /**
+   * The RRULE of a recurring task (see `lib_utils::time::Recurrence`),
+   * e.g., `FREQ=WEEKLY;BYDAY=MO,FR`.
+   */
+  recurrence?: string | null;
+  status?: TaskStatus;
+  title?: string;
+}
+
+/** The task state after one of its writes, the revision 1 being the created state. */
+export interface TaskRevision {
+  actor_id: number;
+  ctime: string;
+  revision: number;
+  state: Task;
+  task_id: number;
+}
+
+/** Note: `Deserialize` for the task revision states (see `TaskRevision`). */
+export interface TaskSearchHit {
+  /** The user responsible for the task (see `TaskBmc::assign`). */
+  assignee_id?: number | null;
+  /** When true, the task is done when all of its subtasks are done. */
+  close_with_subtasks: boolean;
+  description?: string | null;
+  /** Derived from the status (i.e., `status == done`). */
+  done: boolean;
+  due_at?: string | null;
+  id: number;
+  owner_id: number;
+  parent_id?: number | null;
+  priority: TaskPriority;
+  /** The manual order key of the task, among the tasks of its owner. */
+  rank: string;
+  /**
+   * The RRULE of a recurring task (see `lib_utils::time::Recurrence`),
+   * e.g., `FREQ=WEEKLY;BYDAY=MO,FR`.
+   */
+  recurrence?: string | null;
+  /** The full-text search rank (`ts_rank`), higher for the title matches. */
+  score: number;
+  /**
+   * The title and description excerpt, with the matching words wrapped
+   * in `<mark>` tags.
+   * (Note: The text is not html escaped)
+   */
+  snippet: string;
+  status: TaskStatus;
+  title: string;
+}
+
+export type TaskStatus = "todo" | "in_progress" | "blocked" | "done";
+
+/**
+ * The `TaskFilter` on the task tag ids.
+ * e.g., `{"tags": {"$hasAny": [1000, 1001]}}` or `{"tags": {"$hasAll": [1000, 1001]}}`
+ *
+ * Note: `$hasAny` of no tag ids matches no task, and `$hasAll` of no tag ids
+ *       matches all the tasks.
+ */
+export interface TaskTagsFilter {
+  $hasAll?: number[] | null;
+  $hasAny?: number[] | null;
+}
+
+export interface User {
+  id: number;
+  username: string;
+}
+
+/**
+ * Note: The web token identifies the user by username, so a username change
+ *       requires a new login.
+ */
+export interface UserForUpdate {
+  username?: string | null;
+}
+
+export type ClientError = {
+  message: "PARSE_ERROR";
+} | {
+  message: "INVALID_REQUEST";
+} | {
+  message: "METHOD_NOT_FOUND";
+} | {
+  message: "INVALID_PARAMS";
+} | {
+  message: "LOGIN_FAIL";
+} | {
+  message: "NO_AUTH";
+} | {
+  message: "ADMIN_REQUIRED";
+} | {
+  message: "PWD_NOT_MATCHING";
+} | {
+  detail: {
+    entity: string;
+    id: number;
+  };
+  message: "ACCESS_DENIED";
+} | {
+  detail: {
+    entity: string;
+    id: number;
+  };
+  message: "ENTITY_NOT_FOUND";
+} | {
+  detail: {
+    entity: string;
+    field: string;
+  };
+  message: "UNIQUE_VIOLATION";
+} | {
+  detail: {
+    entity: string;
+    field: string;
+  };
+  message: "FOREIGN_KEY_VIOLATION";
+} | {
+  detail: {
+    constraint: string;
+    entity: string;
+  };
+  message: "CHECK_VIOLATION";
+} | {
+  message: "ATTACHMENT_INVALID";
+} | {
+  message: "ATTACHMENT_CONTENT_TYPE_NOT_ALLOWED";
+} | {
+  detail: {
+    max: number;
+  };
+  message: "ATTACHMENT_SIZE_OVER_MAX";
+} | {
+  detail: {
+    field: string;
+    message: string;
+    rule: string;
+  };
+  message: "VALIDATION_FAILED";
+} | {
+  detail: {
+    max: number;
+  };
+  message: "LIST_LIMIT_OVER_MAX";
+} | {
+  detail: {
+    max: number;
+  };
+  message: "BULK_SIZE_OVER_MAX";
+} | {
+  message: "BULK_FILTERS_EMPTY";
+} | {
+  detail: {
+    cause: string;
+    rule: string;
+  };
+  message: "RECURRENCE_INVALID";
+} | {
+  message: "IDEMPOTENCY_KEY_REUSED";
+} | {
+  message: "IDEMPOTENCY_KEY_IN_PROGRESS";
+} | {
+  detail: {
+    retry_after_sec: number;
+  };
+  message: "RATE_LIMITED";
+} | {
+  detail: {
+    from: TaskStatus;
+    id: number;
+    to: TaskStatus;
+  };
+  message: "TASK_STATUS_TRANSITION_INVALID";
+} | {
+  detail: {
+    id: number;
+    parent_id: number;
+  };
+  message: "TASK_PARENT_CYCLE";
+} | {
+  detail: {
+    max: number;
+  };
+  message: "TASK_TREE_DEPTH_OVER_MAX";
+} | {
+  detail: {
+    anchor_id: number;
+    id: number;
+  };
+  message: "TASK_RANK_SCOPE_MISMATCH";
+} | {
+  message: "TASK_RANK_INVALID";
+} | {
+  detail: {
+    id: number;
+    revision: number;
+  };
+  message: "TASK_REVISION_NOT_FOUND";
+} | {
+  detail: {
+    id: number;
+    user_id: number;
+  };
+  message: "TASK_NOT_VISIBLE";
+} | {
+  message: "SERVICE_ERROR";
+};
+
+export interface AddCommentParams {
+  data: CommentForCreate;
+}
+
+export interface AssignTaskParams {
+  assignee_id: number;
+  id: number;
+}
+
+export interface AttachTagParams {
+  tag_id: number;
+  task_id: number;
+}
+
+export interface ChangePasswordParams {
+  pwd_new: string;
+  pwd_old: string;
+}
+
+export interface CreateTagParams {
+  data: TagForCreate;
+}
+
+export interface CreateTaskParams {
+  data: TaskForCreate;
+}
+
+export interface CreateTasksParams {
+  data: TaskForCreate[];
+  per_item?: boolean;
+}
+
+export interface DeleteAttachmentParams {
+  id: number;
+}
+
+export interface DeleteCommentParams {
+  id: number;
+}
+
+export interface DeleteTagParams {
+  id: number;
+}
+
+export interface DeleteTaskParams {
+  id: number;
+  policy?: TaskDeletePolicy;
+}
+
+export interface DeleteTasksParams {
+  filters?: TaskFilter | TaskFilter[] | null;
+  ids?: number[] | null;
+  per_item?: boolean;
+}
+
+export interface DetachTagParams {
+  tag_id: number;
+  task_id: number;
+}
+
+export interface EditCommentParams {
+  data: CommentForUpdate;
+  id: number;
+}
+
+export interface GetTaskParams {
+  fields?: string[] | null;
+  id: number;
+}
+
+export interface GetTaskHistoryParams {
+  id: number;
+}
+
+export interface ListAttachmentsParams {
+  task_id: number;
+}
+
+export interface ListAuditEventsParams {
+  filters?: AuditEventFilter | AuditEventFilter[] | null;
+  list_options?: ListOptions | null;
+}
+
+export interface ListCommentsParams {
+  list_options?: ListOptions | null;
+  task_id: number;
+}
+
+export interface ListTagsParams {
+  filters?: TagFilter | TagFilter[] | null;
+  list_options?: ListOptions | null;
+}
+
+export interface ListTaskOccurrencesParams {
+  filters?: TaskFilter | TaskFilter[] | null;
+  from: string;
+  to: string;
+}
+
+export interface ListTaskTreeParams {
+  root_id?: number | null;
+}
+
+export interface ListTasksParams {
+  fields?: string[] | null;
+  filters?: TaskFilter | TaskFilter[] | null;
+  list_options?: ListOptions | null;
+}
+
+export interface MoveTaskParams {
+  after_id?: number | null;
+  before_id?: number | null;
+  id: number;
+}
+
+export interface RevertTaskParams {
+  id: number;
+  revision: number;
+}
+
+export interface SearchTasksParams {
+  filters?: TaskFilter | TaskFilter[] | null;
+  list_options?: ListOptions | null;
+  query: string;
+}
+
+export interface SetTaskParentParams {
+  id: number;
+  parent_id?: number | null;
+}
+
+export interface UnassignTaskParams {
+  id: number;
+}
+
+export interface UnwatchTaskParams {
+  id: number;
+}
+
+export interface UpdateMeParams {
+  data: UserForUpdate;
+}
+
+export interface UpdateTagParams {
+  data: TagForUpdate;
+  id: number;
+}
+
+export interface UpdateTaskParams {
+  data: TaskForUpdate;
+  id: number;
+}
+
+export interface UpdateTaskOccurrenceParams {
+  data: TaskForUpdate;
+  id: number;
+  scope: TaskOccurrenceScope;
+}
+
+export interface UpdateTasksParams {
+  data: TaskForUpdate;
+  filters?: TaskFilter | TaskFilter[] | null;
+  ids?: number[] | null;
+  per_item?: boolean;
+}
+
+export interface WatchTaskParams {
+  id: number;
+}